@@ -0,0 +1,400 @@
+//! Actix Web integration injecting Open Graph tags into HTML responses.
+//!
+//! This mirrors the `tower` middleware, so both render identical tags:
+//!
+//! - `Og` wraps a responder and attaches the metadata of the page to the
+//!   response, as `OgTags`.
+//! - `OgMiddleware` renders the attached properties and splices them into
+//!   the `<head>` of `text/html` responses using a `TagInjector`. Responses
+//!   without metadata, other content types and compressed bodies are passed
+//!   through untouched. A body that cannot be read is answered with a 500
+//!   Internal Server Error.
+//! - `OgDefaults` extracts an `OgMetadataBuilder` seeded with the site-wide
+//!   defaults from app data, either a `Data<SiteConfig>` or a shared
+//!   `Data<OgMetadataBuilder>`.
+//!
+//! This module is only available with the `actix` feature enabled.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use actix_web::{web, App, HttpResponse, Responder};
+//! use ogp::actix::{Og, OgDefaults, OgMiddleware};
+//! use ogp::config::SiteConfig;
+//!
+//! async fn home(mut defaults: OgDefaults) -> impl Responder {
+//!   defaults.set_title("Home");
+//!   let html = "<html><head><title>Home</title></head></html>";
+//!   Og::new(&defaults.get_metadata(), HttpResponse::Ok().body(html))
+//! }
+//!
+//! let config = SiteConfig {
+//!   site_name: Some("OGP".into()),
+//!   ..Default::default()
+//! };
+//!
+//! let app = App::new()
+//!   .app_data(web::Data::new(config))
+//!   .wrap(OgMiddleware::new().replace_existing(true))
+//!   .route("/", web::get().to(home));
+//! ```
+
+use std::{
+  future::{ready, Future, Ready},
+  ops::{Deref, DerefMut},
+  pin::Pin,
+  rc::Rc,
+};
+
+use actix_web::{
+  body::{self, BoxBody, EitherBody, MessageBody},
+  dev::{
+    forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform,
+  },
+  error::ErrorInternalServerError,
+  http::header::{HeaderMap, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE},
+  web::Data,
+  FromRequest, HttpRequest, HttpResponse, Responder,
+};
+
+use crate::{
+  config::SiteConfig,
+  flat::FlatProperties,
+  inject::{self, OgTags, TagInjector},
+  metadata::OgMetadataBuilder,
+};
+
+/// A responder attaching Open Graph properties to the response of the
+/// wrapped responder, for `OgMiddleware` to inject.
+#[derive(Debug, Clone)]
+pub struct Og<R> {
+  tags: OgTags,
+  responder: R,
+}
+
+impl<R> Og<R> {
+  /// Wraps `responder`, attaching the properties of `value`.
+  pub fn new<T: FlatProperties>(value: &T, responder: R) -> Self {
+    Og {
+      tags: OgTags::new(value),
+      responder,
+    }
+  }
+}
+
+impl<R: Responder> Responder for Og<R> {
+  type Body = R::Body;
+
+  fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+    let mut response = self.responder.respond_to(req);
+    response.extensions_mut().insert(self.tags);
+    response
+  }
+}
+
+/// Middleware injecting the `OgTags` or `OgMetadata` extension of responses
+/// into their HTML body.
+#[derive(Debug, Clone, Default)]
+pub struct OgMiddleware {
+  injector: TagInjector,
+}
+
+impl OgMiddleware {
+  /// Creates a middleware that keeps existing Open Graph tags.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Removes the existing Open Graph tags of responses before injecting.
+  pub fn replace_existing(mut self, replace: bool) -> Self {
+    self.injector = self.injector.replace_existing(replace);
+    self
+  }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for OgMiddleware
+where
+  S: Service<
+      ServiceRequest,
+      Response = ServiceResponse<B>,
+      Error = actix_web::Error,
+    > + 'static,
+  B: MessageBody + 'static,
+{
+  type Response = ServiceResponse<EitherBody<B>>;
+  type Error = actix_web::Error;
+  type Transform = OgMiddlewareService<S>;
+  type InitError = ();
+  type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+  fn new_transform(&self, service: S) -> Self::Future {
+    ready(Ok(OgMiddlewareService {
+      service: Rc::new(service),
+      injector: self.injector.clone(),
+    }))
+  }
+}
+
+/// The service created by `OgMiddleware`.
+pub struct OgMiddlewareService<S> {
+  service: Rc<S>,
+  injector: TagInjector,
+}
+
+impl<S, B> Service<ServiceRequest> for OgMiddlewareService<S>
+where
+  S: Service<
+      ServiceRequest,
+      Response = ServiceResponse<B>,
+      Error = actix_web::Error,
+    > + 'static,
+  B: MessageBody + 'static,
+{
+  type Response = ServiceResponse<EitherBody<B>>;
+  type Error = actix_web::Error;
+  type Future =
+    Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+  forward_ready!(service);
+
+  fn call(&self, req: ServiceRequest) -> Self::Future {
+    let service = Rc::clone(&self.service);
+    let injector = self.injector.clone();
+
+    Box::pin(async move {
+      let mut response = service.call(req).await?;
+
+      let properties = {
+        let mut extensions = response.response_mut().extensions_mut();
+        inject::take_properties(extensions.remove(), extensions.remove())
+      };
+      let properties = match properties {
+        Some(properties) if is_html(response.headers()) => properties,
+        _ => return Ok(response.map_into_left_body()),
+      };
+
+      let (req, response) = response.into_parts();
+      let (mut response, body) = response.into_parts();
+      let bytes = match body::to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+          let err = ErrorInternalServerError(err.into());
+          return Ok(ServiceResponse::from_err(err, req).map_into_right_body());
+        }
+      };
+
+      let injected = std::str::from_utf8(&bytes)
+        .ok()
+        .and_then(|html| injector.inject(html, &properties));
+      let body = match injected {
+        Some(html) => {
+          response.headers_mut().remove(CONTENT_LENGTH);
+          BoxBody::new(html)
+        }
+        None => BoxBody::new(bytes),
+      };
+
+      let response = response.set_body(body);
+      Ok(ServiceResponse::new(req, response).map_into_right_body())
+    })
+  }
+}
+
+/// Whether the response is an uncompressed HTML document.
+fn is_html(headers: &HeaderMap) -> bool {
+  let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+  inject::is_html(header(CONTENT_TYPE), header(CONTENT_ENCODING))
+}
+
+/// An `OgMetadataBuilder` seeded with the site-wide defaults from app data.
+///
+/// A shared `Data<OgMetadataBuilder>` is cloned; otherwise the builder is
+/// seeded from `Data<SiteConfig>`. Extraction fails with an internal server
+/// error if neither is registered.
+#[derive(Debug, Clone)]
+pub struct OgDefaults(pub OgMetadataBuilder);
+
+impl OgDefaults {
+  /// Unwraps the builder.
+  pub fn into_inner(self) -> OgMetadataBuilder {
+    self.0
+  }
+}
+
+impl Deref for OgDefaults {
+  type Target = OgMetadataBuilder;
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl DerefMut for OgDefaults {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.0
+  }
+}
+
+impl FromRequest for OgDefaults {
+  type Error = actix_web::Error;
+  type Future = Ready<Result<Self, Self::Error>>;
+
+  fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+    let builder = match req.app_data::<Data<OgMetadataBuilder>>() {
+      Some(builder) => Some(OgMetadataBuilder::clone(builder)),
+      None => req
+        .app_data::<Data<SiteConfig>>()
+        .map(|config| OgMetadataBuilder::from_site_config(config)),
+    };
+
+    ready(builder.map(OgDefaults).ok_or_else(|| {
+      ErrorInternalServerError(
+        "Neither SiteConfig nor OgMetadataBuilder is registered as app data",
+      )
+    }))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use actix_web::{
+    http::StatusCode,
+    test::{call_service, init_service, read_body, TestRequest},
+    web, App,
+  };
+
+  use std::task::{Context, Poll};
+
+  use actix_web::{body::BodySize, web::Bytes};
+
+  use super::*;
+  use crate::{
+    inject::tests::{metadata, INJECTED, PAGE},
+    metadata::OgMetadata,
+    object_type::{article::ArticleMetadata, retype::ObjectMetadata},
+  };
+
+  /// A response body failing to read.
+  struct BrokenBody;
+
+  impl MessageBody for BrokenBody {
+    type Error = std::io::Error;
+
+    fn size(&self) -> BodySize {
+      BodySize::Stream
+    }
+
+    fn poll_next(
+      self: Pin<&mut Self>,
+      _: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+      Poll::Ready(Some(Err(std::io::Error::other("connection reset"))))
+    }
+  }
+
+  fn html() -> HttpResponse {
+    HttpResponse::Ok().content_type("text/html").body(PAGE)
+  }
+
+  async fn get_page(
+    middleware: OgMiddleware,
+    uri: &str,
+  ) -> (StatusCode, String) {
+    let config = SiteConfig {
+      site_name: Some("OGP".into()),
+      ..Default::default()
+    };
+
+    let app = App::new()
+      .app_data(Data::new(config))
+      .wrap(middleware)
+      .route(
+        "/",
+        web::get().to(|| async { Og::new(&metadata(), html()) }),
+      )
+      .route(
+        "/article",
+        web::get().to(|| async {
+          let mut article = ArticleMetadata::from_root(metadata());
+          article.add_tag("rust");
+          Og::new(&article, html())
+        }),
+      )
+      .route(
+        "/defaults",
+        web::get().to(|mut defaults: OgDefaults| async move {
+          defaults.set_title("Defaults");
+          Og::new(&defaults.get_metadata(), html())
+        }),
+      )
+      .route("/plain", web::get().to(|| async { html() }))
+      .route(
+        "/broken",
+        web::get().to(|| async {
+          let response = HttpResponse::Ok()
+            .content_type("text/html")
+            .body(BrokenBody);
+          Og::new(&metadata(), response)
+        }),
+      )
+      .route(
+        "/text",
+        web::get().to(|| async { Og::new(&metadata(), PAGE) }),
+      );
+
+    let app = init_service(app).await;
+    let response =
+      call_service(&app, TestRequest::get().uri(uri).to_request()).await;
+    let status = response.status();
+    let body = read_body(response).await;
+    (status, String::from_utf8(body.to_vec()).unwrap())
+  }
+
+  #[actix_web::test]
+  async fn injects_metadata_into_html() {
+    let (status, html) = get_page(OgMiddleware::new(), "/").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(html, INJECTED);
+
+    let middleware = OgMiddleware::new().replace_existing(true);
+    let (_, html) = get_page(middleware.clone(), "/").await;
+    assert!(!html.contains("Template"));
+
+    let (_, html) = get_page(middleware, "/article").await;
+    assert!(html.contains(r#"<meta property="og:type" content="article" />"#));
+    assert!(html.contains(r#"<meta property="article:tag" content="rust" />"#));
+  }
+
+  #[actix_web::test]
+  async fn seeds_builder_from_app_data() {
+    let (_, html) = get_page(OgMiddleware::new(), "/defaults").await;
+    assert!(html.contains(r#"<meta property="og:title" content="Defaults" />"#));
+    assert!(html.contains(r#"<meta property="og:site_name" content="OGP" />"#));
+
+    let app = init_service(App::new().route(
+      "/",
+      web::get().to(|_: OgDefaults| async { HttpResponse::Ok().finish() }),
+    ))
+    .await;
+    let response = call_service(&app, TestRequest::get().to_request()).await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+  }
+
+  #[actix_web::test]
+  async fn passes_through_other_responses() {
+    let middleware = OgMiddleware::new().replace_existing(true);
+
+    let (_, html) = get_page(middleware.clone(), "/plain").await;
+    assert_eq!(html, PAGE);
+
+    let (_, text) = get_page(middleware, "/text").await;
+    assert_eq!(text, PAGE);
+  }
+
+  #[actix_web::test]
+  async fn fails_on_unreadable_bodies() {
+    let (status, text) = get_page(OgMiddleware::new(), "/broken").await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(text, "connection reset");
+  }
+}
@@ -0,0 +1,303 @@
+//! Canonical URL normalization for `og:url`.
+//!
+//! The canonical URL of an object should be the undecorated URL, without
+//! session variables, user identifying parameters or counters. The
+//! `UrlNormalizer` turns a decorated URL into its canonical form:
+//!
+//! - the scheme and host are lowercased
+//! - default ports (`:80` for http, `:443` for https) are removed
+//! - the fragment is removed
+//! - tracking parameters (`utm_*`, `fbclid`, `gclid`, session IDs, ...) are
+//!   stripped, including `;jsessionid=...` path parameters
+//! - the remaining query parameters are sorted
+//!
+//! Normalization is opt-in. Use it from the builder with
+//! `OgMetadataBuilder::set_normalized_url`, or as a lint that flags
+//! decorated canonical URLs with `UrlNormalizer::lint`.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ogp::canonical::UrlNormalizer;
+//!
+//! let normalizer = UrlNormalizer::default();
+//! let url = normalizer
+//!   .normalize_str("HTTPS://Example.com:443/post?utm_source=x&b=2&a=1#top")
+//!   .unwrap();
+//!
+//! assert_eq!(url.as_str(), "https://example.com/post?a=1&b=2");
+//! ```
+
+use serde::{Deserialize, Serialize};
+use url::{form_urlencoded, Url};
+
+use crate::{
+  error::Error, metadata::OgMetadata, utils::validate_http_url, Result,
+};
+
+/// Query parameters stripped by default. A trailing `*` matches any
+/// parameter starting with the given prefix.
+pub const DEFAULT_TRACKING_PARAMS: [&str; 16] = [
+  "utm_*",
+  "fbclid",
+  "gclid",
+  "dclid",
+  "gbraid",
+  "wbraid",
+  "msclkid",
+  "yclid",
+  "igshid",
+  "mc_cid",
+  "mc_eid",
+  "_ga",
+  "sessionid",
+  "session_id",
+  "sid",
+  "phpsessid",
+];
+
+/// Path parameters (e.g. `/page;jsessionid=abc`) stripped from the path.
+const SESSION_PATH_PARAMS: [&str; 2] = ["jsessionid", "phpsessid"];
+
+/// Normalizes URLs into their canonical form.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UrlNormalizer {
+  /// Query parameters to strip, matched case-insensitively.
+  /// A trailing `*` matches any parameter starting with the given prefix.
+  #[serde(default = "default_tracking_params")]
+  pub tracking_params: Vec<String>,
+}
+
+impl Default for UrlNormalizer {
+  fn default() -> Self {
+    UrlNormalizer {
+      tracking_params: default_tracking_params(),
+    }
+  }
+}
+
+fn default_tracking_params() -> Vec<String> {
+  DEFAULT_TRACKING_PARAMS
+    .iter()
+    .map(|p| p.to_string())
+    .collect()
+}
+
+impl UrlNormalizer {
+  /// Creates a normalizer that strips the `DEFAULT_TRACKING_PARAMS`.
+  pub fn new() -> Self {
+    UrlNormalizer::default()
+  }
+
+  /// Creates a normalizer that strips the given tracking parameters only.
+  pub fn with_tracking_params(params: &[&str]) -> Self {
+    UrlNormalizer {
+      tracking_params: params.iter().map(|p| p.to_string()).collect(),
+    }
+  }
+
+  /// Adds a tracking parameter to strip, e.g. `ref` or `mkt_*`.
+  pub fn add_tracking_param(&mut self, param: impl Into<String>) -> &mut Self {
+    self.tracking_params.push(param.into());
+    self
+  }
+
+  /// Checks whether the query parameter `name` is a tracking parameter.
+  pub fn is_tracking_param(&self, name: &str) -> bool {
+    let name = name.to_lowercase();
+    self.tracking_params.iter().any(|param| {
+      let param = param.to_lowercase();
+      match param.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == param,
+      }
+    })
+  }
+
+  /// Returns the canonical form of `url`.
+  pub fn normalize(&self, url: &Url) -> Url {
+    let mut canonical = url.clone();
+
+    // The `url` crate already lowercases scheme and host and drops default
+    // ports of special schemes. This takes care of all other schemes.
+    if canonical.port().is_some() && canonical.port() == default_port(url) {
+      canonical.set_port(None).ok();
+    }
+    canonical.set_fragment(None);
+
+    let path = strip_session_path_params(canonical.path());
+    canonical.set_path(&path);
+
+    // Filters and sorts the raw parameters, so that they keep their
+    // encoding, e.g. `%20` and valueless parameters such as `?flag`.
+    let mut params: Vec<&str> = url
+      .query()
+      .unwrap_or_default()
+      .split('&')
+      .filter(|param| !param.is_empty())
+      .filter(|param| {
+        let name = form_urlencoded::parse(param.as_bytes()).next();
+        !name.is_some_and(|(name, _)| self.is_tracking_param(&name))
+      })
+      .collect();
+    params.sort();
+
+    if params.is_empty() {
+      canonical.set_query(None);
+    } else {
+      canonical.set_query(Some(&params.join("&")));
+    }
+
+    canonical
+  }
+
+  /// Parses an HTTP(S) URL and returns its canonical form.
+  pub fn normalize_str(&self, url: &str) -> Result<Url> {
+    validate_http_url(url.trim()).map(|url| self.normalize(&url))
+  }
+
+  /// Checks whether `url` is already in its canonical form.
+  pub fn is_canonical(&self, url: &Url) -> bool {
+    self.normalize(url) == *url
+  }
+
+  /// Flags a decorated canonical URL (`og:url`).
+  ///
+  /// Returns an `Error::DecoratedCanonicalUrl` naming the canonical form
+  /// if the URL is decorated, e.g. with tracking parameters.
+  pub fn lint(&self, metadata: &OgMetadata) -> Result<()> {
+    let Some(url) = metadata.url.as_ref() else {
+      return Ok(());
+    };
+
+    let parsed = validate_http_url(url)?;
+    let canonical = self.normalize(&parsed);
+    if canonical == parsed {
+      return Ok(());
+    }
+
+    Err(Error::DecoratedCanonicalUrl(url.clone(), canonical.into()))
+  }
+}
+
+fn default_port(url: &Url) -> Option<u16> {
+  match url.scheme() {
+    "http" | "ws" => Some(80),
+    "https" | "wss" => Some(443),
+    "ftp" => Some(21),
+    _ => None,
+  }
+}
+
+fn strip_session_path_params(path: &str) -> String {
+  path
+    .split('/')
+    .map(|segment| {
+      let mut parts = segment.split(';');
+      let name = parts.next().unwrap_or_default();
+      let params: Vec<&str> = parts
+        .filter(|param| {
+          let key = param.split('=').next().unwrap_or_default();
+          !SESSION_PATH_PARAMS.contains(&key.to_lowercase().as_str())
+        })
+        .collect();
+
+      std::iter::once(name)
+        .chain(params)
+        .collect::<Vec<&str>>()
+        .join(";")
+    })
+    .collect::<Vec<String>>()
+    .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn normalize(url: &str) -> String {
+    UrlNormalizer::default().normalize_str(url).unwrap().into()
+  }
+
+  #[test]
+  fn lowercases_scheme_and_host() {
+    assert_eq!(
+      normalize("HTTPS://WWW.Example.COM/Path"),
+      "https://www.example.com/Path"
+    );
+  }
+
+  #[test]
+  fn removes_default_port_and_fragment() {
+    assert_eq!(
+      normalize("https://example.com:443/a#b"),
+      "https://example.com/a"
+    );
+    assert_eq!(normalize("http://example.com:80/a"), "http://example.com/a");
+    assert_eq!(
+      normalize("http://example.com:8080/a"),
+      "http://example.com:8080/a"
+    );
+  }
+
+  #[test]
+  fn strips_tracking_params() {
+    assert_eq!(
+      normalize(
+        "https://example.com/a?utm_source=x&UTM_Medium=y&fbclid=1&gclid=2&id=7"
+      ),
+      "https://example.com/a?id=7"
+    );
+    assert_eq!(
+      normalize("https://example.com/a;jsessionid=ABC?sid=1"),
+      "https://example.com/a"
+    );
+  }
+
+  #[test]
+  fn sorts_query_params() {
+    assert_eq!(
+      normalize("https://example.com/?b=2&a=1&a=0"),
+      "https://example.com/?a=0&a=1&b=2"
+    );
+  }
+
+  #[test]
+  fn keeps_query_encoding() {
+    assert_eq!(
+      normalize("https://example.com/?q=a%20b&flag&utm_source=x"),
+      "https://example.com/?flag&q=a%20b"
+    );
+
+    let normalizer = UrlNormalizer::default();
+    let url = Url::parse("https://example.com/?flag&q=a%20b").unwrap();
+    assert!(normalizer.is_canonical(&url));
+  }
+
+  #[test]
+  fn custom_tracking_params() {
+    let mut normalizer = UrlNormalizer::with_tracking_params(&["ref"]);
+    normalizer.add_tracking_param("mkt_*");
+    let url = normalizer
+      .normalize_str("https://example.com/?ref=a&mkt_tok=b&utm_source=c")
+      .unwrap();
+    assert_eq!(url.as_str(), "https://example.com/?utm_source=c");
+  }
+
+  #[test]
+  fn lint_flags_decorated_canonical_url() {
+    let normalizer = UrlNormalizer::default();
+    let mut metadata = OgMetadata {
+      url: Some("https://example.com/post".into()),
+      ..Default::default()
+    };
+    assert!(normalizer.lint(&metadata).is_ok());
+
+    metadata.url = Some("https://example.com/post?utm_source=x".into());
+    assert!(matches!(
+      normalizer.lint(&metadata),
+      Err(Error::DecoratedCanonicalUrl(_, canonical))
+        if canonical == "https://example.com/post"
+    ));
+  }
+}
@@ -0,0 +1,385 @@
+//! Site-wide defaults for Open Graph metadata.
+//!
+//! A `SiteConfig` holds the properties that are the same on every page of a
+//! site, such as the site name, the default locale or a fallback image. It
+//! can be loaded from JSON, TOML (feature `toml`) or YAML (feature `yaml`)
+//! and seeds an `OgMetadataBuilder`, so call sites only set page-specific
+//! properties.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ogp::config::SiteConfig;
+//! use ogp::metadata::OgMetadataBuilder;
+//!
+//! let config = SiteConfig::from_json_str(r#"{
+//!   "site_name": "OGP",
+//!   "locale": "en_US",
+//!   "locale_alternates": ["de_DE"],
+//!   "images": [{ "url": "https://example.com/logo.png", "alt": "Logo" }],
+//!   "twitter_site": "@ogp",
+//!   "canonical_host": "https://example.com"
+//! }"#).unwrap();
+//!
+//! let mut builder = OgMetadataBuilder::from_site_config(&config);
+//! builder
+//!   .set_title("Release notes")
+//!   .set_url(config.canonical_url("/news?utm_source=feed").unwrap());
+//!
+//! let metadata = builder.get_metadata();
+//! assert_eq!(metadata.site_name.as_deref(), Some("OGP"));
+//! assert_eq!(metadata.url.as_deref(), Some("https://example.com/news"));
+//! ```
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+  canonical::{UrlNormalizer, DEFAULT_TRACKING_PARAMS},
+  error::Error,
+  metadata::{Image, OgMetadataBuilder},
+  utils::{escape_html, resolve_http_url, validate_http_url, validate_locale},
+  validator::Validatable,
+  Result,
+};
+
+/// Site-wide defaults shared by all pages of a site.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct SiteConfig {
+  /// The name of the overall site, e.g. "IMDb".
+  pub site_name: Option<String>,
+
+  /// The default locale, e.g. `en_US`.
+  pub locale: Option<String>,
+
+  /// Other locales the site is available in.
+  pub locale_alternates: Vec<String>,
+
+  /// Fallback images for pages without an image of their own.
+  pub images: Vec<Image>,
+
+  /// The Twitter (X) handle of the site, e.g. `@imdb`.
+  pub twitter_site: Option<String>,
+
+  /// The Facebook app ID (`fb:app_id`).
+  #[serde(alias = "fb:app_id")]
+  pub fb_app_id: Option<String>,
+
+  /// The scheme and host canonical URLs are rewritten to,
+  /// e.g. `https://www.example.com`.
+  pub canonical_host: Option<Url>,
+
+  /// Query parameters stripped from canonical URLs. Defaults to
+  /// `DEFAULT_TRACKING_PARAMS`. A trailing `*` matches any parameter
+  /// starting with the given prefix.
+  pub tracking_params: Vec<String>,
+
+  /// Query parameters stripped in addition to `tracking_params`.
+  pub extra_tracking_params: Vec<String>,
+}
+
+impl Default for SiteConfig {
+  fn default() -> Self {
+    SiteConfig {
+      site_name: None,
+      locale: None,
+      locale_alternates: Vec::new(),
+      images: Vec::new(),
+      twitter_site: None,
+      fb_app_id: None,
+      canonical_host: None,
+      tracking_params: DEFAULT_TRACKING_PARAMS
+        .iter()
+        .map(|p| p.to_string())
+        .collect(),
+      extra_tracking_params: Vec::new(),
+    }
+  }
+}
+
+impl SiteConfig {
+  /// Loads and validates a configuration from a JSON string.
+  pub fn from_json_str(s: &str) -> Result<Self> {
+    let config: SiteConfig = serde_json::from_str(s)
+      .map_err(|err| Error::InvalidConfig(err.to_string()))?;
+    config.validate()?;
+    Ok(config)
+  }
+
+  /// Loads and validates a configuration from a TOML string.
+  #[cfg(feature = "toml")]
+  pub fn from_toml_str(s: &str) -> Result<Self> {
+    let config: SiteConfig =
+      toml::from_str(s).map_err(|err| Error::InvalidConfig(err.to_string()))?;
+    config.validate()?;
+    Ok(config)
+  }
+
+  /// Loads and validates a configuration from a YAML string.
+  #[cfg(feature = "yaml")]
+  pub fn from_yaml_str(s: &str) -> Result<Self> {
+    let config: SiteConfig = serde_yaml::from_str(s)
+      .map_err(|err| Error::InvalidConfig(err.to_string()))?;
+    config.validate()?;
+    Ok(config)
+  }
+
+  /// Loads and validates a configuration file. The format is chosen by
+  /// the file extension: `json`, `toml` or `yaml`/`yml`.
+  pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+    let path = path.as_ref();
+    let contents = std::fs::read_to_string(path)?;
+    let ext = path
+      .extension()
+      .and_then(|ext| ext.to_str())
+      .unwrap_or_default()
+      .to_lowercase();
+
+    match ext.as_str() {
+      "json" => SiteConfig::from_json_str(&contents),
+      #[cfg(feature = "toml")]
+      "toml" => SiteConfig::from_toml_str(&contents),
+      #[cfg(feature = "yaml")]
+      "yaml" | "yml" => SiteConfig::from_yaml_str(&contents),
+      _ => Err(Error::InvalidConfig(format!(
+        "Unsupported configuration format '{}'",
+        path.display()
+      ))),
+    }
+  }
+
+  /// The normalizer for canonical URLs, built from `tracking_params` and
+  /// `extra_tracking_params`.
+  pub fn normalizer(&self) -> UrlNormalizer {
+    let mut normalizer = UrlNormalizer {
+      tracking_params: self.tracking_params.clone(),
+    };
+    for param in &self.extra_tracking_params {
+      normalizer.add_tracking_param(param);
+    }
+    normalizer
+  }
+
+  /// Returns the canonical URL of a page, given its path or URL.
+  ///
+  /// Relative paths are resolved against `canonical_host`, and absolute
+  /// URLs are moved to its scheme and host. The result is normalized.
+  pub fn canonical_url(&self, url: &str) -> Result<String> {
+    let host = self.canonical_host.as_ref();
+    let mut url = resolve_http_url(url, host)?;
+
+    if let Some(host) = host {
+      // Both URLs are http(s) URLs, so setting these cannot fail.
+      let _ = url.set_scheme(host.scheme());
+      let _ = url.set_host(host.host_str());
+      let _ = url.set_port(host.port());
+    }
+
+    Ok(self.normalizer().normalize(&url).into())
+  }
+
+  /// The Twitter handle with a leading `@`.
+  pub fn twitter_handle(&self) -> Option<String> {
+    let handle = self.twitter_site.as_deref()?.trim().trim_start_matches('@');
+    (!handle.is_empty()).then(|| format!("@{}", handle))
+  }
+
+  /// Meta tags for the site-wide properties that are not part of
+  /// `OgMetadata`, i.e. `twitter:site` and `fb:app_id`.
+  pub fn to_html(&self) -> Vec<String> {
+    let mut tags = Vec::new();
+
+    if let Some(handle) = self.twitter_handle() {
+      tags.push(format!(
+        r#"<meta name="twitter:site" content="{}" />"#,
+        escape_html(&handle)
+      ));
+    }
+
+    if let Some(app_id) = self.fb_app_id.as_deref() {
+      tags.push(format!(
+        r#"<meta property="fb:app_id" content="{}" />"#,
+        escape_html(app_id)
+      ));
+    }
+
+    tags
+  }
+}
+
+impl Validatable for SiteConfig {
+  fn validate(&self) -> Result<()> {
+    if let Some(locale) = self.locale.as_deref() {
+      validate_locale(locale)?;
+    }
+
+    for locale in &self.locale_alternates {
+      validate_locale(locale)?;
+    }
+
+    for image in &self.images {
+      image.validate()?;
+    }
+
+    if let Some(host) = self.canonical_host.as_ref() {
+      validate_http_url(host.as_str())?;
+    }
+
+    if let Some(app_id) = self.fb_app_id.as_deref() {
+      if app_id.is_empty() || !app_id.chars().all(|c| c.is_ascii_digit()) {
+        return Err(Error::InvalidConfig(format!(
+          "fb:app_id '{}' must be numeric",
+          app_id
+        )));
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl OgMetadataBuilder {
+  /// Creates a builder seeded with the site-wide defaults of `config`.
+  ///
+  /// The canonical host becomes the base URL for relative media URLs.
+  /// `config` is expected to be valid, e.g. loaded with
+  /// `SiteConfig::from_path`.
+  pub fn from_site_config(config: &SiteConfig) -> Self {
+    let mut builder = OgMetadataBuilder::new();
+
+    if let Some(site_name) = config.site_name.as_ref() {
+      builder.set_site_name(site_name);
+    }
+
+    if let Some(locale) = config.locale.as_ref() {
+      builder.set_locale(locale);
+    }
+
+    for locale in &config.locale_alternates {
+      builder.add_locale_alternate(locale);
+    }
+
+    for image in &config.images {
+      builder.add_image(image.clone());
+    }
+
+    if let Some(host) = config.canonical_host.as_ref() {
+      builder.set_base_url(host.as_str());
+    }
+
+    builder
+  }
+}
+
+impl From<&SiteConfig> for OgMetadataBuilder {
+  fn from(config: &SiteConfig) -> Self {
+    OgMetadataBuilder::from_site_config(config)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const JSON: &str = r#"{
+    "site_name": "OGP",
+    "locale": "en_US",
+    "locale_alternates": ["de_DE"],
+    "images": [{ "url": "https://example.com/logo.png", "width": 1200, "height": 630 }],
+    "twitter_site": "ogp",
+    "fb:app_id": "1234567890",
+    "canonical_host": "https://www.example.com",
+    "extra_tracking_params": ["ref"]
+  }"#;
+
+  #[test]
+  fn loads_json_config() {
+    let config = SiteConfig::from_json_str(JSON).unwrap();
+    assert_eq!(config.site_name.as_deref(), Some("OGP"));
+    assert_eq!(config.images[0].width, Some(1200));
+    assert_eq!(config.tracking_params.len(), DEFAULT_TRACKING_PARAMS.len());
+    assert_eq!(
+      config.to_html(),
+      vec![
+        r#"<meta name="twitter:site" content="@ogp" />"#,
+        r#"<meta property="fb:app_id" content="1234567890" />"#,
+      ]
+    );
+  }
+
+  #[test]
+  fn rejects_invalid_config() {
+    let err = SiteConfig::from_json_str(r#"{ "site": "OGP" }"#).unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(_)));
+
+    let err = SiteConfig::from_json_str(r#"{ "locale": "english" }"#);
+    assert!(err.is_err());
+
+    let err = SiteConfig::from_json_str(r#"{ "fb_app_id": "app" }"#);
+    assert!(matches!(err, Err(Error::InvalidConfig(_))));
+  }
+
+  #[test]
+  fn rewrites_canonical_urls() {
+    let config = SiteConfig::from_json_str(JSON).unwrap();
+    let url = |url| config.canonical_url(url).unwrap();
+
+    assert_eq!(
+      url("/news?ref=home&b=1"),
+      "https://www.example.com/news?b=1"
+    );
+    assert_eq!(
+      url("http://example.com/news#top"),
+      "https://www.example.com/news"
+    );
+  }
+
+  #[test]
+  fn seeds_builder() {
+    let config = SiteConfig::from_json_str(JSON).unwrap();
+    let mut builder = OgMetadataBuilder::from(&config);
+    builder.add_image_url("/cover.png").unwrap();
+
+    let metadata = builder.get_metadata();
+    assert_eq!(metadata.locale.as_deref(), Some("en_US"));
+    assert_eq!(metadata.locale_alternate, Some(vec!["de_DE".into()]));
+    assert_eq!(metadata.images.len(), 2);
+    assert_eq!(
+      metadata.images[1].url.as_ref().map(Url::as_str),
+      Some("https://www.example.com/cover.png")
+    );
+  }
+
+  #[cfg(feature = "toml")]
+  #[test]
+  fn loads_toml_config() {
+    let config = SiteConfig::from_toml_str(
+      r#"
+      site_name = "OGP"
+      locale = "en_US"
+      tracking_params = ["utm_*"]
+
+      [[images]]
+      url = "https://example.com/logo.png"
+      "#,
+    )
+    .unwrap();
+    assert_eq!(config.site_name.as_deref(), Some("OGP"));
+    assert_eq!(config.tracking_params, vec!["utm_*"]);
+    assert_eq!(config.images.len(), 1);
+  }
+
+  #[cfg(feature = "yaml")]
+  #[test]
+  fn loads_yaml_config() {
+    let config = SiteConfig::from_yaml_str(
+      "site_name: OGP\nlocale_alternates:\n  - de_DE\n  - fr_FR\n",
+    )
+    .unwrap();
+    assert_eq!(config.site_name.as_deref(), Some("OGP"));
+    assert_eq!(config.locale_alternates, vec!["de_DE", "fr_FR"]);
+  }
+}
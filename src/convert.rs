@@ -1,48 +1,95 @@
-use serde::Serialize;
-use serde_json::{json, to_string_pretty, Value};
+use std::fmt;
 
-use crate::object_type::website::Website;
+use crate::{flat::FlatProperties, utils::escape_html};
 
-macro_rules! meta_tag {
-  ($name:expr, $content:expr) => {
-    format!(r#"<meta property="{}" content="{}" />"#, $name, $content)
-  };
+/// Renders a single meta tag, escaping the property and its content.
+pub(crate) fn meta_tag(property: &str, content: &str) -> String {
+  format!(
+    r#"<meta property="{}" content="{}" />"#,
+    escape_html(property),
+    escape_html(content)
+  )
 }
 
-pub trait ToHTML
-where
-  Self: Serialize,
-{
+/// Rendering of metadata as HTML meta tags.
+///
+/// Tags are rendered from the flat representation (see `FlatProperties`),
+/// so they are in the order the protocol expects, e.g. `og:image:width`
+/// follows its `og:image`, and their contents are HTML-escaped.
+pub trait ToHTML: FlatProperties {
+  /// The meta tags of `self`, one per property.
   fn to_html(&self) -> Vec<String> {
-    let serialized = json!(self);
-
-    fn process_json(value: &Value, prefix: &str) -> Vec<String> {
-      match value {
-        Value::Object(obj) => {
-          let mut result = Vec::new();
-          for (key, value) in obj {
-            let new_prefix = if prefix.is_empty() {
-              key.to_string()
-            } else {
-              format!("{}:{}", prefix, key)
-            };
-            result.extend(process_json(value, &new_prefix));
-          }
-          result
-        }
-        Value::Array(arr) => {
-          let mut result = Vec::new();
-          for (index, value) in arr.iter().enumerate() {
-            let new_prefix = format!("{}{}", prefix, "");
-            result.extend(process_json(value, &new_prefix));
-          }
-          result
-        }
-        Value::String(s) => vec![meta_tag!(prefix, s)],
-        _ => vec![], // Ignore other types for simplicity
-      }
-    }
-
-    process_json(&serialized, "")
+    self
+      .to_properties()
+      .iter()
+      .map(|(property, content)| meta_tag(property, content))
+      .collect()
+  }
+
+  /// A `Display` adapter rendering the meta tags of `self`, one per line.
+  ///
+  /// The output is already escaped, so template engines must not escape
+  /// it again. With the `askama` feature, `MetaTags` is marked as safe.
+  fn meta_tags(&self) -> MetaTags<'_, Self> {
+    MetaTags(self)
+  }
+}
+
+/// Displays the meta tags of a `ToHTML` value, one per line.
+pub struct MetaTags<'a, T: ?Sized>(pub &'a T);
+
+impl<T: ToHTML> fmt::Display for MetaTags<'_, T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.0.to_html().join("\n"))
+  }
+}
+
+#[cfg(feature = "askama")]
+impl<T: ToHTML> askama::filters::HtmlSafe for MetaTags<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr;
+
+  use super::*;
+  use crate::{
+    metadata::{Image, OgMetadata},
+    object_type::{book::Book, retype::ObjectMetadata},
+  };
+
+  #[test]
+  fn renders_escaped_tags_in_order() {
+    let metadata = OgMetadata {
+      title: Some(r#"Tom & "Jerry""#.into()),
+      images: vec![Image {
+        width: Some(1200),
+        height: Some(630),
+        ..Image::from_str("https://example.com/a.png").unwrap()
+      }],
+      ..Default::default()
+    };
+
+    assert_eq!(
+      metadata.to_html(),
+      [
+        r#"<meta property="og:type" content="website" />"#,
+        r#"<meta property="og:title" content="Tom &amp; &quot;Jerry&quot;" />"#,
+        r#"<meta property="og:image" content="https://example.com/a.png" />"#,
+        r#"<meta property="og:image:width" content="1200" />"#,
+        r#"<meta property="og:image:height" content="630" />"#,
+      ]
+    );
+  }
+
+  #[test]
+  fn displays_meta_tags() {
+    let mut book = Book::from_root(OgMetadata::default());
+    book.isbn = Some("978-3-16-148410-0".into());
+
+    assert_eq!(
+      book.meta_tags().to_string(),
+      "<meta property=\"og:type\" content=\"book\" />\n\
+       <meta property=\"book:isbn\" content=\"978-3-16-148410-0\" />"
+    );
   }
 }
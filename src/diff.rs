@@ -0,0 +1,441 @@
+//! Structured diffs between two versions of Open Graph metadata.
+//!
+//! A `MetadataDiff` lists the properties that were added, removed or
+//! changed, each by its full property path:
+//!
+//! - `og:title` for a property
+//! - `og:image[https://example.com/a.png]` for an element of an array,
+//!   matched by its URL rather than its position
+//! - `og:image[https://example.com/a.png]:width` for a structured property
+//! - `article:tag[rust]` for an element of an array of strings
+//!
+//! Reordering arrays is therefore not a change, and inserting an image
+//! does not report every following image as changed.
+//!
+//! Diffs work on anything that serializes to Open Graph properties, i.e.
+//! `OgMetadata` and the type-specific structs such as `ArticleMetadata`,
+//! as well as on `Extraction`s of HTML documents.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ogp::diff::MetadataDiff;
+//! use ogp::metadata::OgMetadata;
+//!
+//! let old = OgMetadata {
+//!   title: Some("Release notes".into()),
+//!   ..Default::default()
+//! };
+//! let new = OgMetadata {
+//!   title: Some("Release notes 1.0".into()),
+//!   description: Some("What's new".into()),
+//!   ..Default::default()
+//! };
+//!
+//! let diff = MetadataDiff::between(&old, &new);
+//! assert_eq!(
+//!   diff.to_text(),
+//!   "+ og:description: \"What's new\"\n\
+//!    ~ og:title: \"Release notes\" -> \"Release notes 1.0\"\n\
+//!    1 added, 0 removed, 1 changed"
+//! );
+//! ```
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{extract::Extraction, metadata::OgMetadata};
+
+/// How a property changed.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+  Added,
+  Removed,
+  Changed,
+}
+
+/// A single added, removed or changed property.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PropertyChange {
+  /// The full property path, e.g. `og:image[https://example.com/a.png]:width`.
+  pub path: String,
+
+  pub kind: ChangeKind,
+
+  /// The previous value. `None` for added properties.
+  pub old: Option<Value>,
+
+  /// The new value. `None` for removed properties.
+  pub new: Option<Value>,
+}
+
+/// The differences between two versions of metadata.
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct MetadataDiff {
+  pub changes: Vec<PropertyChange>,
+}
+
+impl MetadataDiff {
+  /// Compares two values of `OgMetadata` or a type-specific struct.
+  pub fn between<T: Serialize>(old: &T, new: &T) -> Self {
+    let old = serde_json::to_value(old).unwrap_or_default();
+    let new = serde_json::to_value(new).unwrap_or_default();
+    Self::between_values(&old, &new)
+  }
+
+  /// Compares the properties of two HTML documents, including
+  /// type-specific properties such as `article:author`.
+  pub fn between_extractions(old: &Extraction, new: &Extraction) -> Self {
+    Self::between_values(&extraction_value(old), &extraction_value(new))
+  }
+
+  /// Compares two JSON representations of metadata.
+  pub fn between_values(old: &Value, new: &Value) -> Self {
+    let mut diff = Self::default();
+    diff.compare(String::new(), old, new);
+    diff
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.changes.is_empty()
+  }
+
+  pub fn added(&self) -> impl Iterator<Item = &PropertyChange> {
+    self.of_kind(ChangeKind::Added)
+  }
+
+  pub fn removed(&self) -> impl Iterator<Item = &PropertyChange> {
+    self.of_kind(ChangeKind::Removed)
+  }
+
+  pub fn changed(&self) -> impl Iterator<Item = &PropertyChange> {
+    self.of_kind(ChangeKind::Changed)
+  }
+
+  fn of_kind(&self, kind: ChangeKind) -> impl Iterator<Item = &PropertyChange> {
+    self
+      .changes
+      .iter()
+      .filter(move |change| change.kind == kind)
+  }
+
+  /// A line per change, prefixed with `+`, `-` or `~`, followed by totals.
+  pub fn to_text(&self) -> String {
+    let render = |value: &Option<Value>| {
+      value.as_ref().map(Value::to_string).unwrap_or_default()
+    };
+
+    let mut lines: Vec<String> = self
+      .changes
+      .iter()
+      .map(|change| match change.kind {
+        ChangeKind::Added => {
+          format!("+ {}: {}", change.path, render(&change.new))
+        }
+        ChangeKind::Removed => {
+          format!("- {}: {}", change.path, render(&change.old))
+        }
+        ChangeKind::Changed => format!(
+          "~ {}: {} -> {}",
+          change.path,
+          render(&change.old),
+          render(&change.new)
+        ),
+      })
+      .collect();
+
+    lines.push(format!(
+      "{} added, {} removed, {} changed",
+      self.added().count(),
+      self.removed().count(),
+      self.changed().count()
+    ));
+    lines.join("\n")
+  }
+
+  /// The changes as pretty-printed JSON.
+  pub fn to_json(&self) -> String {
+    serde_json::to_string_pretty(self).unwrap_or_default()
+  }
+
+  fn push(
+    &mut self,
+    path: String,
+    kind: ChangeKind,
+    old: Option<&Value>,
+    new: Option<&Value>,
+  ) {
+    self.changes.push(PropertyChange {
+      path,
+      kind,
+      old: old.map(without_nulls),
+      new: new.map(without_nulls),
+    });
+  }
+
+  fn compare(&mut self, path: String, old: &Value, new: &Value) {
+    match (old, new) {
+      (Value::Object(old), Value::Object(new)) => {
+        self.compare_objects(&path, old, new)
+      }
+      (Value::Array(old), Value::Array(new)) => {
+        self.compare_arrays(&path, old, new)
+      }
+      // Unset and empty arrays are equivalent.
+      (Value::Null, Value::Array(new)) => self.compare_arrays(&path, &[], new),
+      (Value::Array(old), Value::Null) => self.compare_arrays(&path, old, &[]),
+      // A property declared once and one declared several times.
+      (Value::Array(old), new) => {
+        self.compare_arrays(&path, old, std::slice::from_ref(new))
+      }
+      (old, Value::Array(new)) => {
+        self.compare_arrays(&path, std::slice::from_ref(old), new)
+      }
+      (Value::Null, Value::Null) => {}
+      (Value::Null, new) => self.push(path, ChangeKind::Added, None, Some(new)),
+      (old, Value::Null) => {
+        self.push(path, ChangeKind::Removed, Some(old), None)
+      }
+      (old, new) if old != new => {
+        self.push(path, ChangeKind::Changed, Some(old), Some(new))
+      }
+      _ => {}
+    }
+  }
+
+  fn compare_objects(
+    &mut self,
+    path: &str,
+    old: &Map<String, Value>,
+    new: &Map<String, Value>,
+  ) {
+    let keys = old
+      .keys()
+      .chain(new.keys().filter(|key| !old.contains_key(*key)));
+
+    for key in keys {
+      let child = if path.is_empty() {
+        key.clone()
+      } else {
+        format!("{}:{}", path, key)
+      };
+      self.compare(
+        child,
+        old.get(key).unwrap_or(&Value::Null),
+        new.get(key).unwrap_or(&Value::Null),
+      );
+    }
+  }
+
+  fn compare_arrays(&mut self, path: &str, old: &[Value], new: &[Value]) {
+    let old = keyed(old);
+    let mut new = keyed(new);
+
+    for (key, old_value) in old {
+      let path = format!("{}[{}]", path, key);
+      match new.iter().position(|(new_key, _)| *new_key == key) {
+        Some(index) => {
+          let (_, new_value) = new.remove(index);
+          self.compare(path, old_value, new_value);
+        }
+        None if !old_value.is_null() => {
+          self.push(path, ChangeKind::Removed, Some(old_value), None)
+        }
+        None => {}
+      }
+    }
+
+    for (key, new_value) in new {
+      if !new_value.is_null() {
+        let path = format!("{}[{}]", path, key);
+        self.push(path, ChangeKind::Added, None, Some(new_value));
+      }
+    }
+  }
+}
+
+impl OgMetadata {
+  /// Lists the properties that differ in `other`.
+  pub fn diff(&self, other: &OgMetadata) -> MetadataDiff {
+    MetadataDiff::between(self, other)
+  }
+}
+
+/// Drops unset fields of added or removed objects, e.g. of an `Image`.
+fn without_nulls(value: &Value) -> Value {
+  match value {
+    Value::Object(object) => Value::Object(
+      object
+        .iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(key, value)| (key.clone(), without_nulls(value)))
+        .collect(),
+    ),
+    value => value.clone(),
+  }
+}
+
+/// Keys array elements by their URL, their value for strings and numbers,
+/// or their position as a last resort.
+fn keyed(values: &[Value]) -> Vec<(String, &Value)> {
+  values
+    .iter()
+    .enumerate()
+    .map(|(index, value)| {
+      let key = match value {
+        Value::Object(object) => match object.get("url") {
+          Some(Value::String(url)) => url.clone(),
+          _ => index.to_string(),
+        },
+        Value::String(value) => value.clone(),
+        Value::Number(value) => value.to_string(),
+        _ => index.to_string(),
+      };
+      (key, value)
+    })
+    .collect()
+}
+
+/// The metadata of an extraction, extended by its type-specific properties.
+fn extraction_value(extraction: &Extraction) -> Value {
+  let mut value =
+    serde_json::to_value(&extraction.metadata).unwrap_or_default();
+  let Value::Object(object) = &mut value else {
+    return value;
+  };
+
+  let type_specific = extraction
+    .properties
+    .iter()
+    .filter(|(property, _)| !property.starts_with("og:"));
+  for (property, content) in type_specific {
+    let content = Value::String(content.clone());
+    match object.get_mut(property) {
+      Some(Value::Array(values)) => values.push(content),
+      Some(existing) => {
+        *existing = Value::Array(vec![existing.take(), content])
+      }
+      None => {
+        object.insert(property.clone(), content);
+      }
+    }
+  }
+  value
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::extract::{Extractor, ParseMode};
+  use crate::metadata::Image;
+  use crate::object_type::article::ArticleMetadata;
+  use crate::object_type::retype::ObjectMetadata;
+
+  fn image(url: &str, width: Option<u32>) -> Image {
+    Image {
+      url: Some(url.parse().unwrap()),
+      width,
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn reports_nothing_for_equal_metadata() {
+    let metadata = OgMetadata {
+      title: Some("The Rock".into()),
+      images: vec![image("https://example.com/a.png", None)],
+      ..Default::default()
+    };
+    assert!(metadata.diff(&metadata.clone()).is_empty());
+    assert!(OgMetadata::default()
+      .diff(&OgMetadata::default())
+      .is_empty());
+  }
+
+  #[test]
+  fn matches_array_elements_by_url() {
+    let old = OgMetadata {
+      images: vec![
+        image("https://example.com/a.png", Some(600)),
+        image("https://example.com/b.png", None),
+      ],
+      ..Default::default()
+    };
+    let new = OgMetadata {
+      images: vec![
+        image("https://example.com/c.png", None),
+        image("https://example.com/a.png", Some(1200)),
+      ],
+      ..Default::default()
+    };
+
+    let diff = old.diff(&new);
+    let paths: Vec<(&str, ChangeKind)> = diff
+      .changes
+      .iter()
+      .map(|change| (change.path.as_str(), change.kind))
+      .collect();
+    assert_eq!(
+      paths,
+      [
+        (
+          "og:image[https://example.com/a.png]:width",
+          ChangeKind::Changed
+        ),
+        ("og:image[https://example.com/b.png]", ChangeKind::Removed),
+        ("og:image[https://example.com/c.png]", ChangeKind::Added),
+      ]
+    );
+    assert_eq!(diff.changes[0].old, Some(600.into()));
+    assert_eq!(diff.changes[0].new, Some(1200.into()));
+    assert_eq!(
+      diff.changes[2].new,
+      Some(serde_json::json!({ "url": "https://example.com/c.png" }))
+    );
+  }
+
+  #[test]
+  fn diffs_type_specific_structs() {
+    let mut old = ArticleMetadata::from_root(OgMetadata::default());
+    old.set_section("Technology").add_tags(&["rust", "web"]);
+    let mut new = old.clone();
+    new.set_section("Science").add_tags(&["wasm"]);
+
+    let diff = MetadataDiff::between(&old, &new);
+    assert_eq!(
+      diff.to_text(),
+      "~ article:section: \"Technology\" -> \"Science\"\n\
+       + article:tag[wasm]: \"wasm\"\n\
+       1 added, 0 removed, 1 changed"
+    );
+  }
+
+  #[test]
+  fn diffs_extractions() {
+    let extract =
+      |html: &str| Extractor::new(ParseMode::Strict).extract(html).unwrap();
+    let old = extract(
+      r#"<meta property="og:title" content="A">
+      <meta property="article:tag" content="rust">
+      <meta property="article:tag" content="web">"#,
+    );
+    let new = extract(
+      r#"<meta property="og:title" content="A">
+      <meta property="article:author" content="https://example.com/me">
+      <meta property="article:tag" content="web">"#,
+    );
+
+    let diff = MetadataDiff::between_extractions(&old, &new);
+    let paths: Vec<&str> = diff
+      .changes
+      .iter()
+      .map(|change| change.path.as_str())
+      .collect();
+    assert_eq!(paths, ["article:tag[rust]", "article:author"]);
+
+    let json: Value = serde_json::from_str(&diff.to_json()).unwrap();
+    assert_eq!(json["changes"][0]["kind"], "removed");
+    assert_eq!(json["changes"][1]["new"], "https://example.com/me");
+  }
+}
@@ -32,8 +32,25 @@
 //! - `IO`: Represents an I/O error, transparently wrapping a `std::io::Error`.
 //! - `InvalidHttpUrlScheme`: Represents an error for an invalid URL scheme, which must be one of 'http' or 'https'.
 //! - `InvalidHttpsUrlScheme`: Represents an error for an invalid URL scheme in the context of HTTPS. The URL must start with 'https://'.
-//! - `InvalidImageExtension`: Represents an error for an invalid image extension. Only 'png', 'jpg', 'jpeg', 'gif', or 'webp' images are allowed.
+//! - `InvalidImageExtension`: Represents an error for an invalid image extension. Only 'png', 'jpg', 'jpeg', 'gif', 'webp' or 'avif' images are allowed.
+//! - `InvalidMediaType`: Represents an error for a MIME type that is not in the format `type/subtype`.
+//! - `UnsupportedMediaType`: Represents an error for a MIME type that is not allowed for the kind of media.
+//! - `MediaTypeMismatch`: Represents an error for a declared MIME type that does not match the URL's file extension.
 //! - `IncompleteImageDimensions`: Represents an error for incomplete visual object dimensions. Only one dimension value is found, but values for both 'width' and 'height' are required.
+//! - `InvalidObjectType`: Represents an error for an unknown Open Graph object type, suggesting the closest valid type.
+//! - `InvalidDeterminer`: Represents an error for an unknown determiner, suggesting the closest valid determiner.
+//! - `LossyRetype`: Represents an error for a conversion between object types that would drop type-specific properties.
+//! - `InvalidConfig`: Represents an error for a site configuration that cannot be loaded or is invalid.
+//! - `InvalidFrontMatter`: Represents an error for Markdown front matter that cannot be parsed or contains invalid values.
+//! - `InvalidMarkup`: Represents an error for HTML markup that does not follow the Open Graph protocol.
+//! - `InvalidPropertyValue`: Represents an error for a property whose content cannot be parsed into its value type.
+//! - `ImageProbe`: Represents an error for an image whose headers could not be probed.
+//! - `SecureUrlMismatch`: Represents an error for a secure URL that does not point at the same resource as the regular URL.
+//! - `DecoratedCanonicalUrl`: Represents an error for a canonical URL that contains tracking parameters, fragments or other decorations.
+//! - `FetchFailed`: Represents an error for a page that could not be fetched, e.g. because of a network error or a timeout.
+//! - `HttpStatus`: Represents an error for a page that was fetched with an unsuccessful HTTP status.
+//! - `TooManyRedirects`: Represents an error for a page whose redirects exceed the redirect limit.
+//! - `UnsupportedContentType`: Represents an error for a fetched resource that is not an HTML document.
 //!
 //! # Dependencies
 //!
@@ -67,13 +84,39 @@ pub enum Error {
   #[error("Invalid URL scheme '{0}'. URL must start with 'https://'")]
   InvalidHttpsUrlScheme(String),
 
-  /// Represents an error for an invalid image extension. Only 'png', 'jpg', 'jpeg', 'gif', or 'webp' images are allowed.
+  /// Represents an error for an image whose headers could not be probed,
+  /// e.g. because the format is not supported or the file is truncated.
+  #[error("Failed to probe image: {0}")]
+  ImageProbe(String),
+
+  /// Represents an error for a secure URL that does not point at the same
+  /// resource (same path) as the regular URL it is an alternate for.
+  #[error("Secure URL '{1}' does not point at the same resource as '{0}'")]
+  SecureUrlMismatch(String, String),
+
+  /// Represents an error for an invalid image extension. Only 'png', 'jpg', 'jpeg', 'gif', 'webp' or 'avif' images are allowed.
   #[error(
     "Invalid image extension '{0}'. \
-        Only png|jpg|jpeg|gif|webp images are allowed."
+        Only png|jpg|jpeg|gif|webp|avif images are allowed."
   )]
   InvalidImageExtension(String),
 
+  /// Represents an error for a MIME type that is not in the format `type/subtype`.
+  #[error("Invalid media type '{0}'. Must be in format `type/subtype`")]
+  InvalidMediaType(String),
+
+  /// Represents an error for a MIME type that is not allowed for the kind
+  /// of media, e.g. `video/mp4` for an image.
+  #[error("Media type '{0}' is not allowed. Must be one of {1}")]
+  UnsupportedMediaType(String, String),
+
+  /// Represents an error for a declared MIME type that does not match
+  /// the file extension of the media URL.
+  #[error(
+    "Media type '{0}' does not match the file extension '{1}' of the URL"
+  )]
+  MediaTypeMismatch(String, String),
+
   /// Represents an error for incomplete visual object dimensions.
   /// Only found value for the specified dimension, but values for both 'width' and 'height' are required.
   #[error(
@@ -128,4 +171,64 @@ pub enum Error {
     Must be in format `language_TERRITORY` (e.g. 'en_US')"
   )]
   EmptyLocale,
+
+  /// Represents an error for an unknown Open Graph object type (`og:type`).
+  /// Carries the invalid value and the closest valid type.
+  #[error("Invalid object type '{0}'. Did you mean '{1}'?")]
+  InvalidObjectType(String, String),
+
+  /// Represents an error for an unknown determiner (`og:determiner`).
+  /// Carries the invalid value and the closest valid determiner.
+  #[error("Invalid determiner '{0}'. Did you mean '{1}'?")]
+  InvalidDeterminer(String, String),
+
+  /// Represents an error for a conversion between object types that would
+  /// drop type-specific properties. Carries the source type, the target
+  /// type and the dropped properties.
+  #[error("Converting '{0}' to '{1}' would lose {2}")]
+  LossyRetype(String, String, String),
+
+  /// Represents an error for a site configuration that cannot be parsed,
+  /// has an unsupported format or contains invalid values.
+  #[error("Invalid site configuration: {0}")]
+  InvalidConfig(String),
+
+  /// Represents an error for Markdown front matter that cannot be parsed,
+  /// e.g. a missing closing fence or an invalid date.
+  #[error("Invalid front matter: {0}")]
+  InvalidFrontMatter(String),
+
+  /// Represents an error for HTML markup that does not follow
+  /// the Open Graph protocol, e.g. `name="og:title"` instead of `property`.
+  #[error("Invalid markup: {0}")]
+  InvalidMarkup(String),
+
+  /// Represents an error for a property whose content cannot be parsed,
+  /// e.g. a date that is not in ISO 8601 format.
+  #[error("Invalid value '{1}' for property '{0}'")]
+  InvalidPropertyValue(String, String),
+
+  /// Represents an error for a canonical URL (`og:url`) that is decorated
+  /// with tracking parameters, session IDs, fragments or default ports.
+  #[error("Canonical URL '{0}' is decorated. Use '{1}' instead")]
+  DecoratedCanonicalUrl(String, String),
+
+  /// Represents an error for a page that could not be fetched, e.g.
+  /// because of a network error or a timeout. Carries the URL and reason.
+  #[error("Failed to fetch '{0}': {1}")]
+  FetchFailed(String, String),
+
+  /// Represents an error for a page that was fetched with an unsuccessful
+  /// HTTP status, e.g. 404.
+  #[error("Fetching '{0}' failed with HTTP status {1}")]
+  HttpStatus(String, u16),
+
+  /// Represents an error for a page whose redirects exceed the limit.
+  #[error("Fetching '{0}' exceeded the limit of {1} redirects")]
+  TooManyRedirects(String, usize),
+
+  /// Represents an error for a fetched resource that is not an HTML
+  /// document, e.g. an image.
+  #[error("Unsupported content type '{1}' of '{0}'")]
+  UnsupportedContentType(String, String),
 }
@@ -0,0 +1,321 @@
+//! Minimal HTML tag scanner used by the extractor.
+//!
+//! This is not a full HTML parser. It recognizes start tags and their
+//! attributes, skips comments, doctypes and end tags, and captures the raw
+//! text of elements such as `<title>` and `<script>`. That is all Open Graph
+//! extraction needs, and it keeps the crate free of a DOM dependency.
+
+use std::ops::Range;
+
+/// Elements whose content is raw text and must not be scanned for tags.
+const RAW_TEXT_ELEMENTS: [&str; 4] = ["title", "script", "style", "textarea"];
+
+/// A start tag found in an HTML document.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Tag {
+  /// The lowercased tag name, e.g. `meta`.
+  pub name: String,
+
+  /// Attributes in document order. Names are lowercased, values decoded.
+  pub attrs: Vec<(String, String)>,
+
+  /// The raw text content for raw text elements like `<title>`.
+  pub text: Option<String>,
+
+  /// The byte range of the start tag in the document.
+  pub span: Range<usize>,
+}
+
+impl Tag {
+  /// Returns the value of the first attribute with the given (lowercase) name.
+  pub fn attr(&self, name: &str) -> Option<&str> {
+    self
+      .attrs
+      .iter()
+      .find(|(key, _)| key == name)
+      .map(|(_, value)| value.as_str())
+  }
+}
+
+/// An end tag found in an HTML document, e.g. `</head>`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct EndTag {
+  /// The lowercased tag name, e.g. `head`.
+  pub name: String,
+
+  /// The byte range of the end tag in the document.
+  pub span: Range<usize>,
+}
+
+/// The tags of an HTML document, see `scan_document`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Document {
+  /// Start tags in document order.
+  pub tags: Vec<Tag>,
+
+  /// End tags in document order, except those closing raw text elements.
+  /// End tags within comments and raw text are not reported.
+  pub end_tags: Vec<EndTag>,
+}
+
+/// Scans an HTML document and returns all start tags in document order.
+pub(crate) fn scan(html: &str) -> Vec<Tag> {
+  scan_document(html).tags
+}
+
+/// Scans an HTML document for its start and end tags.
+pub(crate) fn scan_document(html: &str) -> Document {
+  let lower = html.to_ascii_lowercase();
+  let bytes = html.as_bytes();
+  let mut document = Document::default();
+  let mut pos = 0;
+
+  while let Some(offset) = html[pos..].find('<') {
+    let start = pos + offset;
+    let rest = &html[start..];
+
+    if rest.starts_with("<!--") {
+      match rest.find("-->") {
+        Some(end) => pos = start + end + 3,
+        None => break,
+      }
+      continue;
+    }
+
+    let name_len = rest[1..]
+      .bytes()
+      .take_while(|b| b.is_ascii_alphanumeric())
+      .count();
+
+    if name_len == 0 {
+      // End tags, doctypes, processing instructions or a stray '<'.
+      let Some(end) = rest.find('>') else {
+        break;
+      };
+      pos = start + end + 1;
+
+      let end_name_len = rest.strip_prefix("</").map_or(0, |name| {
+        name
+          .bytes()
+          .take_while(|b| b.is_ascii_alphanumeric())
+          .count()
+      });
+      if end_name_len > 0 {
+        document.end_tags.push(EndTag {
+          name: lower[start + 2..start + 2 + end_name_len].to_string(),
+          span: start..pos,
+        });
+      }
+      continue;
+    }
+
+    let name = lower[start + 1..start + 1 + name_len].to_string();
+    let (attrs, end) = scan_attrs(html, bytes, start + 1 + name_len);
+    pos = end;
+
+    let mut tag = Tag {
+      name,
+      attrs,
+      text: None,
+      span: start..end,
+    };
+
+    if RAW_TEXT_ELEMENTS.contains(&tag.name.as_str()) {
+      let closing = format!("</{}", tag.name);
+      let close = lower[pos..]
+        .find(&closing)
+        .map(|i| pos + i)
+        .unwrap_or(html.len());
+      tag.text = Some(html[pos..close].to_string());
+      pos = match html[close..].find('>') {
+        Some(i) => close + i + 1,
+        None => html.len(),
+      };
+    }
+
+    document.tags.push(tag);
+  }
+
+  document
+}
+
+/// Scans the attributes of a start tag beginning at `pos`.
+///
+/// Returns the attributes and the position right after the closing `>`.
+fn scan_attrs(
+  html: &str,
+  bytes: &[u8],
+  mut pos: usize,
+) -> (Vec<(String, String)>, usize) {
+  let mut attrs = Vec::new();
+
+  loop {
+    while pos < bytes.len()
+      && (bytes[pos].is_ascii_whitespace() || bytes[pos] == b'/')
+    {
+      pos += 1;
+    }
+
+    if pos >= bytes.len() {
+      return (attrs, pos);
+    }
+
+    if bytes[pos] == b'>' {
+      return (attrs, pos + 1);
+    }
+
+    let name_start = pos;
+    while pos < bytes.len()
+      && !bytes[pos].is_ascii_whitespace()
+      && !matches!(bytes[pos], b'=' | b'>' | b'/')
+    {
+      pos += 1;
+    }
+    let name = html[name_start..pos].to_ascii_lowercase();
+
+    while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+      pos += 1;
+    }
+
+    if pos >= bytes.len() || bytes[pos] != b'=' {
+      attrs.push((name, String::new()));
+      continue;
+    }
+
+    pos += 1;
+    while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+      pos += 1;
+    }
+
+    let value = match bytes.get(pos) {
+      Some(quote @ (b'"' | b'\'')) => {
+        let value_start = pos + 1;
+        let value_end = html[value_start..]
+          .find(*quote as char)
+          .map(|i| value_start + i)
+          .unwrap_or(html.len());
+        pos = (value_end + 1).min(html.len());
+        &html[value_start..value_end]
+      }
+      _ => {
+        let value_start = pos;
+        while pos < bytes.len()
+          && !bytes[pos].is_ascii_whitespace()
+          && bytes[pos] != b'>'
+        {
+          pos += 1;
+        }
+        &html[value_start..pos]
+      }
+    };
+
+    attrs.push((name, decode_entities(value)));
+  }
+}
+
+/// Decodes the character references commonly found in attribute values.
+pub(crate) fn decode_entities(value: &str) -> String {
+  if !value.contains('&') {
+    return value.to_string();
+  }
+
+  let mut decoded = String::with_capacity(value.len());
+  let mut rest = value;
+
+  while let Some(amp) = rest.find('&') {
+    decoded.push_str(&rest[..amp]);
+    rest = &rest[amp..];
+
+    let reference = rest
+      .find(';')
+      .filter(|end| *end <= 10)
+      .and_then(|end| decode_reference(&rest[1..end]).map(|c| (c, end)));
+
+    match reference {
+      Some((c, end)) => {
+        decoded.push(c);
+        rest = &rest[end + 1..];
+      }
+      None => {
+        decoded.push('&');
+        rest = &rest[1..];
+      }
+    }
+  }
+
+  decoded.push_str(rest);
+  decoded
+}
+
+fn decode_reference(reference: &str) -> Option<char> {
+  match reference {
+    "amp" => Some('&'),
+    "lt" => Some('<'),
+    "gt" => Some('>'),
+    "quot" => Some('"'),
+    "apos" => Some('\''),
+    "nbsp" => Some('\u{a0}'),
+    _ => {
+      let code = reference.strip_prefix('#')?;
+      let code = match code.strip_prefix(['x', 'X']) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => code.parse().ok()?,
+      };
+      char::from_u32(code)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn scans_meta_attributes() {
+    let tags = scan(r#"<META Property="og:title" content='A &amp; B'>"#);
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].name, "meta");
+    assert_eq!(tags[0].attr("property"), Some("og:title"));
+    assert_eq!(tags[0].attr("content"), Some("A & B"));
+    assert_eq!(tags[0].span, 0..46);
+  }
+
+  #[test]
+  fn scans_unquoted_and_boolean_attributes() {
+    let tags = scan("<meta property=og:url content=https://a.com/x async/>");
+    assert_eq!(tags[0].attr("content"), Some("https://a.com/x"));
+    assert_eq!(tags[0].attr("async"), Some(""));
+  }
+
+  #[test]
+  fn skips_comments_and_raw_text() {
+    let html = r#"<!-- <meta property="og:title" content="no"> -->
+      <script>var s = '<meta property="og:title">';</script>
+      <title>Hello</title>"#;
+    let tags = scan(html);
+    assert_eq!(tags.len(), 2);
+    assert_eq!(tags[1].text.as_deref(), Some("Hello"));
+  }
+
+  #[test]
+  fn scans_end_tags_outside_comments_and_raw_text() {
+    let html = "<head><script>var s = '</head>';</script><!-- </head> -->\
+      </HEAD><body>";
+    let document = scan_document(html);
+    let names: Vec<_> = document
+      .end_tags
+      .iter()
+      .map(|tag| tag.name.as_str())
+      .collect();
+    assert_eq!(names, ["head"]);
+    assert_eq!(&html[document.end_tags[0].span.clone()], "</HEAD>");
+  }
+
+  #[test]
+  fn decodes_numeric_references() {
+    assert_eq!(
+      decode_entities("&#39;a&#x27; &unknown; &"),
+      "'a' &unknown; &"
+    );
+  }
+}
@@ -0,0 +1,857 @@
+//! Extraction of Open Graph metadata from HTML documents.
+//!
+//! The extractor scans the `<meta>` tags of a document and assembles the
+//! Open Graph properties it finds into an [`OgMetadata`] value.
+//!
+//! # Modes
+//!
+//! - `ParseMode::Strict`: Only spec-conforming markup is accepted. Any markup
+//!   mistake results in an `Error::InvalidMarkup`.
+//! - `ParseMode::Lenient`: Common real-world mistakes are tolerated and
+//!   normalized. Each fix-up is recorded as a [`Warning`].
+//!
+//! The lenient mode accepts:
+//!
+//! - `name="og:title"` instead of `property="og:title"`
+//! - uppercase property names, e.g. `OG:TITLE`
+//! - `value="..."` instead of `content="..."`
+//! - duplicated single-value properties (the first occurrence wins)
+//! - whitespace-padded URLs
+//!
+//! In both modes, an `og:type` not defined by the protocol, e.g. `product`,
+//! is read as `website`. The lenient mode records a warning for it.
+//!
+//! # Relative URLs
+//!
+//! Relative (`/img/cover.png`) and protocol-relative
+//! (`//cdn.example.com/x.jpg`) media URLs are resolved against the document's
+//! `<base href>`. Without one, the page URL set with `Extractor::set_page_url`
+//! is used, and finally the document's own `og:url`.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ogp::extract::{Extractor, ParseMode};
+//!
+//! let html = r#"<meta name="og:title" content="Hello">"#;
+//!
+//! let extraction = Extractor::new(ParseMode::Lenient).extract(html).unwrap();
+//! assert_eq!(extraction.metadata.title.as_deref(), Some("Hello"));
+//! assert_eq!(extraction.warnings.len(), 1);
+//!
+//! assert!(Extractor::new(ParseMode::Strict).extract(html).is_err());
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::{
+  error::Error,
+  metadata::{Audio, Image, MediaType, OgMetadata, Video},
+  object_type::{Determiner, ObjectType},
+  utils::{resolve_http_url, validate_http_url},
+  Result,
+};
+use url::Url;
+
+pub(crate) mod html;
+
+/// Property namespaces recognized as Open Graph properties.
+const PROPERTY_NAMESPACES: [&str; 7] = [
+  "og:", "fb:", "article:", "book:", "profile:", "music:", "video:",
+];
+
+/// Properties that may only appear once per document.
+const SINGLE_VALUE_PROPERTIES: [&str; 7] = [
+  "og:type",
+  "og:url",
+  "og:title",
+  "og:description",
+  "og:site_name",
+  "og:determiner",
+  "og:locale",
+];
+
+/// Properties whose content is a URL.
+const URL_PROPERTIES: [&str; 10] = [
+  "og:url",
+  "og:image",
+  "og:image:url",
+  "og:image:secure_url",
+  "og:video",
+  "og:video:url",
+  "og:video:secure_url",
+  "og:audio",
+  "og:audio:url",
+  "og:audio:secure_url",
+];
+
+/// How tolerant the extractor is towards markup mistakes.
+#[derive(Serialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParseMode {
+  /// Reject any markup that does not follow the Open Graph protocol.
+  #[default]
+  Strict,
+
+  /// Accept and normalize common markup mistakes, recording a warning
+  /// for each fix-up.
+  Lenient,
+}
+
+/// The kind of fix-up applied by the lenient parse mode.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+  /// The property was declared with `name` instead of `property`.
+  NameAttribute,
+
+  /// The property name was not lowercase.
+  UppercaseProperty,
+
+  /// The value was declared with `value` instead of `content`.
+  ValueAttribute,
+
+  /// A single-value property was declared more than once.
+  DuplicateProperty,
+
+  /// A URL value had leading or trailing whitespace.
+  PaddedUrl,
+
+  /// A structured property (e.g. `og:image:width`) appeared before
+  /// the property it belongs to.
+  OrphanedProperty,
+
+  /// The value could not be parsed and was dropped.
+  InvalidValue,
+
+  /// The `og:type` is not defined by the protocol and was read as
+  /// `website`.
+  UnknownObjectType,
+}
+
+/// A markup mistake that was tolerated by the lenient parse mode.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Warning {
+  /// The kind of fix-up that was applied.
+  pub kind: WarningKind,
+
+  /// The (normalized) property the warning refers to.
+  pub property: String,
+
+  /// A human readable description of the fix-up.
+  pub message: String,
+}
+
+impl fmt::Display for Warning {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.message)
+  }
+}
+
+/// Whether `content` resolves to `url`, e.g. an `og:image:url` repeating
+/// the preceding `og:image`.
+fn is_same_url(
+  url: Option<&Url>,
+  content: &str,
+  base_url: Option<&Url>,
+) -> bool {
+  url.is_some_and(|url| {
+    resolve_http_url(content, base_url).is_ok_and(|content| content == *url)
+  })
+}
+
+/// The result of extracting Open Graph metadata from an HTML document.
+#[derive(Serialize, Default, Debug, Clone)]
+pub struct Extraction {
+  /// The metadata assembled from the document's Open Graph properties.
+  pub metadata: OgMetadata,
+
+  /// All recognized properties as normalized `(property, content)` pairs,
+  /// in document order. Includes type-specific properties such as
+  /// `article:author` which are not part of `OgMetadata`.
+  pub properties: Vec<(String, String)>,
+
+  /// Fix-ups applied in lenient mode. Always empty in strict mode.
+  pub warnings: Vec<Warning>,
+
+  /// The base URL relative URLs have been resolved against, if any.
+  pub base_url: Option<Url>,
+}
+
+/// Extracts Open Graph metadata from HTML documents.
+#[derive(Default, Debug, Clone)]
+pub struct Extractor {
+  mode: ParseMode,
+  page_url: Option<Url>,
+}
+
+impl Extractor {
+  pub fn new(mode: ParseMode) -> Self {
+    Extractor {
+      mode,
+      ..Default::default()
+    }
+  }
+
+  pub fn set_mode(&mut self, mode: ParseMode) -> &mut Self {
+    self.mode = mode;
+    self
+  }
+
+  pub fn mode(&self) -> ParseMode {
+    self.mode
+  }
+
+  /// Sets the URL the document was retrieved from. Used to resolve relative
+  /// URLs when the document has no `<base href>`.
+  pub fn set_page_url(&mut self, page_url: Url) -> &mut Self {
+    self.page_url = Some(page_url);
+    self
+  }
+
+  /// Extracts the Open Graph metadata contained in `html`.
+  pub fn extract(&self, html: &str) -> Result<Extraction> {
+    let tags = html::scan(html);
+    let base_url = self.base_url(&tags);
+
+    let mut assembler = Assembler {
+      mode: self.mode,
+      base_url: base_url.clone(),
+      extraction: Extraction {
+        base_url,
+        ..Default::default()
+      },
+    };
+
+    for tag in &tags {
+      if tag.name == "meta" {
+        assembler.meta(tag)?;
+      }
+    }
+
+    Ok(assembler.extraction)
+  }
+
+  /// Assembles the metadata of `(property, content)` pairs, e.g. of the
+  /// flat representation in `ogp::flat`. Relative URLs are resolved
+  /// against the page URL.
+  pub fn extract_properties<'a>(
+    &self,
+    properties: impl IntoIterator<Item = (&'a str, &'a str)>,
+  ) -> Result<Extraction> {
+    let base_url = self.page_url.clone();
+    let mut assembler = Assembler {
+      mode: self.mode,
+      base_url: base_url.clone(),
+      extraction: Extraction {
+        base_url,
+        ..Default::default()
+      },
+    };
+
+    for (property, content) in properties {
+      assembler.property(property.to_string(), content)?;
+    }
+
+    Ok(assembler.extraction)
+  }
+
+  /// Determines the URL relative URLs are resolved against: the document's
+  /// `<base href>`, the page URL, or the document's `og:url`, in that order.
+  fn base_url(&self, tags: &[html::Tag]) -> Option<Url> {
+    let base_href = tags
+      .iter()
+      .find(|tag| tag.name == "base")
+      .and_then(|tag| tag.attr("href"))
+      .and_then(|href| {
+        resolve_http_url(href.trim(), self.page_url.as_ref()).ok()
+      });
+
+    let og_url = || {
+      tags
+        .iter()
+        .filter(|tag| tag.name == "meta")
+        .find(|tag| {
+          tag
+            .attr("property")
+            .or_else(|| tag.attr("name"))
+            .is_some_and(|property| {
+              property.trim().eq_ignore_ascii_case("og:url")
+            })
+        })
+        .and_then(|tag| tag.attr("content").or_else(|| tag.attr("value")))
+        .and_then(|url| validate_http_url(url.trim()).ok())
+    };
+
+    base_href.or_else(|| self.page_url.clone()).or_else(og_url)
+  }
+}
+
+/// Extracts Open Graph metadata from `html` using the strict parse mode.
+pub fn extract(html: &str) -> Result<Extraction> {
+  Extractor::default().extract(html)
+}
+
+/// Assembles a flat list of properties into an `Extraction`.
+struct Assembler {
+  mode: ParseMode,
+  base_url: Option<Url>,
+  extraction: Extraction,
+}
+
+impl Assembler {
+  /// Records a fix-up in lenient mode, or rejects the markup in strict mode.
+  fn report(
+    &mut self,
+    kind: WarningKind,
+    property: &str,
+    message: String,
+  ) -> Result<()> {
+    match self.mode {
+      ParseMode::Strict => Err(Error::InvalidMarkup(message)),
+      ParseMode::Lenient => {
+        self.extraction.warnings.push(Warning {
+          kind,
+          property: property.into(),
+          message,
+        });
+        Ok(())
+      }
+    }
+  }
+
+  /// Drops an invalid value in lenient mode, or returns `err` in strict mode.
+  ///
+  /// Returns `false`, since the value has not been applied.
+  fn invalid(&mut self, property: &str, err: Error) -> Result<bool> {
+    match self.mode {
+      ParseMode::Strict => Err(err),
+      ParseMode::Lenient => self
+        .report(
+          WarningKind::InvalidValue,
+          property,
+          format!("Dropped invalid value for '{}': {}", property, err),
+        )
+        .map(|_| false),
+    }
+  }
+
+  /// Drops a structured property that has no root property to belong to.
+  ///
+  /// Returns `false`, since the value has not been applied.
+  fn orphaned(&mut self, property: &str) -> Result<bool> {
+    self
+      .report(
+        WarningKind::OrphanedProperty,
+        property,
+        format!(
+          "Dropped '{}' because it does not follow the property it belongs to",
+          property
+        ),
+      )
+      .map(|_| false)
+  }
+
+  fn meta(&mut self, tag: &html::Tag) -> Result<()> {
+    let property = match tag.attr("property") {
+      Some(property) => property.trim().to_string(),
+      None => match tag.attr("name").map(str::trim) {
+        Some(name) if is_og_property(name) => {
+          self.report(
+            WarningKind::NameAttribute,
+            name,
+            format!(
+              "Property '{}' uses the `name` attribute instead of `property`",
+              name
+            ),
+          )?;
+          name.to_string()
+        }
+        _ => return Ok(()),
+      },
+    };
+
+    if !is_og_property(&property) {
+      return Ok(());
+    }
+
+    let normalized = property.to_lowercase();
+    if normalized != property {
+      self.report(
+        WarningKind::UppercaseProperty,
+        &normalized,
+        format!("Property '{}' must be lowercase", property),
+      )?;
+    }
+    let property = normalized;
+
+    let content = match (tag.attr("content"), tag.attr("value")) {
+      (Some(content), _) => content,
+      (None, Some(value)) => {
+        self.report(
+          WarningKind::ValueAttribute,
+          &property,
+          format!(
+            "Property '{}' uses the `value` attribute instead of `content`",
+            property
+          ),
+        )?;
+        value
+      }
+      (None, None) => return Ok(()),
+    };
+
+    self.property(property, content)
+  }
+
+  /// Applies a normalized property and records it.
+  fn property(&mut self, property: String, content: &str) -> Result<()> {
+    let mut content = content.to_string();
+    if URL_PROPERTIES.contains(&property.as_str()) && content.trim() != content
+    {
+      self.report(
+        WarningKind::PaddedUrl,
+        &property,
+        format!("URL of '{}' is padded with whitespace", property),
+      )?;
+      content = content.trim().to_string();
+    }
+
+    let is_duplicate = SINGLE_VALUE_PROPERTIES.contains(&property.as_str())
+      && self
+        .extraction
+        .properties
+        .iter()
+        .any(|(p, _)| *p == property);
+    if is_duplicate {
+      return self.report(
+        WarningKind::DuplicateProperty,
+        &property,
+        format!(
+          "Property '{}' is declared more than once; \
+          only the first occurrence is kept",
+          property
+        ),
+      );
+    }
+
+    if self.apply(&property, &content)? {
+      self.extraction.properties.push((property, content));
+    }
+
+    Ok(())
+  }
+
+  /// Applies a property to the metadata.
+  ///
+  /// Returns whether the property has been applied or was dropped.
+  fn apply(&mut self, property: &str, content: &str) -> Result<bool> {
+    let metadata = &mut self.extraction.metadata;
+
+    match property {
+      // Types outside the protocol, such as `product`, are common on real
+      // pages. They are read as `website` and only reported in lenient mode.
+      "og:type" => {
+        if let Err(err) = ObjectType::from_str(content) {
+          if self.mode == ParseMode::Lenient {
+            self.report(
+              WarningKind::UnknownObjectType,
+              property,
+              format!("{} Read as 'website'.", err),
+            )?;
+          }
+        }
+        self.extraction.metadata.object_type = ObjectType::from_string(content);
+      }
+      "og:title" => metadata.title = Some(content.into()),
+      "og:description" => metadata.description = Some(content.into()),
+      "og:site_name" => metadata.site_name = Some(content.into()),
+      "og:determiner" => match Determiner::from_str(content) {
+        Ok(determiner) => metadata.determiner = Some(determiner),
+        Err(err) => return self.invalid(property, err),
+      },
+      "og:locale" => metadata.locale = Some(content.into()),
+      "og:locale:alternate" => metadata
+        .locale_alternate
+        .get_or_insert_with(Vec::new)
+        .push(content.into()),
+      "og:url" => match validate_http_url(content) {
+        Ok(url) => metadata.url = Some(url.into()),
+        Err(err) => return self.invalid(property, err),
+      },
+
+      "og:image" | "og:image:url" => {
+        let is_same = is_same_url(
+          metadata.images.last().and_then(|img| img.url.as_ref()),
+          content,
+          self.base_url.as_ref(),
+        );
+        if property == "og:image" || !is_same {
+          match Image::from_str_with_base(content, self.base_url.as_ref()) {
+            Ok(img) => metadata.images.push(img),
+            Err(err) => return self.invalid(property, err),
+          }
+        }
+      }
+      "og:image:secure_url"
+      | "og:image:type"
+      | "og:image:alt"
+      | "og:image:width"
+      | "og:image:height" => {
+        let value =
+          match MediaValue::parse(property, content, self.base_url.as_ref()) {
+            Ok(value) => value,
+            Err(err) => return self.invalid(property, err),
+          };
+        match metadata.images.last_mut() {
+          Some(img) => value.apply_image(img),
+          None => return self.orphaned(property),
+        }
+      }
+
+      "og:video" | "og:video:url" => {
+        let is_same = is_same_url(
+          metadata.videos.last().and_then(|video| video.url.as_ref()),
+          content,
+          self.base_url.as_ref(),
+        );
+        if property == "og:video" || !is_same {
+          match Video::from_str_with_base(content, self.base_url.as_ref()) {
+            Ok(video) => metadata.videos.push(video),
+            Err(err) => return self.invalid(property, err),
+          }
+        }
+      }
+      "og:video:secure_url"
+      | "og:video:type"
+      | "og:video:alt"
+      | "og:video:width"
+      | "og:video:height" => {
+        let value =
+          match MediaValue::parse(property, content, self.base_url.as_ref()) {
+            Ok(value) => value,
+            Err(err) => return self.invalid(property, err),
+          };
+        match metadata.videos.last_mut() {
+          Some(video) => value.apply_video(video),
+          None => return self.orphaned(property),
+        }
+      }
+
+      "og:audio" | "og:audio:url" => {
+        let is_same = is_same_url(
+          metadata.audios.last().and_then(|audio| audio.url.as_ref()),
+          content,
+          self.base_url.as_ref(),
+        );
+        if property == "og:audio" || !is_same {
+          match Audio::from_str_with_base(content, self.base_url.as_ref()) {
+            Ok(audio) => metadata.audios.push(audio),
+            Err(err) => return self.invalid(property, err),
+          }
+        }
+      }
+      "og:audio:secure_url" | "og:audio:type" => {
+        let value =
+          match MediaValue::parse(property, content, self.base_url.as_ref()) {
+            Ok(value) => value,
+            Err(err) => return self.invalid(property, err),
+          };
+        match metadata.audios.last_mut() {
+          Some(audio) => value.apply_audio(audio),
+          None => return self.orphaned(property),
+        }
+      }
+
+      _ => {}
+    }
+
+    Ok(true)
+  }
+}
+
+/// A parsed value of a structured media property such as `og:image:width`.
+enum MediaValue {
+  SecureUrl(Url),
+  Mimetype(MediaType),
+  Alt(String),
+  Width(u32),
+  Height(u32),
+}
+
+impl MediaValue {
+  fn parse(
+    property: &str,
+    content: &str,
+    base_url: Option<&Url>,
+  ) -> Result<MediaValue> {
+    let field = property.rsplit(':').next().unwrap_or_default();
+    let dimension = || {
+      content.trim().parse::<u32>().map_err(|_| {
+        Error::Generic(format!("'{}' is not a valid dimension", content))
+      })
+    };
+
+    match field {
+      "secure_url" => {
+        resolve_http_url(content, base_url).map(MediaValue::SecureUrl)
+      }
+      "type" => MediaType::from_str(content).map(MediaValue::Mimetype),
+      "alt" => Ok(MediaValue::Alt(content.into())),
+      "width" => dimension().map(MediaValue::Width),
+      "height" => dimension().map(MediaValue::Height),
+      _ => Err(Error::Generic(format!("Unknown property '{}'", property))),
+    }
+  }
+
+  fn apply_image(self, img: &mut Image) {
+    match self {
+      MediaValue::SecureUrl(url) => img.secure_url = Some(url),
+      MediaValue::Mimetype(mimetype) => img.mimetype = Some(mimetype),
+      MediaValue::Alt(alt) => img.alt = Some(alt),
+      MediaValue::Width(width) => img.width = Some(width),
+      MediaValue::Height(height) => img.height = Some(height),
+    }
+  }
+
+  fn apply_video(self, video: &mut Video) {
+    match self {
+      MediaValue::SecureUrl(url) => video.secure_url = Some(url),
+      MediaValue::Mimetype(mimetype) => video.mimetype = Some(mimetype),
+      MediaValue::Alt(alt) => video.alt = Some(alt),
+      MediaValue::Width(width) => video.width = Some(width),
+      MediaValue::Height(height) => video.height = Some(height),
+    }
+  }
+
+  fn apply_audio(self, audio: &mut Audio) {
+    match self {
+      MediaValue::SecureUrl(url) => audio.secure_url = Some(url),
+      MediaValue::Mimetype(mimetype) => audio.mimetype = Some(mimetype),
+      _ => {}
+    }
+  }
+}
+
+/// Checks whether `property` belongs to an Open Graph namespace,
+/// ignoring case.
+fn is_og_property(property: &str) -> bool {
+  let property = property.to_lowercase();
+  PROPERTY_NAMESPACES
+    .iter()
+    .any(|namespace| property.starts_with(namespace))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const MESSY_HTML: &str = r#"
+    <html><head>
+      <meta name="og:title" content="The Rock">
+      <meta property="OG:TYPE" content="video.movie">
+      <meta property="og:description" value="A movie">
+      <meta property="og:title" content="The Rock (1996)">
+      <meta property="og:image" content="  https://example.com/rock.jpg ">
+      <meta property="og:image:width" content="400">
+      <meta property="og:image:height" content="300">
+    </head></html>
+  "#;
+
+  // region    Strict mode
+  #[test]
+  fn strict_extracts_valid_markup() {
+    let html = r#"
+      <meta property="og:title" content="The Rock">
+      <meta property="og:type" content="video.movie">
+      <meta property="og:url" content="https://www.imdb.com/title/tt0117500/">
+      <meta property="og:image" content="https://example.com/rock.jpg">
+      <meta property="og:image:alt" content="A rock">
+      <meta property="og:locale:alternate" content="de_DE">
+      <meta property="article:author" content="Jane">
+    "#;
+
+    let extraction = extract(html).unwrap();
+    let metadata = extraction.metadata;
+    assert_eq!(metadata.title.as_deref(), Some("The Rock"));
+    assert_eq!(metadata.object_type, ObjectType::VideoMovie);
+    assert_eq!(metadata.images.len(), 1);
+    assert_eq!(metadata.images[0].alt.as_deref(), Some("A rock"));
+    assert_eq!(metadata.locale_alternate, Some(vec!["de_DE".into()]));
+    assert_eq!(extraction.properties.len(), 7);
+    assert!(extraction.warnings.is_empty());
+  }
+
+  #[test]
+  fn strict_rejects_markup_mistakes() {
+    let cases = [
+      r#"<meta name="og:title" content="A">"#,
+      r#"<meta property="OG:TITLE" content="A">"#,
+      r#"<meta property="og:title" value="A">"#,
+      r#"<meta property="og:title" content="A">
+         <meta property="og:title" content="B">"#,
+      r#"<meta property="og:url" content=" https://example.com ">"#,
+    ];
+
+    for html in cases {
+      let err = Extractor::new(ParseMode::Strict).extract(html);
+      assert!(matches!(err, Err(Error::InvalidMarkup(_))), "{}", html);
+    }
+  }
+
+  #[test]
+  fn strict_rejects_invalid_values() {
+    let html =
+      r#"<meta property="og:image" content="ftp://example.com/a.png">"#;
+    assert!(extract(html).is_err());
+  }
+
+  #[test]
+  fn reads_unknown_object_types_as_website() {
+    let html = r#"<meta property="og:type" content="product">"#;
+    let extraction = extract(html).unwrap();
+    assert_eq!(extraction.metadata.object_type, ObjectType::Website);
+    assert!(extraction.warnings.is_empty());
+
+    let extraction = Extractor::new(ParseMode::Lenient).extract(html).unwrap();
+    assert_eq!(extraction.metadata.object_type, ObjectType::Website);
+    assert_eq!(extraction.warnings[0].kind, WarningKind::UnknownObjectType);
+    assert!(extraction.warnings[0].message.contains("'product'"));
+  }
+  // endregion Strict mode
+
+  // region    Lenient mode
+  #[test]
+  fn lenient_normalizes_markup_mistakes() {
+    let extraction = Extractor::new(ParseMode::Lenient)
+      .extract(MESSY_HTML)
+      .unwrap();
+    let metadata = &extraction.metadata;
+
+    assert_eq!(metadata.title.as_deref(), Some("The Rock"));
+    assert_eq!(metadata.object_type, ObjectType::VideoMovie);
+    assert_eq!(metadata.description.as_deref(), Some("A movie"));
+    assert_eq!(
+      metadata.images[0].url.as_ref().map(|url| url.as_str()),
+      Some("https://example.com/rock.jpg")
+    );
+    assert_eq!(metadata.images[0].width, Some(400));
+    assert_eq!(metadata.images[0].height, Some(300));
+
+    let kinds: Vec<WarningKind> =
+      extraction.warnings.iter().map(|w| w.kind).collect();
+    assert_eq!(
+      kinds,
+      vec![
+        WarningKind::NameAttribute,
+        WarningKind::UppercaseProperty,
+        WarningKind::ValueAttribute,
+        WarningKind::DuplicateProperty,
+        WarningKind::PaddedUrl,
+      ]
+    );
+  }
+
+  #[test]
+  fn lenient_drops_orphaned_and_invalid_values() {
+    let html = r#"
+      <meta property="og:image:width" content="400">
+      <meta property="og:image" content="https://example.com/a.png">
+      <meta property="og:image:height" content="tall">
+    "#;
+
+    let extraction = Extractor::new(ParseMode::Lenient).extract(html).unwrap();
+    assert_eq!(extraction.metadata.images.len(), 1);
+    assert_eq!(extraction.metadata.images[0].width, None);
+    assert_eq!(extraction.metadata.images[0].height, None);
+    assert_eq!(extraction.warnings[0].kind, WarningKind::OrphanedProperty);
+    assert_eq!(extraction.warnings[1].kind, WarningKind::InvalidValue);
+  }
+
+  #[test]
+  fn ignores_non_og_meta_tags() {
+    let html = r#"
+      <meta name="description" content="Plain description">
+      <meta name="twitter:title" content="Tweet">
+    "#;
+
+    let extraction = Extractor::new(ParseMode::Lenient).extract(html).unwrap();
+    assert!(extraction.properties.is_empty());
+    assert!(extraction.warnings.is_empty());
+  }
+  // endregion Lenient mode
+
+  // region    Relative URLs
+  #[test]
+  fn resolves_against_base_href() {
+    let html = r#"
+      <base href="https://static.example.com/assets/">
+      <meta property="og:url" content="https://example.com/post">
+      <meta property="og:image" content="img/cover.png">
+      <meta property="og:image:secure_url" content="//cdn.example.com/x.jpg">
+    "#;
+
+    let extraction = extract(html).unwrap();
+    let img = &extraction.metadata.images[0];
+    assert_eq!(
+      img.url.as_ref().map(Url::as_str),
+      Some("https://static.example.com/assets/img/cover.png")
+    );
+    assert_eq!(img.original_url.as_deref(), Some("img/cover.png"));
+    assert_eq!(
+      img.secure_url.as_ref().map(Url::as_str),
+      Some("https://cdn.example.com/x.jpg")
+    );
+  }
+
+  #[test]
+  fn merges_repeated_relative_media_urls() {
+    let html = r#"
+      <meta property="og:image" content="/a.png">
+      <meta property="og:image:url" content="/a.png">
+      <meta property="og:video" content="/a.mp4">
+      <meta property="og:video:url" content="https://example.com/a.mp4">
+      <meta property="og:audio" content="a.mp3">
+      <meta property="og:audio:url" content="/b.mp3">
+    "#;
+
+    let mut extractor = Extractor::new(ParseMode::Strict);
+    extractor.set_page_url(Url::parse("https://example.com/").unwrap());
+    let metadata = extractor.extract(html).unwrap().metadata;
+    assert_eq!(metadata.images.len(), 1);
+    assert_eq!(metadata.videos.len(), 1);
+    assert_eq!(metadata.audios.len(), 2);
+  }
+
+  #[test]
+  fn resolves_against_page_url_then_og_url() {
+    let html = r#"
+      <meta property="og:video" content="/video.mp4">
+      <meta property="og:url" content="https://example.com/post">
+    "#;
+
+    let extraction = extract(html).unwrap();
+    assert_eq!(
+      extraction.metadata.videos[0].url.as_ref().map(Url::as_str),
+      Some("https://example.com/video.mp4")
+    );
+
+    let mut extractor = Extractor::default();
+    extractor.set_page_url(Url::parse("https://page.example.com/a/").unwrap());
+    let extraction = extractor.extract(html).unwrap();
+    assert_eq!(
+      extraction.metadata.videos[0].url.as_ref().map(Url::as_str),
+      Some("https://page.example.com/video.mp4")
+    );
+  }
+
+  #[test]
+  fn rejects_relative_urls_without_base() {
+    let html = r#"<meta property="og:audio" content="/a.mp3">"#;
+    assert!(extract(html).is_err());
+  }
+  // endregion Relative URLs
+}
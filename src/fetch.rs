@@ -0,0 +1,657 @@
+//! Fetching pages and extracting their Open Graph metadata.
+//!
+//! A `Fetcher` retrieves a URL and extracts its `OgMetadata`, e.g. for a
+//! link unfurler. It
+//!
+//! - follows redirects up to `FetchOptions::max_redirects`,
+//! - rejects unsuccessful HTTP statuses and non-HTML content types,
+//! - reads the body only up to `</head>`, and at most
+//!   `FetchOptions::max_body_size` bytes,
+//! - fails once `FetchOptions::timeout` has elapsed,
+//! - decodes the body using the charset of its byte order mark, the
+//!   `Content-Type` header or the document's `<meta charset>`, in that
+//!   order, falling back to UTF-8.
+//!
+//! The HTTP layer sits behind the `Transport` trait, so any client can be
+//! plugged in. Transports send a single request and must not follow
+//! redirects themselves. `MemoryTransport` serves canned responses, e.g.
+//! for tests.
+//!
+//! This module is only available with the `fetch` feature enabled.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ogp::fetch::{FetchOptions, Fetcher, MemoryTransport};
+//!
+//! let mut transport = MemoryTransport::new();
+//! transport
+//!   .redirect("http://example.com/", "https://example.com/", 301)
+//!   .page(
+//!     "https://example.com/",
+//!     r#"<head><meta property="og:title" content="Example"></head>"#,
+//!   );
+//!
+//! let page = Fetcher::new(transport, FetchOptions::default())
+//!   .fetch("http://example.com/")
+//!   .unwrap();
+//! assert_eq!(page.url.as_str(), "https://example.com/");
+//! assert_eq!(page.extraction.metadata.title.as_deref(), Some("Example"));
+//! ```
+//!
+//! A transport for a blocking HTTP client, e.g. `reqwest`, forwards the
+//! request without following redirects:
+//!
+//! ```rust,ignore
+//! use ogp::error::Error;
+//! use ogp::fetch::{FetchRequest, FetchResponse, Transport};
+//!
+//! struct Reqwest(reqwest::blocking::Client); // built with `Policy::none()`
+//!
+//! impl Transport for Reqwest {
+//!   fn send(&self, request: &FetchRequest) -> ogp::Result<FetchResponse> {
+//!     let fail =
+//!       |err: reqwest::Error| Error::FetchFailed(request.url.to_string(), err.to_string());
+//!     let mut builder = self.0.get(request.url.as_str()).timeout(request.timeout);
+//!     for (name, value) in &request.headers {
+//!       builder = builder.header(name, value);
+//!     }
+//!     let response = builder.send().map_err(fail)?;
+//!     let headers = response
+//!       .headers()
+//!       .iter()
+//!       .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.into())))
+//!       .collect();
+//!     Ok(FetchResponse::new(response.status().as_u16(), headers, response))
+//!   }
+//! }
+//! ```
+
+use std::{
+  collections::HashMap,
+  io::{Cursor, Read},
+  time::{Duration, Instant},
+};
+
+use encoding_rs::{Encoding, UTF_8};
+use url::Url;
+
+use crate::{
+  error::Error,
+  extract::{html, Extraction, Extractor, ParseMode},
+  utils::validate_http_url,
+  Result,
+};
+
+/// The number of bytes read from the body at a time.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// The number of bytes scanned for a `<meta charset>`, as in the HTML spec.
+const CHARSET_PRESCAN_SIZE: usize = 1024;
+
+/// Content types accepted as HTML documents.
+const HTML_CONTENT_TYPES: [&str; 2] = ["text/html", "application/xhtml+xml"];
+
+/// A single HTTP `GET` request sent by a `Transport`.
+#[derive(Debug, Clone)]
+pub struct FetchRequest {
+  /// The URL to request.
+  pub url: Url,
+
+  /// Request headers, e.g. `User-Agent` and `Accept`.
+  pub headers: Vec<(String, String)>,
+
+  /// The time left until the fetch times out.
+  pub timeout: Duration,
+}
+
+/// The response to a `FetchRequest`.
+pub struct FetchResponse {
+  /// The HTTP status code.
+  pub status: u16,
+
+  /// Response headers. Names are matched case-insensitively.
+  pub headers: Vec<(String, String)>,
+
+  /// The response body, read lazily.
+  pub body: Box<dyn Read + Send>,
+}
+
+impl FetchResponse {
+  /// Creates a response.
+  pub fn new(
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: impl Read + Send + 'static,
+  ) -> Self {
+    FetchResponse {
+      status,
+      headers,
+      body: Box::new(body),
+    }
+  }
+
+  /// Returns the value of the first header with the given name.
+  pub fn header(&self, name: &str) -> Option<&str> {
+    self
+      .headers
+      .iter()
+      .find(|(key, _)| key.eq_ignore_ascii_case(name))
+      .map(|(_, value)| value.as_str())
+  }
+}
+
+/// The HTTP layer of a `Fetcher`.
+///
+/// Implementations send a single request and return the response as is.
+/// They must not follow redirects, and should apply `FetchRequest::timeout`
+/// to connecting and reading. Failures are reported as
+/// `Error::FetchFailed`.
+pub trait Transport {
+  /// Sends `request` and returns the response.
+  fn send(&self, request: &FetchRequest) -> Result<FetchResponse>;
+}
+
+impl<F> Transport for F
+where
+  F: Fn(&FetchRequest) -> Result<FetchResponse>,
+{
+  fn send(&self, request: &FetchRequest) -> Result<FetchResponse> {
+    self(request)
+  }
+}
+
+/// Options of a `Fetcher`.
+#[derive(Debug, Clone)]
+pub struct FetchOptions {
+  /// The maximum number of redirects followed.
+  pub max_redirects: usize,
+
+  /// The maximum number of body bytes read.
+  pub max_body_size: usize,
+
+  /// The time after which fetching fails, including redirects.
+  pub timeout: Duration,
+
+  /// The `User-Agent` header sent with requests.
+  pub user_agent: String,
+
+  /// The mode used to extract the metadata.
+  pub mode: ParseMode,
+}
+
+impl Default for FetchOptions {
+  fn default() -> Self {
+    FetchOptions {
+      max_redirects: 5,
+      max_body_size: 1024 * 1024,
+      timeout: Duration::from_secs(10),
+      user_agent: format!("ogp/{}", env!("CARGO_PKG_VERSION")),
+      mode: ParseMode::Lenient,
+    }
+  }
+}
+
+/// A fetched page and its extracted metadata.
+#[derive(Debug)]
+pub struct FetchedPage {
+  /// The final URL, after following redirects.
+  pub url: Url,
+
+  /// The URLs redirected from, in order.
+  pub redirects: Vec<Url>,
+
+  /// The name of the charset the body was decoded with, e.g. `UTF-8`.
+  pub charset: &'static str,
+
+  /// Whether the body was cut off at `max_body_size` before `</head>`.
+  pub truncated: bool,
+
+  /// The metadata extracted from the page.
+  pub extraction: Extraction,
+}
+
+/// Fetches pages over a `Transport` and extracts their metadata.
+pub struct Fetcher<T> {
+  transport: T,
+  options: FetchOptions,
+}
+
+impl<T: Transport> Fetcher<T> {
+  pub fn new(transport: T, options: FetchOptions) -> Self {
+    Fetcher { transport, options }
+  }
+
+  pub fn options(&self) -> &FetchOptions {
+    &self.options
+  }
+
+  /// Fetches the page at `url` and extracts its metadata.
+  pub fn fetch(&self, url: &str) -> Result<FetchedPage> {
+    let deadline = Instant::now() + self.options.timeout;
+    let mut url = validate_http_url(url)?;
+    let mut redirects = Vec::new();
+
+    let mut response = loop {
+      let request = FetchRequest {
+        url: url.clone(),
+        headers: vec![
+          ("User-Agent".into(), self.options.user_agent.clone()),
+          ("Accept".into(), HTML_CONTENT_TYPES.join(", ")),
+        ],
+        timeout: remaining(&url, deadline)?,
+      };
+      let response = self.transport.send(&request)?;
+
+      if !is_redirect(response.status) {
+        break response;
+      }
+      let location = response
+        .header("Location")
+        .and_then(|location| url.join(location.trim()).ok())
+        .ok_or_else(|| Error::HttpStatus(url.to_string(), response.status))?;
+      let location = validate_http_url(location.as_str())?;
+
+      if redirects.len() == self.options.max_redirects {
+        return Err(Error::TooManyRedirects(
+          redirects.first().unwrap_or(&url).to_string(),
+          self.options.max_redirects,
+        ));
+      }
+      redirects.push(std::mem::replace(&mut url, location));
+    };
+
+    if !(200..300).contains(&response.status) {
+      return Err(Error::HttpStatus(url.to_string(), response.status));
+    }
+    let content_type = response.header("Content-Type").map(str::to_string);
+    if let Some(content_type) = &content_type {
+      if !is_html(content_type) {
+        return Err(Error::UnsupportedContentType(
+          url.to_string(),
+          content_type.clone(),
+        ));
+      }
+    }
+
+    let (bytes, truncated) = read_head(
+      &mut response.body,
+      &url,
+      deadline,
+      self.options.max_body_size,
+    )?;
+    let encoding = detect_encoding(&bytes, content_type.as_deref());
+    let (html, encoding, _) = encoding.decode(&bytes);
+
+    let extraction = Extractor::new(self.options.mode)
+      .set_page_url(url.clone())
+      .extract(&html)?;
+
+    Ok(FetchedPage {
+      url,
+      redirects,
+      charset: encoding.name(),
+      truncated,
+      extraction,
+    })
+  }
+}
+
+/// The time left until `deadline`, failing if it has passed.
+fn remaining(url: &Url, deadline: Instant) -> Result<Duration> {
+  deadline
+    .checked_duration_since(Instant::now())
+    .filter(|remaining| !remaining.is_zero())
+    .ok_or_else(|| Error::FetchFailed(url.to_string(), "Timed out".into()))
+}
+
+fn is_redirect(status: u16) -> bool {
+  matches!(status, 301 | 302 | 303 | 307 | 308)
+}
+
+fn is_html(content_type: &str) -> bool {
+  let media_type = content_type.split(';').next().unwrap_or_default();
+  HTML_CONTENT_TYPES
+    .iter()
+    .any(|html| media_type.trim().eq_ignore_ascii_case(html))
+}
+
+/// Reads `body` up to and including `</head>`, at most `max_size` bytes.
+///
+/// Returns the bytes read and whether reading stopped at `max_size`.
+fn read_head(
+  body: &mut dyn Read,
+  url: &Url,
+  deadline: Instant,
+  max_size: usize,
+) -> Result<(Vec<u8>, bool)> {
+  let mut bytes = Vec::new();
+  let mut chunk = [0; CHUNK_SIZE];
+  // Where to search for `</head` next. Stays at a match whose `>` has not
+  // been read yet, and otherwise covers a match spanning two chunks.
+  let mut search_from = 0;
+
+  while bytes.len() < max_size {
+    remaining(url, deadline)?;
+
+    let len = CHUNK_SIZE.min(max_size - bytes.len());
+    let read = body
+      .read(&mut chunk[..len])
+      .map_err(|err| Error::FetchFailed(url.to_string(), err.to_string()))?;
+    if read == 0 {
+      return Ok((bytes, false));
+    }
+    bytes.extend_from_slice(&chunk[..read]);
+
+    loop {
+      let tag = bytes[search_from..]
+        .windows(6)
+        .position(|window| window.eq_ignore_ascii_case(b"</head"))
+        .map(|start| search_from + start);
+      let Some(start) = tag else {
+        search_from = search_from.max(bytes.len().saturating_sub(5));
+        break;
+      };
+      let Some(end) = bytes[start..].iter().position(|b| *b == b'>') else {
+        search_from = start;
+        break;
+      };
+
+      let end = start + end + 1;
+      if closes_head(&bytes[..end]) {
+        bytes.truncate(end);
+        return Ok((bytes, false));
+      }
+      // Within a comment or raw text like a script, keep searching.
+      search_from = start + 1;
+    }
+  }
+
+  Ok((bytes, true))
+}
+
+/// Checks whether `prefix` ends with a `</head>` end tag that is not part
+/// of a comment or raw text.
+fn closes_head(prefix: &[u8]) -> bool {
+  let html = String::from_utf8_lossy(prefix);
+  html::scan_document(&html)
+    .end_tags
+    .last()
+    .is_some_and(|tag| tag.name == "head" && tag.span.end == html.len())
+}
+
+/// Determines the encoding of an HTML document, see the module docs.
+fn detect_encoding(
+  bytes: &[u8],
+  content_type: Option<&str>,
+) -> &'static Encoding {
+  if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+    return encoding;
+  }
+
+  content_type
+    .and_then(charset_param)
+    .or_else(|| meta_charset(bytes))
+    .and_then(|label| Encoding::for_label(label.as_bytes()))
+    // A document declaring UTF-16 in ASCII is not UTF-16.
+    .map(|encoding| encoding.output_encoding())
+    .unwrap_or(UTF_8)
+}
+
+/// The `charset` parameter of a `Content-Type` value.
+fn charset_param(content_type: &str) -> Option<String> {
+  content_type.split(';').skip(1).find_map(|param| {
+    let (name, value) = param.split_once('=')?;
+    name
+      .trim()
+      .eq_ignore_ascii_case("charset")
+      .then(|| value.trim().trim_matches(['"', '\'']).to_string())
+  })
+}
+
+/// The charset declared by a `<meta>` tag at the start of the document.
+fn meta_charset(bytes: &[u8]) -> Option<String> {
+  let prefix = &bytes[..bytes.len().min(CHARSET_PRESCAN_SIZE)];
+  html::scan(&String::from_utf8_lossy(prefix))
+    .iter()
+    .filter(|tag| tag.name == "meta")
+    .find_map(|tag| {
+      tag.attr("charset").map(str::to_string).or_else(|| {
+        tag
+          .attr("http-equiv")
+          .filter(|name| name.eq_ignore_ascii_case("content-type"))
+          .and(tag.attr("content"))
+          .and_then(charset_param)
+      })
+    })
+}
+
+/// A `Transport` serving canned responses from memory.
+///
+/// Requests for unknown URLs fail with a 404 response.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryTransport {
+  responses: HashMap<String, CannedResponse>,
+}
+
+#[derive(Debug, Clone)]
+struct CannedResponse {
+  status: u16,
+  headers: Vec<(String, String)>,
+  body: Vec<u8>,
+}
+
+impl MemoryTransport {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Serves `body` for `url` with the given status and headers.
+  pub fn response(
+    &mut self,
+    url: &str,
+    status: u16,
+    headers: &[(&str, &str)],
+    body: impl Into<Vec<u8>>,
+  ) -> &mut Self {
+    let headers = headers
+      .iter()
+      .map(|(name, value)| (name.to_string(), value.to_string()))
+      .collect();
+    self.responses.insert(
+      normalize(url),
+      CannedResponse {
+        status,
+        headers,
+        body: body.into(),
+      },
+    );
+    self
+  }
+
+  /// Serves an HTML page for `url`.
+  pub fn page(&mut self, url: &str, html: &str) -> &mut Self {
+    let headers = [("Content-Type", "text/html; charset=utf-8")];
+    self.response(url, 200, &headers, html)
+  }
+
+  /// Redirects `url` to `location` with the given status.
+  pub fn redirect(
+    &mut self,
+    url: &str,
+    location: &str,
+    status: u16,
+  ) -> &mut Self {
+    self.response(url, status, &[("Location", location)], "")
+  }
+}
+
+impl Transport for MemoryTransport {
+  fn send(&self, request: &FetchRequest) -> Result<FetchResponse> {
+    Ok(match self.responses.get(&normalize(request.url.as_str())) {
+      Some(canned) => FetchResponse::new(
+        canned.status,
+        canned.headers.clone(),
+        Cursor::new(canned.body.clone()),
+      ),
+      None => FetchResponse::new(404, Vec::new(), std::io::empty()),
+    })
+  }
+}
+
+/// Parses `url` so equal URLs match, e.g. with and without a trailing `/`.
+fn normalize(url: &str) -> String {
+  Url::parse(url).map_or_else(|_| url.to_string(), String::from)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PAGE: &str = r#"<html><head>
+    <meta property="og:title" content="Example">
+    <meta property="og:image" content="/cover.png">
+  </head><body><p>Body</p></body></html>"#;
+
+  fn fetch(transport: MemoryTransport, url: &str) -> Result<FetchedPage> {
+    Fetcher::new(transport, FetchOptions::default()).fetch(url)
+  }
+
+  #[test]
+  fn follows_redirects() {
+    let mut transport = MemoryTransport::new();
+    transport
+      .redirect("http://example.com/a", "/b", 302)
+      .redirect("http://example.com/b", "https://example.com/c", 301)
+      .page("https://example.com/c", PAGE);
+
+    let page = fetch(transport.clone(), "http://example.com/a").unwrap();
+    assert_eq!(page.url.as_str(), "https://example.com/c");
+    assert_eq!(page.redirects.len(), 2);
+    let image = &page.extraction.metadata.images[0];
+    assert_eq!(
+      image.url.as_ref().map(Url::as_str),
+      Some("https://example.com/cover.png")
+    );
+
+    let options = FetchOptions {
+      max_redirects: 1,
+      ..Default::default()
+    };
+    let err = Fetcher::new(transport, options).fetch("http://example.com/a");
+    assert!(
+      matches!(err, Err(Error::TooManyRedirects(url, 1)) if url == "http://example.com/a")
+    );
+  }
+
+  #[test]
+  fn rejects_unsuccessful_responses() {
+    let mut transport = MemoryTransport::new();
+    transport
+      .response(
+        "https://example.com/img",
+        200,
+        &[("Content-Type", "image/png")],
+        "",
+      )
+      .redirect("https://example.com/loop", "/loop", 307)
+      .redirect("https://example.com/ftp", "ftp://example.com/", 301);
+
+    let fetch = |url| fetch(transport.clone(), url);
+    assert!(matches!(
+      fetch("https://example.com/missing"),
+      Err(Error::HttpStatus(_, 404))
+    ));
+    assert!(matches!(
+      fetch("https://example.com/img"),
+      Err(Error::UnsupportedContentType(_, _))
+    ));
+    assert!(matches!(
+      fetch("https://example.com/loop"),
+      Err(Error::TooManyRedirects(_, 5))
+    ));
+    assert!(matches!(
+      fetch("https://example.com/ftp"),
+      Err(Error::InvalidHttpUrlScheme(_))
+    ));
+  }
+
+  #[test]
+  fn reads_up_to_head_end() {
+    let mut body = Cursor::new(PAGE.as_bytes());
+    let deadline = Instant::now() + Duration::from_secs(1);
+    let url = Url::parse("https://example.com/").unwrap();
+
+    let (bytes, truncated) =
+      read_head(&mut body, &url, deadline, 1 << 20).unwrap();
+    assert!(String::from_utf8(bytes).unwrap().ends_with("</head>"));
+    assert!(!truncated);
+
+    let mut body = Cursor::new(PAGE.as_bytes());
+    let (bytes, truncated) = read_head(&mut body, &url, deadline, 20).unwrap();
+    assert_eq!(bytes.len(), 20);
+    assert!(truncated);
+
+    let err = read_head(&mut body, &url, Instant::now(), 20);
+    assert!(matches!(err, Err(Error::FetchFailed(_, _))));
+
+    // `</head>` split across reads, followed by a large body.
+    let rest = format!("<body>{}</body>", "x".repeat(2 << 20));
+    for split in [5, 6] {
+      let head = "<head><title>Chunked</title></head>";
+      let at = head.len() - 7 + split;
+      let mut body = Cursor::new(&head.as_bytes()[..at])
+        .chain(Cursor::new(&head.as_bytes()[at..]))
+        .chain(Cursor::new(rest.as_bytes()));
+      let (bytes, truncated) =
+        read_head(&mut body, &url, deadline, 1 << 20).unwrap();
+      assert_eq!(bytes, head.as_bytes());
+      assert!(!truncated);
+    }
+
+    // `</head>` in an inline script or a comment does not end the head.
+    let head = "<head><script>var a = '</head>';</script><!-- </head> -->\
+      <meta property=\"og:title\" content=\"Late\"></head>";
+    let mut body = Cursor::new(format!("{}<body></body>", head).into_bytes());
+    let (bytes, truncated) =
+      read_head(&mut body, &url, deadline, 1 << 20).unwrap();
+    assert_eq!(bytes, head.as_bytes());
+    assert!(!truncated);
+  }
+
+  #[test]
+  fn detects_charset() {
+    let latin1 = b"<head><meta charset=\"iso-8859-1\"><title>Caf\xe9</title>\
+      <meta property=\"og:title\" content=\"Caf\xe9\"></head>";
+
+    let mut transport = MemoryTransport::new();
+    transport
+      .response(
+        "https://example.com/header",
+        200,
+        &[("Content-Type", "text/html; charset=\"windows-1252\"")],
+        &latin1[..],
+      )
+      .response("https://example.com/meta", 200, &[], &latin1[..])
+      .response(
+        "https://example.com/bom",
+        200,
+        &[],
+        b"\xef\xbb\xbf<head></head>".to_vec(),
+      );
+
+    for url in ["https://example.com/header", "https://example.com/meta"] {
+      let page = fetch(transport.clone(), url).unwrap();
+      assert_eq!(page.charset, "windows-1252");
+      assert_eq!(page.extraction.metadata.title.as_deref(), Some("Café"));
+    }
+
+    let page = fetch(transport, "https://example.com/bom").unwrap();
+    assert_eq!(page.charset, "UTF-8");
+
+    assert_eq!(
+      detect_encoding(b"<meta charset=utf-16le>", None).name(),
+      "UTF-8"
+    );
+  }
+}
@@ -0,0 +1,357 @@
+//! The flat Open Graph property representation.
+//!
+//! The serde derives of the metadata structs produce nested JSON, e.g.
+//! `"og:image": [{"url": ..., "width": ...}]`. The flat representation is an
+//! ordered list of `(property, content)` pairs instead, exactly as the
+//! properties appear as meta tags in HTML:
+//!
+//! ```text
+//! [["og:type", "article"], ["og:title", "The Rock"],
+//!  ["og:image", "https://example.com/rock.jpg"], ["og:image:width", "1200"],
+//!  ["article:tag", "movies"]]
+//! ```
+//!
+//! Structured properties such as `og:image:width` follow the property they
+//! belong to, and dates are written in ISO 8601. This makes the flat form a
+//! neutral, lossless format for storing metadata, e.g. in caches or message
+//! queues.
+//!
+//! Every metadata struct implements `FlatProperties`. To use the flat form
+//! with serde, wrap a value in `Flat` or annotate a field with
+//! `#[serde(with = "ogp::flat")]`.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ogp::flat::{Flat, FlatProperties};
+//! use ogp::metadata::OgMetadata;
+//!
+//! let metadata = OgMetadata::from_properties(&[
+//!   ("og:title".into(), "The Rock".into()),
+//!   ("og:url".into(), "https://example.com/rock".into()),
+//! ])
+//! .unwrap();
+//!
+//! let json = serde_json::to_string(&Flat(metadata)).unwrap();
+//! assert_eq!(
+//!   json,
+//!   r#"[["og:type","website"],["og:url","https://example.com/rock"],["og:title","The Rock"]]"#
+//! );
+//!
+//! let Flat(metadata): Flat<OgMetadata> = serde_json::from_str(&json).unwrap();
+//! assert_eq!(metadata.title.as_deref(), Some("The Rock"));
+//! ```
+
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+  extract::{Extractor, ParseMode},
+  metadata::{Audio, Image, OgMetadata, Video},
+  object_type::{Determiner, ObjectType},
+  Result,
+};
+
+/// Conversion from and to the flat `(property, content)` representation.
+pub trait FlatProperties: Sized {
+  /// The properties of `self` in the order they appear as meta tags.
+  fn to_properties(&self) -> Vec<(String, String)>;
+
+  /// Parses properties in the order they appear as meta tags.
+  ///
+  /// Properties unknown to the type are ignored, e.g. `article:author`
+  /// when parsing `OgMetadata`. Invalid values are rejected.
+  fn from_properties(properties: &[(String, String)]) -> Result<Self>;
+}
+
+/// Serializes and deserializes the wrapped value in the flat representation.
+#[derive(Debug, Clone, Default)]
+pub struct Flat<T>(pub T);
+
+impl<T: FlatProperties> Serialize for Flat<T> {
+  fn serialize<S: Serializer>(
+    &self,
+    serializer: S,
+  ) -> std::result::Result<S::Ok, S::Error> {
+    serialize(&self.0, serializer)
+  }
+}
+
+impl<'de, T: FlatProperties> Deserialize<'de> for Flat<T> {
+  fn deserialize<D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> std::result::Result<Self, D::Error> {
+    deserialize(deserializer).map(Flat)
+  }
+}
+
+/// Serializes a value in the flat representation, for use with
+/// `#[serde(with = "ogp::flat")]`.
+pub fn serialize<T: FlatProperties, S: Serializer>(
+  value: &T,
+  serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+  value.to_properties().serialize(serializer)
+}
+
+/// Deserializes a value from the flat representation, for use with
+/// `#[serde(with = "ogp::flat")]`.
+pub fn deserialize<'de, T: FlatProperties, D: Deserializer<'de>>(
+  deserializer: D,
+) -> std::result::Result<T, D::Error> {
+  let properties = Vec::<(String, String)>::deserialize(deserializer)?;
+  T::from_properties(&properties).map_err(de::Error::custom)
+}
+
+impl FlatProperties for OgMetadata {
+  fn to_properties(&self) -> Vec<(String, String)> {
+    let mut properties = Properties::default();
+
+    properties.push("og:type", self.object_type.as_str());
+    properties.push_opt("og:url", self.url.as_ref());
+    properties.push_opt("og:title", self.title.as_ref());
+    properties.push_opt("og:description", self.description.as_ref());
+    properties.push_opt("og:site_name", self.site_name.as_ref());
+    if let Some(determiner) = &self.determiner {
+      let determiner = match determiner {
+        Determiner::Auto => "auto",
+        determiner => determiner.as_str(),
+      };
+      properties.push("og:determiner", determiner);
+    }
+    properties.push_opt("og:locale", self.locale.as_ref());
+    for locale in self.locale_alternate.iter().flatten() {
+      properties.push("og:locale:alternate", locale);
+    }
+
+    self.images.iter().for_each(|image| properties.image(image));
+    self.videos.iter().for_each(|video| properties.video(video));
+    self.audios.iter().for_each(|audio| properties.audio(audio));
+
+    properties.0
+  }
+
+  fn from_properties(properties: &[(String, String)]) -> Result<Self> {
+    // The extractor reads unknown types as `website`, as found on real
+    // pages. The flat representation only holds known ones.
+    for (property, content) in properties {
+      if property == "og:type" {
+        ObjectType::from_str(content)?;
+      }
+    }
+
+    let og_properties = properties
+      .iter()
+      .filter(|(property, _)| property.starts_with("og:"))
+      .map(|(property, content)| (property.as_str(), content.as_str()));
+
+    Extractor::new(ParseMode::Strict)
+      .extract_properties(og_properties)
+      .map(|extraction| extraction.metadata)
+  }
+}
+
+/// Collects properties in order.
+#[derive(Default)]
+pub(crate) struct Properties(pub(crate) Vec<(String, String)>);
+
+impl Properties {
+  pub(crate) fn push(&mut self, property: &str, content: impl ToString) {
+    self.0.push((property.into(), content.to_string()));
+  }
+
+  pub(crate) fn push_opt(
+    &mut self,
+    property: &str,
+    content: Option<impl ToString>,
+  ) {
+    if let Some(content) = content {
+      self.push(property, content);
+    }
+  }
+
+  fn image(&mut self, image: &Image) {
+    self.push_opt("og:image", image.url.as_ref());
+    self.push_opt("og:image:secure_url", image.secure_url.as_ref());
+    self.push_opt("og:image:type", image.mimetype.as_ref());
+    self.push_opt("og:image:width", image.width);
+    self.push_opt("og:image:height", image.height);
+    self.push_opt("og:image:alt", image.alt.as_ref());
+  }
+
+  fn video(&mut self, video: &Video) {
+    self.push_opt("og:video", video.url.as_ref());
+    self.push_opt("og:video:secure_url", video.secure_url.as_ref());
+    self.push_opt("og:video:type", video.mimetype.as_ref());
+    self.push_opt("og:video:width", video.width);
+    self.push_opt("og:video:height", video.height);
+    self.push_opt("og:video:alt", video.alt.as_ref());
+  }
+
+  fn audio(&mut self, audio: &Audio) {
+    self.push_opt("og:audio", audio.url.as_ref());
+    self.push_opt("og:audio:secure_url", audio.secure_url.as_ref());
+    self.push_opt("og:audio:type", audio.mimetype.as_ref());
+  }
+}
+
+/// Splits properties into the root metadata and the type-specific
+/// properties, which are returned in order.
+pub(crate) fn split_properties(
+  properties: &[(String, String)],
+) -> Result<(OgMetadata, Vec<(&str, &str)>)> {
+  let root = OgMetadata::from_properties(properties)?;
+  let type_specific = properties
+    .iter()
+    .filter(|(property, _)| !property.starts_with("og:"))
+    .map(|(property, content)| (property.as_str(), content.as_str()))
+    .collect();
+  Ok((root, type_specific))
+}
+
+/// Implements `FlatProperties` and `ToHTML` for object types without
+/// type-specific properties, based on `ObjectMetadata`.
+macro_rules! impl_flat_properties {
+  ($($ty:ty),+ $(,)?) => {
+    $(
+      impl $crate::flat::FlatProperties for $ty {
+        fn to_properties(&self) -> Vec<(String, String)> {
+          use $crate::object_type::retype::ObjectMetadata;
+          self.root().to_properties()
+        }
+
+        fn from_properties(
+          properties: &[(String, String)],
+        ) -> $crate::Result<Self> {
+          use $crate::object_type::retype::ObjectMetadata;
+          $crate::metadata::OgMetadata::from_properties(properties)
+            .map(Self::from_root)
+        }
+      }
+
+      impl $crate::convert::ToHTML for $ty {}
+    )+
+  };
+}
+
+pub(crate) use impl_flat_properties;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::object_type::{
+    article::ArticleMetadata, book::Book, music::MusicSong,
+    profile::ProfileMetadata, retype::ObjectMetadata, ObjectType,
+  };
+
+  fn properties(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+      .iter()
+      .map(|(property, content)| (property.to_string(), content.to_string()))
+      .collect()
+  }
+
+  #[test]
+  fn round_trips_metadata() {
+    let pairs = properties(&[
+      ("og:type", "video.movie"),
+      ("og:url", "https://example.com/rock"),
+      ("og:title", "The Rock"),
+      ("og:determiner", "auto"),
+      ("og:locale", "en_US"),
+      ("og:locale:alternate", "fr_FR"),
+      ("og:locale:alternate", "es_ES"),
+      ("og:image", "https://example.com/rock.jpg"),
+      ("og:image:type", "image/jpeg"),
+      ("og:image:width", "1200"),
+      ("og:image:height", "630"),
+      ("og:image:alt", "A shark"),
+      ("og:image", "https://example.com/rock2.jpg"),
+      ("og:video", "https://example.com/rock.mp4"),
+      ("og:video:secure_url", "https://example.com/rock.mp4"),
+      ("og:audio", "https://example.com/rock.mp3"),
+    ]);
+
+    let metadata = OgMetadata::from_properties(&pairs).unwrap();
+    assert_eq!(metadata.object_type, ObjectType::VideoMovie);
+    assert_eq!(metadata.images.len(), 2);
+    assert_eq!(metadata.to_properties(), pairs);
+  }
+
+  #[test]
+  fn rejects_invalid_values() {
+    let pairs = properties(&[("og:image:width", "wide")]);
+    assert!(OgMetadata::from_properties(&pairs).is_err());
+
+    let pairs = properties(&[("og:type", "movie")]);
+    assert!(OgMetadata::from_properties(&pairs).is_err());
+  }
+
+  #[test]
+  fn round_trips_object_types() {
+    let pairs = properties(&[
+      ("og:type", "article"),
+      ("og:title", "Release notes"),
+      ("article:published_time", "2024-03-01T09:30:00Z"),
+      ("article:author", "https://example.com/jane"),
+      ("article:section", "News"),
+      ("article:tag", "release"),
+      ("article:tag", "rust"),
+    ]);
+    let article = ArticleMetadata::from_properties(&pairs).unwrap();
+    assert_eq!(article.tags(), ["release", "rust"]);
+    assert_eq!(article.to_properties(), pairs);
+
+    let pairs = properties(&[
+      ("og:type", "profile"),
+      ("profile:first_name", "Jane"),
+      ("profile:gender", "female"),
+    ]);
+    let profile = ProfileMetadata::from_properties(&pairs).unwrap();
+    assert_eq!(profile.first_name.as_deref(), Some("Jane"));
+    assert_eq!(profile.to_properties(), pairs);
+
+    let pairs = properties(&[
+      ("og:type", "book"),
+      ("book:isbn", "978-3-16-148410-0"),
+      ("book:release_date", "2011-01-01"),
+    ]);
+    assert_eq!(
+      Book::from_properties(&pairs).unwrap().to_properties(),
+      pairs
+    );
+
+    let pairs = properties(&[("og:type", "music.song"), ("og:title", "Song")]);
+    let song = MusicSong::from_properties(&pairs).unwrap();
+    assert_eq!(song.root().title.as_deref(), Some("Song"));
+    assert_eq!(song.to_properties(), pairs);
+
+    let pairs = properties(&[("article:published_time", "yesterday")]);
+    assert!(ArticleMetadata::from_properties(&pairs).is_err());
+  }
+
+  #[test]
+  fn serializes_with_serde() {
+    #[derive(Serialize, Deserialize)]
+    struct Cached {
+      #[serde(with = "crate::flat")]
+      metadata: ArticleMetadata,
+    }
+
+    let mut article = ArticleMetadata::from_root(OgMetadata::default());
+    article.add_tag("rust");
+
+    let json = serde_json::to_string(&Cached { metadata: article }).unwrap();
+    assert_eq!(
+      json,
+      r#"{"metadata":[["og:type","article"],["article:tag","rust"]]}"#
+    );
+
+    let cached: Cached = serde_json::from_str(&json).unwrap();
+    assert_eq!(cached.metadata.tags(), ["rust"]);
+
+    let err = serde_json::from_str::<Flat<OgMetadata>>(r#"[["og:type","x"]]"#);
+    assert!(err.is_err());
+  }
+}
@@ -0,0 +1,680 @@
+//! Open Graph metadata from Markdown front matter.
+//!
+//! Static site generators store page metadata as front matter at the top
+//! of Markdown files:
+//!
+//! - YAML between `---` lines (Jekyll, Hugo, Eleventy), feature `yaml`
+//! - TOML between `+++` lines (Hugo, Zola), feature `toml`
+//! - JSON objects (Hugo)
+//!
+//! A `FrontMatterAdapter` maps the front matter of a page into
+//! `ArticleMetadata`, seeded with the site-wide defaults of a `SiteConfig`.
+//! The `FrontMatterMapping` lists the keys each property is read from and
+//! covers the conventions of Hugo, Jekyll, Zola and Eleventy by default,
+//! e.g. `lastmod`, `last_modified_at` and `updated` for the modified time.
+//! Nested keys are written as dotted paths, e.g. `taxonomies.tags`.
+//!
+//! Values are validated like values set on the builder directly.
+//! Published pages also need a title, a description and a URL; the
+//! description falls back to the first paragraph of the Markdown body.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ogp::config::SiteConfig;
+//! use ogp::frontmatter::FrontMatterAdapter;
+//!
+//! let site = SiteConfig::from_json_str(r#"{
+//!   "site_name": "OGP",
+//!   "canonical_host": "https://example.com"
+//! }"#).unwrap();
+//!
+//! let markdown = r#"{
+//!   "title": "Release notes",
+//!   "date": "2024-05-01",
+//!   "tags": ["rust", "seo"],
+//!   "image": "/images/cover.png"
+//! }
+//!
+//! The first release of the crate is out.
+//! "#;
+//!
+//! let adapter = FrontMatterAdapter::new(site);
+//! let page = adapter.page(markdown, Some("/news/release-notes")).unwrap();
+//! let metadata = page.metadata();
+//!
+//! assert_eq!(metadata.url.as_deref(), Some("https://example.com/news/release-notes"));
+//! assert_eq!(metadata.description.as_deref(), Some("The first release of the crate is out."));
+//! assert_eq!(page.article.tags(), ["rust", "seo"]);
+//! ```
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+  config::SiteConfig,
+  error::Error,
+  metadata::{Image, OgMetadata, OgMetadataBuilder},
+  object_type::{article::ArticleMetadata, retype::ObjectMetadata},
+  utils::{validate_http_url, validate_locale},
+  validator::Validatable,
+  Result,
+};
+
+/// The maximum length of a description taken from the Markdown body.
+const EXCERPT_MAX_CHARS: usize = 300;
+
+/// The front-matter keys each property is read from, in order of
+/// preference. Nested keys are written as dotted paths.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct FrontMatterMapping {
+  pub title: Vec<String>,
+  pub description: Vec<String>,
+  pub url: Vec<String>,
+  pub locale: Vec<String>,
+  pub image: Vec<String>,
+  pub published_time: Vec<String>,
+  pub modified_time: Vec<String>,
+  pub expiration_time: Vec<String>,
+  pub author: Vec<String>,
+  pub section: Vec<String>,
+  pub tags: Vec<String>,
+
+  /// Keys marking a page as draft when `true`.
+  pub draft: Vec<String>,
+
+  /// Keys marking a page as draft when `false`, e.g. Jekyll's `published`.
+  pub published: Vec<String>,
+
+  /// Whether to take the description from the first paragraph of the
+  /// body if none of the `description` keys is set.
+  pub excerpt_from_body: bool,
+}
+
+impl Default for FrontMatterMapping {
+  fn default() -> Self {
+    let keys = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect();
+
+    FrontMatterMapping {
+      title: keys(&["title"]),
+      description: keys(&["description", "summary", "excerpt"]),
+      url: keys(&["permalink", "url"]),
+      locale: keys(&["locale", "lang"]),
+      image: keys(&[
+        "image",
+        "images",
+        "cover.image",
+        "cover",
+        "featured_image",
+        "og_image",
+        "extra.image",
+      ]),
+      published_time: keys(&["date", "publishDate", "pubDate"]),
+      modified_time: keys(&["lastmod", "last_modified_at", "updated"]),
+      expiration_time: keys(&["expiryDate", "expires"]),
+      author: keys(&["author", "authors", "extra.author"]),
+      section: keys(&[
+        "section",
+        "category",
+        "categories",
+        "taxonomies.categories",
+      ]),
+      tags: keys(&["tags", "taxonomies.tags", "keywords"]),
+      draft: keys(&["draft"]),
+      published: keys(&["published"]),
+      excerpt_from_body: true,
+    }
+  }
+}
+
+/// A page parsed from Markdown with front matter.
+#[derive(Debug, Clone)]
+pub struct FrontMatterPage {
+  /// The article metadata of the page.
+  pub article: ArticleMetadata,
+
+  /// Whether the page is a draft.
+  pub draft: bool,
+}
+
+impl FrontMatterPage {
+  /// The metadata shared by all object types, e.g. title and URL.
+  pub fn metadata(&self) -> OgMetadata {
+    self.article.root()
+  }
+}
+
+/// Maps front matter of Markdown pages into Open Graph metadata.
+#[derive(Debug, Clone, Default)]
+pub struct FrontMatterAdapter {
+  pub mapping: FrontMatterMapping,
+  pub site: SiteConfig,
+}
+
+impl FrontMatterAdapter {
+  /// Creates an adapter with the default mapping.
+  pub fn new(site: SiteConfig) -> Self {
+    FrontMatterAdapter {
+      mapping: FrontMatterMapping::default(),
+      site,
+    }
+  }
+
+  pub fn with_mapping(mut self, mapping: FrontMatterMapping) -> Self {
+    self.mapping = mapping;
+    self
+  }
+
+  /// Parses a Markdown page and maps its front matter into
+  /// `ArticleMetadata`.
+  ///
+  /// `page_url` is the path or URL the page is published at. It is made
+  /// canonical with `SiteConfig::canonical_url` and takes precedence
+  /// over the `url` keys of the front matter.
+  pub fn page(
+    &self,
+    markdown: &str,
+    page_url: Option<&str>,
+  ) -> Result<FrontMatterPage> {
+    let (front_matter, body) = split(markdown)?;
+    self.map(&front_matter, body, page_url)
+  }
+
+  /// Maps parsed front matter into `ArticleMetadata`.
+  pub fn map(
+    &self,
+    front_matter: &Map<String, Value>,
+    body: &str,
+    page_url: Option<&str>,
+  ) -> Result<FrontMatterPage> {
+    let mapping = &self.mapping;
+    let string = |keys: &[String]| first_string(front_matter, keys);
+
+    let draft = lookup(front_matter, &mapping.draft)
+      .is_some_and(|value| value.as_bool() == Some(true))
+      || lookup(front_matter, &mapping.published)
+        .is_some_and(|value| value.as_bool() == Some(false));
+
+    let mut builder = OgMetadataBuilder::from_site_config(&self.site);
+
+    if let Some(title) = string(&mapping.title) {
+      builder.set_title(title);
+    }
+
+    let description = string(&mapping.description)
+      .or_else(|| mapping.excerpt_from_body.then(|| excerpt(body)).flatten());
+    if let Some(description) = description {
+      builder.set_description(description);
+    }
+
+    let url = page_url
+      .map(str::to_string)
+      .or_else(|| string(&mapping.url));
+    let url = match url {
+      Some(url) => Some(match self.site.canonical_host {
+        Some(_) => validate_http_url(&self.site.canonical_url(&url)?)?,
+        None => validate_http_url(&url)?,
+      }),
+      None => None,
+    };
+    if let Some(url) = &url {
+      builder.set_url(url.as_str());
+    }
+
+    if let Some(locale) = string(&mapping.locale) {
+      let locale = locale.replace('-', "_");
+      validate_locale(&locale)?;
+      builder.set_locale(locale);
+    }
+
+    if let Some(image) = string(&mapping.image) {
+      // Relative images are resolved against the page, not the site root.
+      let base = url.as_ref().or(self.site.canonical_host.as_ref());
+      let mut image = Image::from_str_with_base(&image, base)?;
+      image.infer_mimetype();
+      // The page image takes precedence over the site's fallback images.
+      let mut metadata = builder.get_metadata();
+      metadata.images = vec![image];
+      builder = metadata.into();
+    }
+
+    let mut article = builder.article();
+
+    if let Some(date) = string(&mapping.published_time) {
+      article.set_published_time(parse_date(&date)?.to_rfc3339());
+    }
+    if let Some(date) = string(&mapping.modified_time) {
+      article.set_modified_time(parse_date(&date)?.to_rfc3339());
+    }
+    if let Some(date) = string(&mapping.expiration_time) {
+      article.set_expiration_time(parse_date(&date)?.to_rfc3339());
+    }
+    if let Some(author) = string(&mapping.author) {
+      article.set_author(author);
+    }
+    if let Some(section) = string(&mapping.section) {
+      article.set_section(section);
+    }
+    if let Some(tags) = lookup(front_matter, &mapping.tags) {
+      for tag in strings(tags) {
+        article.add_tag(tag);
+      }
+    }
+
+    let metadata = article.root();
+    for image in &metadata.images {
+      image.validate()?;
+    }
+    if !draft {
+      metadata.validate()?;
+    }
+
+    Ok(FrontMatterPage { article, draft })
+  }
+}
+
+/// Splits a Markdown document into its front matter and body.
+///
+/// Documents without front matter have an empty front matter.
+pub fn split(markdown: &str) -> Result<(Map<String, Value>, &str)> {
+  let markdown = markdown.trim_start_matches('\u{feff}');
+  let first_line = markdown.lines().next().unwrap_or_default().trim_end();
+
+  let value = match first_line {
+    "---" => {
+      let (front_matter, body) = fenced(markdown, "---")?;
+      (parse_yaml(front_matter)?, body)
+    }
+    "+++" => {
+      let (front_matter, body) = fenced(markdown, "+++")?;
+      (parse_toml(front_matter)?, body)
+    }
+    _ if markdown.starts_with('{') => {
+      return Ok(json(markdown).unwrap_or((Map::new(), markdown)));
+    }
+    _ => return Ok((Map::new(), markdown)),
+  };
+
+  match value {
+    (Value::Object(front_matter), body) => Ok((front_matter, body)),
+    (Value::Null, body) => Ok((Map::new(), body)),
+    _ => Err(Error::InvalidFrontMatter(
+      "Front matter must be a map of keys and values".into(),
+    )),
+  }
+}
+
+/// Splits a document starting with a JSON object on its own line(s).
+///
+/// Returns `None` for other documents starting with `{`, e.g. with a Hugo
+/// shortcode (`{{< figure >}}`) or a Liquid tag (`{% raw %}`).
+fn json(markdown: &str) -> Option<(Map<String, Value>, &str)> {
+  let mut values = serde_json::Deserializer::from_str(markdown)
+    .into_iter::<Map<String, Value>>();
+  let front_matter = values.next()?.ok()?;
+  let body = &markdown[values.byte_offset()..];
+
+  let rest = body.trim_start_matches([' ', '\t', '\r']);
+  (rest.is_empty() || rest.starts_with('\n')).then_some((front_matter, body))
+}
+
+/// Splits a document whose front matter is fenced by `fence` lines.
+fn fenced<'a>(markdown: &'a str, fence: &str) -> Result<(&'a str, &'a str)> {
+  let rest = &markdown[markdown.find('\n').map_or(markdown.len(), |i| i + 1)..];
+
+  let mut offset = 0;
+  for line in rest.split_inclusive('\n') {
+    if line.trim_end() == fence {
+      return Ok((&rest[..offset], &rest[offset + line.len()..]));
+    }
+    offset += line.len();
+  }
+
+  Err(Error::InvalidFrontMatter(format!(
+    "Missing closing '{}' line",
+    fence
+  )))
+}
+
+#[cfg(feature = "yaml")]
+fn parse_yaml(front_matter: &str) -> Result<Value> {
+  serde_yaml::from_str(front_matter)
+    .map_err(|err| Error::InvalidFrontMatter(err.to_string()))
+}
+
+#[cfg(not(feature = "yaml"))]
+fn parse_yaml(_: &str) -> Result<Value> {
+  Err(Error::InvalidFrontMatter(
+    "YAML front matter requires the 'yaml' feature".into(),
+  ))
+}
+
+#[cfg(feature = "toml")]
+fn parse_toml(front_matter: &str) -> Result<Value> {
+  fn to_json(value: toml::Value) -> Value {
+    match value {
+      toml::Value::String(s) => Value::String(s),
+      toml::Value::Integer(i) => Value::from(i),
+      toml::Value::Float(f) => Value::from(f),
+      toml::Value::Boolean(b) => Value::Bool(b),
+      toml::Value::Datetime(dt) => Value::String(dt.to_string()),
+      toml::Value::Array(values) => {
+        Value::Array(values.into_iter().map(to_json).collect())
+      }
+      toml::Value::Table(table) => {
+        Value::Object(table.into_iter().map(|(k, v)| (k, to_json(v))).collect())
+      }
+    }
+  }
+
+  front_matter
+    .parse::<toml::Table>()
+    .map(|table| to_json(toml::Value::Table(table)))
+    .map_err(|err| Error::InvalidFrontMatter(err.to_string()))
+}
+
+#[cfg(not(feature = "toml"))]
+fn parse_toml(_: &str) -> Result<Value> {
+  Err(Error::InvalidFrontMatter(
+    "TOML front matter requires the 'toml' feature".into(),
+  ))
+}
+
+/// Returns the value of the first key that is set, following dotted paths.
+fn lookup<'a>(
+  front_matter: &'a Map<String, Value>,
+  keys: &[String],
+) -> Option<&'a Value> {
+  keys.iter().find_map(|key| {
+    let mut parts = key.split('.');
+    let mut value = front_matter.get(parts.next()?)?;
+    for part in parts {
+      value = value.get(part)?;
+    }
+    (!value.is_null()).then_some(value)
+  })
+}
+
+/// Returns the first string value of the first key that is set.
+///
+/// Lists yield their first item, and maps their `name`, `url` or `src`,
+/// e.g. an Eleventy author `{ name: "Jane" }` or a Hugo `cover.image`.
+fn first_string(
+  front_matter: &Map<String, Value>,
+  keys: &[String],
+) -> Option<String> {
+  lookup(front_matter, keys).and_then(|value| strings(value).next())
+}
+
+/// Returns all string values, flattening lists.
+fn strings(value: &Value) -> Box<dyn Iterator<Item = String> + '_> {
+  match value {
+    Value::String(s) if !s.trim().is_empty() => {
+      Box::new(std::iter::once(s.trim().to_string()))
+    }
+    Value::Number(n) => Box::new(std::iter::once(n.to_string())),
+    Value::Array(values) => Box::new(values.iter().flat_map(strings)),
+    Value::Object(map) => Box::new(
+      ["name", "url", "src"]
+        .into_iter()
+        .find_map(|key| map.get(key))
+        .into_iter()
+        .flat_map(strings),
+    ),
+    _ => Box::new(std::iter::empty()),
+  }
+}
+
+/// Parses the date formats used in front matter: RFC 3339, Jekyll's
+/// `2024-05-01 10:00:00 +0200`, and dates with or without time.
+/// Dates without an offset are taken as UTC.
+fn parse_date(date: &str) -> Result<DateTime<Utc>> {
+  let date = date.trim();
+
+  DateTime::parse_from_rfc3339(date)
+    .or_else(|_| DateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S %z"))
+    .map(|date| date.with_timezone(&Utc))
+    .or_else(|_| {
+      NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S"))
+        .map(|date| date.and_utc())
+    })
+    .or_else(|_| {
+      NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+    })
+    .map_err(|_| Error::InvalidFrontMatter(format!("Invalid date '{}'", date)))
+}
+
+/// Returns the first paragraph of a Markdown body as plain text,
+/// skipping headings, images and HTML.
+fn excerpt(body: &str) -> Option<String> {
+  let paragraph = body
+    .split("\n\n")
+    .map(str::trim)
+    .find(|p| !p.is_empty() && !p.starts_with(['#', '!', '<', '`', '|']))?;
+
+  let mut text = String::new();
+  let mut chars = paragraph.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '*' | '_' | '`' | '[' => {}
+      // Drops the target of a link, e.g. `[text](url)`.
+      ']' if chars.peek() == Some(&'(') => {
+        for c in chars.by_ref() {
+          if c == ')' {
+            break;
+          }
+        }
+      }
+      ']' => {}
+      '\n' => text.push(' '),
+      c => text.push(c),
+    }
+  }
+
+  let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+  if text.chars().count() <= EXCERPT_MAX_CHARS {
+    return Some(text);
+  }
+
+  let truncated: String = text.chars().take(EXCERPT_MAX_CHARS).collect();
+  let truncated = truncated
+    .rsplit_once(' ')
+    .map_or(truncated.as_str(), |(words, _)| words);
+  Some(format!("{}…", truncated))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn adapter() -> FrontMatterAdapter {
+    FrontMatterAdapter::new(
+      SiteConfig::from_json_str(
+        r#"{
+          "site_name": "OGP",
+          "images": [{ "url": "https://example.com/logo.png" }],
+          "canonical_host": "https://example.com"
+        }"#,
+      )
+      .unwrap(),
+    )
+  }
+
+  #[test]
+  fn maps_json_front_matter() {
+    let page = adapter()
+      .page(
+        r#"{
+          "title": "Release notes",
+          "summary": "What's new",
+          "permalink": "/news/?utm_source=feed",
+          "lastmod": "2024-05-02 10:00:00 +0200",
+          "authors": [{ "name": "Jane Doe" }],
+          "taxonomies": { "tags": ["rust"], "categories": ["News"] }
+        }"#,
+        None,
+      )
+      .unwrap();
+
+    let metadata = page.metadata();
+    assert!(!page.draft);
+    assert_eq!(metadata.description.as_deref(), Some("What's new"));
+    assert_eq!(metadata.url.as_deref(), Some("https://example.com/news/"));
+    assert_eq!(metadata.site_name.as_deref(), Some("OGP"));
+    assert_eq!(metadata.images.len(), 1);
+    assert_eq!(page.article.author(), Some("Jane Doe"));
+    assert_eq!(page.article.section(), Some("News"));
+    assert_eq!(page.article.tags(), ["rust"]);
+    assert_eq!(
+      page
+        .article
+        .modified_time()
+        .map(|d| d.to_rfc3339())
+        .as_deref(),
+      Some("2024-05-02T08:00:00+00:00")
+    );
+  }
+
+  #[test]
+  fn replaces_fallback_image_and_takes_excerpt() {
+    let page = adapter()
+      .page(
+        "{\"title\": \"Post\", \"image\": \"cover.png\"}\n\n# Heading\n\n\
+        Read the *full* [story](https://example.com).\nIt is long.\n\nMore.",
+        Some("/blog/post/"),
+      )
+      .unwrap();
+
+    let metadata = page.metadata();
+    assert_eq!(
+      metadata.description.as_deref(),
+      Some("Read the full story. It is long.")
+    );
+    assert_eq!(metadata.images.len(), 1);
+    assert_eq!(
+      metadata.images[0].url.as_ref().map(|url| url.as_str()),
+      Some("https://example.com/blog/post/cover.png")
+    );
+  }
+
+  #[test]
+  fn resolves_image_against_page_url_without_canonical_host() {
+    let adapter = FrontMatterAdapter::new(
+      SiteConfig::from_json_str(r#"{ "site_name": "OGP" }"#).unwrap(),
+    );
+    let page = adapter
+      .page(
+        r#"{ "title": "Post", "description": "D", "image": "cover.png" }"#,
+        Some("https://example.org/blog/post/"),
+      )
+      .unwrap();
+    assert_eq!(
+      page.metadata().images[0]
+        .url
+        .as_ref()
+        .map(|url| url.as_str()),
+      Some("https://example.org/blog/post/cover.png")
+    );
+
+    let err = adapter
+      .page(
+        r#"{ "title": "Post", "description": "D", "image": "cover.png" }"#,
+        None,
+      )
+      .unwrap_err();
+    assert!(matches!(err, Error::UrlParseError(_)));
+  }
+
+  #[test]
+  fn validates_published_pages_only() {
+    let err = adapter().page(r#"{ "title": "Post" }"#, None).unwrap_err();
+    assert!(matches!(err, Error::MissingRequiredProperty(_)));
+
+    let page = adapter()
+      .page(r#"{ "title": "Post", "published": false }"#, None)
+      .unwrap();
+    assert!(page.draft);
+
+    let err = adapter().page(r#"{ "date": "yesterday" }"#, None);
+    assert!(matches!(err, Err(Error::InvalidFrontMatter(_))));
+  }
+
+  #[test]
+  fn splits_documents_without_front_matter() {
+    let (front_matter, body) = split("# Title\n\nText").unwrap();
+    assert!(front_matter.is_empty());
+    assert_eq!(body, "# Title\n\nText");
+
+    assert!(split("+++\ntitle = 'x'\n").is_err());
+
+    for markdown in [
+      "{{< figure src=\"a.png\" >}}\n\nText",
+      "{% raw %}{{ x }}{% endraw %}\n",
+      "{\"a\": 1} is an object\n",
+    ] {
+      let (front_matter, body) = split(markdown).unwrap();
+      assert!(front_matter.is_empty());
+      assert_eq!(body, markdown);
+    }
+  }
+
+  #[test]
+  fn splits_json_front_matter() {
+    let (front_matter, body) =
+      split("{\n  \"title\": \"Hello\"\n}  \n# Hello\n").unwrap();
+    assert_eq!(front_matter["title"], "Hello");
+    assert_eq!(body, "  \n# Hello\n");
+  }
+
+  #[test]
+  fn parses_front_matter_dates() {
+    let date = |s| parse_date(s).unwrap().to_rfc3339();
+    assert_eq!(date("2024-05-01"), "2024-05-01T00:00:00+00:00");
+    assert_eq!(date("2024-05-01T10:00:00"), "2024-05-01T10:00:00+00:00");
+    assert_eq!(
+      date("2024-05-01T10:00:00-02:00"),
+      "2024-05-01T12:00:00+00:00"
+    );
+  }
+
+  #[cfg(feature = "yaml")]
+  #[test]
+  fn maps_yaml_front_matter() {
+    let page = adapter()
+      .page(
+        "---\ntitle: Jekyll post\ndescription: A post\ncategories: [News]\n\
+        draft: true\n---\nBody\n",
+        Some("/jekyll"),
+      )
+      .unwrap();
+    assert!(page.draft);
+    assert_eq!(page.metadata().title.as_deref(), Some("Jekyll post"));
+    assert_eq!(page.article.section(), Some("News"));
+  }
+
+  #[cfg(feature = "toml")]
+  #[test]
+  fn maps_toml_front_matter() {
+    let page = adapter()
+      .page(
+        "+++\ntitle = \"Zola post\"\ndescription = \"A post\"\n\
+        date = 2024-05-01\n[taxonomies]\ntags = [\"rust\"]\n+++\nBody\n",
+        Some("/zola"),
+      )
+      .unwrap();
+    assert_eq!(page.metadata().title.as_deref(), Some("Zola post"));
+    assert_eq!(page.article.tags(), ["rust"]);
+    assert_eq!(
+      page
+        .article
+        .published_time()
+        .map(|d| d.to_rfc3339())
+        .as_deref(),
+      Some("2024-05-01T00:00:00+00:00")
+    );
+  }
+}
@@ -0,0 +1,363 @@
+//! Injecting Open Graph meta tags into HTML documents.
+//!
+//! A `TagInjector` renders properties as `<meta property=".." content="..">`
+//! tags and splices them into the `<head>` of an existing document, e.g. a
+//! rendered template. Existing Open Graph tags are kept by default; with
+//! `replace_existing` they are removed first, so the injected properties
+//! are the only ones on the page. `fb:` properties are always kept.
+//!
+//! The injector is framework-agnostic. The web framework integrations, such
+//! as the `tower` middleware, are built on top of it.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ogp::inject::TagInjector;
+//! use ogp::metadata::OgMetadata;
+//!
+//! let metadata = OgMetadata {
+//!   title: Some("The Rock".into()),
+//!   ..Default::default()
+//! };
+//!
+//! let html = "<html><head><title>The Rock</title></head></html>";
+//! let html = TagInjector::new().inject_metadata(html, &metadata).unwrap();
+//! assert!(html.contains(r#"<meta property="og:title" content="The Rock" />"#));
+//! ```
+
+use std::ops::Range;
+
+use crate::{
+  convert::meta_tag, extract::html, flat::FlatProperties, metadata::OgMetadata,
+};
+
+/// Property namespaces of tags removed by `replace_existing`.
+const REPLACED_NAMESPACES: [&str; 6] =
+  ["og:", "article:", "book:", "profile:", "music:", "video:"];
+
+/// Properties to inject, in the flat representation.
+///
+/// The web framework integrations read `OgTags` from response extensions.
+/// Use it for object types, e.g. `ArticleMetadata`, whose type-specific
+/// properties are not part of `OgMetadata`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OgTags(pub Vec<(String, String)>);
+
+impl OgTags {
+  /// Creates tags from the properties of `value`.
+  pub fn new<T: FlatProperties>(value: &T) -> Self {
+    Self(value.to_properties())
+  }
+}
+
+/// Renders properties as meta tags, one per line.
+pub fn render_tags(properties: &[(String, String)]) -> String {
+  properties
+    .iter()
+    .map(|(property, content)| meta_tag(property, content))
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// Splices meta tags into the `<head>` of HTML documents.
+#[derive(Debug, Clone, Default)]
+pub struct TagInjector {
+  replace_existing: bool,
+}
+
+impl TagInjector {
+  /// Creates an injector that keeps existing Open Graph tags.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Removes the document's existing Open Graph tags before injecting.
+  pub fn replace_existing(mut self, replace: bool) -> Self {
+    self.replace_existing = replace;
+    self
+  }
+
+  /// Injects the properties of `value` into `html`.
+  ///
+  /// Returns `None` if the document has no `<head>` before its `<body>`.
+  pub fn inject_metadata<T: FlatProperties>(
+    &self,
+    html: &str,
+    value: &T,
+  ) -> Option<String> {
+    self.inject(html, &value.to_properties())
+  }
+
+  /// Injects the properties into `html`, right before `</head>`.
+  ///
+  /// Returns `None` if the document has no `<head>` before its `<body>`.
+  pub fn inject(
+    &self,
+    html: &str,
+    properties: &[(String, String)],
+  ) -> Option<String> {
+    let document = html::scan_document(html);
+    let tags = &document.tags;
+    let head = tags.iter().find(|tag| tag.name == "head")?;
+    let body_start = tags
+      .iter()
+      .find(|tag| tag.name == "body")
+      .map_or(html.len(), |tag| tag.span.start);
+    if head.span.end > body_start {
+      return None;
+    }
+
+    // Insert before `</head>`, or before `<body>` if it is omitted.
+    let insert_at = document
+      .end_tags
+      .iter()
+      .find(|tag| {
+        tag.name == "head"
+          && tag.span.start >= head.span.end
+          && tag.span.start <= body_start
+      })
+      .map_or(body_start, |tag| tag.span.start);
+
+    let mut removed: Vec<Range<usize>> = Vec::new();
+    if self.replace_existing {
+      removed = tags
+        .iter()
+        .filter(|tag| {
+          tag.name == "meta"
+            && tag.span.start >= head.span.end
+            && tag.span.end <= insert_at
+            && is_replaced(tag)
+        })
+        .map(|tag| line_span(html, &tag.span))
+        .collect();
+    }
+
+    let mut output = String::with_capacity(html.len() + properties.len() * 64);
+    let mut pos = 0;
+    for span in &removed {
+      output.push_str(&html[pos..span.start]);
+      pos = span.end;
+    }
+    output.push_str(&html[pos..insert_at]);
+
+    if !properties.is_empty() {
+      let tags = render_tags(properties);
+      match indentation(&output) {
+        // `</head>` is on its own line: indent the tags like the line
+        // before it, usually the last child of `<head>`.
+        Some(indent) => {
+          let indent = indent.to_string();
+          output.truncate(output.len() - indent.len());
+          let child = previous_indentation(&output).to_string();
+          for tag in tags.lines() {
+            output.push_str(&format!("{child}{tag}\n"));
+          }
+          output.push_str(&indent);
+        }
+        None => output.push_str(&tags.replace('\n', "")),
+      }
+    }
+
+    output.push_str(&html[insert_at..]);
+    Some(output)
+  }
+}
+
+/// The properties to inject into a response, taken from its `OgTags` or,
+/// failing that, its `OgMetadata` extension.
+pub(crate) fn take_properties(
+  tags: Option<OgTags>,
+  metadata: Option<OgMetadata>,
+) -> Option<Vec<(String, String)>> {
+  tags
+    .map(|tags| tags.0)
+    .or_else(|| metadata.map(|metadata| metadata.to_properties()))
+}
+
+/// Whether a response with the given `Content-Type` and `Content-Encoding`
+/// is an uncompressed HTML document, i.e. can be injected into.
+pub(crate) fn is_html(
+  content_type: Option<&str>,
+  content_encoding: Option<&str>,
+) -> bool {
+  let is_html = content_type.is_some_and(|value| {
+    value
+      .trim_start()
+      .to_ascii_lowercase()
+      .starts_with("text/html")
+  });
+  let is_encoded = content_encoding.is_some_and(|value| value != "identity");
+  is_html && !is_encoded
+}
+
+/// Whether `tag` declares a property replaced by `replace_existing`.
+fn is_replaced(tag: &html::Tag) -> bool {
+  tag
+    .attr("property")
+    .or_else(|| tag.attr("name"))
+    .map(|property| property.trim().to_ascii_lowercase())
+    .is_some_and(|property| {
+      REPLACED_NAMESPACES
+        .iter()
+        .any(|namespace| property.starts_with(namespace))
+    })
+}
+
+/// Extends `span` to its whole line if the tag is the only thing on it.
+fn line_span(html: &str, span: &Range<usize>) -> Range<usize> {
+  let line_start = html[..span.start].rfind('\n').map_or(0, |i| i + 1);
+  let line_end = html[span.end..].find('\n').map(|i| span.end + i + 1);
+
+  match line_end {
+    Some(line_end)
+      if html[line_start..span.start].trim().is_empty()
+        && html[span.end..line_end].trim().is_empty() =>
+    {
+      line_start..line_end
+    }
+    _ => span.clone(),
+  }
+}
+
+/// The indentation of the last line of `html`, if it has nothing else on it.
+fn indentation(html: &str) -> Option<&str> {
+  let (_, line) = html.rsplit_once('\n')?;
+  line.trim().is_empty().then_some(line)
+}
+
+/// The leading whitespace of the last line of `html`, which ends with `\n`.
+fn previous_indentation(html: &str) -> &str {
+  let html = html.strip_suffix('\n').unwrap_or(html);
+  let line = html.rsplit('\n').next().unwrap_or_default();
+  &line[..line.len() - line.trim_start().len()]
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use super::*;
+
+  /// A page with an Open Graph tag, shared by the framework integrations.
+  pub(crate) const PAGE: &str = "<html><head>\
+    <meta property=\"og:title\" content=\"Template\">\
+    </head><body></body></html>";
+
+  /// `PAGE` with `metadata()` injected.
+  pub(crate) const INJECTED: &str = "<html><head>\
+    <meta property=\"og:title\" content=\"Template\">\
+    <meta property=\"og:type\" content=\"website\" />\
+    <meta property=\"og:title\" content=\"Home &amp; Away\" />\
+    </head><body></body></html>";
+
+  pub(crate) fn metadata() -> OgMetadata {
+    OgMetadata {
+      title: Some("Home & Away".into()),
+      ..Default::default()
+    }
+  }
+
+  fn properties(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+      .iter()
+      .map(|(property, content)| (property.to_string(), content.to_string()))
+      .collect()
+  }
+
+  #[test]
+  fn renders_escaped_tags() {
+    let tags = render_tags(&properties(&[
+      ("og:title", r#"Tom & "Jerry""#),
+      ("og:image:width", "1200"),
+    ]));
+    assert_eq!(
+      tags,
+      "<meta property=\"og:title\" content=\"Tom &amp; &quot;Jerry&quot;\" />\n\
+       <meta property=\"og:image:width\" content=\"1200\" />"
+    );
+  }
+
+  #[test]
+  fn injects_before_closing_head() {
+    let html = "<html>\n  <head>\n    <title>Home</title>\n  </head>\n</html>";
+    let injected = TagInjector::new()
+      .inject(
+        html,
+        &properties(&[("og:title", "Home"), ("og:type", "website")]),
+      )
+      .unwrap();
+    assert_eq!(
+      injected,
+      "<html>\n  <head>\n    <title>Home</title>\n    \
+       <meta property=\"og:title\" content=\"Home\" />\n    \
+       <meta property=\"og:type\" content=\"website\" />\n  </head>\n</html>"
+    );
+
+    let html = "<head><title>Home</title></head><body></body>";
+    let injected = TagInjector::new()
+      .inject(html, &properties(&[("og:title", "Home")]))
+      .unwrap();
+    assert_eq!(
+      injected,
+      "<head><title>Home</title>\
+       <meta property=\"og:title\" content=\"Home\" /></head><body></body>"
+    );
+
+    assert!(TagInjector::new().inject("<p>Hi</p>", &[]).is_none());
+  }
+
+  #[test]
+  fn ignores_closing_head_in_raw_text() {
+    let html = "<head><script>var a = '</head>';</script></head><body></body>";
+    let injected = TagInjector::new()
+      .inject(html, &properties(&[("og:title", "Home")]))
+      .unwrap();
+    assert_eq!(
+      injected,
+      "<head><script>var a = '</head>';</script>\
+       <meta property=\"og:title\" content=\"Home\" /></head><body></body>"
+    );
+  }
+
+  #[test]
+  fn rejects_head_after_body() {
+    let html = "<body></body><head></head>";
+    let injected =
+      TagInjector::new().inject(html, &properties(&[("og:title", "Home")]));
+    assert!(injected.is_none());
+  }
+
+  #[test]
+  fn detects_uncompressed_html() {
+    assert!(is_html(Some("Text/HTML; charset=utf-8"), None));
+    assert!(is_html(Some("text/html"), Some("identity")));
+    assert!(!is_html(Some("text/html"), Some("gzip")));
+    assert!(!is_html(Some("text/plain"), None));
+    assert!(!is_html(None, None));
+  }
+
+  #[test]
+  fn replaces_existing_tags() {
+    let html = "<head>\n  <meta property=\"og:title\" content=\"Old\">\n  \
+                <meta property=\"fb:app_id\" content=\"1\">\n  \
+                <meta name=\"article:tag\" content=\"old\">\n</head>\n\
+                <body><meta property=\"og:title\" content=\"Body\"></body>";
+    let metadata = OgMetadata {
+      title: Some("New".into()),
+      ..Default::default()
+    };
+
+    let kept = TagInjector::new().inject_metadata(html, &metadata).unwrap();
+    assert!(kept.contains("content=\"Old\""));
+
+    let replaced = TagInjector::new()
+      .replace_existing(true)
+      .inject_metadata(html, &metadata)
+      .unwrap();
+    assert_eq!(
+      replaced,
+      "<head>\n  <meta property=\"fb:app_id\" content=\"1\">\n  \
+       <meta property=\"og:type\" content=\"website\" />\n  \
+       <meta property=\"og:title\" content=\"New\" />\n</head>\n\
+       <body><meta property=\"og:title\" content=\"Body\"></body>"
+    );
+  }
+}
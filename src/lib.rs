@@ -1,9 +1,33 @@
 #![allow(dead_code, unused)]
 
+#[cfg(feature = "actix")]
+pub mod actix;
+pub mod canonical;
+pub mod config;
 pub mod convert;
+pub mod diff;
 pub mod error;
+pub mod extract;
+#[cfg(feature = "fetch")]
+pub mod fetch;
+pub mod flat;
+pub mod frontmatter;
+pub mod inject;
+pub mod lint;
+pub mod merge;
 pub mod metadata;
+#[cfg(feature = "tower")]
+pub mod middleware;
 pub mod object_type;
+pub mod platform;
+pub mod preview;
+#[cfg(feature = "probe")]
+pub mod probe;
+#[cfg(feature = "schemars")]
+pub mod schema;
+pub mod site;
+pub mod template;
+pub mod unfurl;
 mod utils;
 pub mod validator;
 
@@ -0,0 +1,606 @@
+//! Linting of Open Graph markup in built HTML pages.
+//!
+//! `lint_html` checks a single page: the markup is extracted, the
+//! metadata and its media are validated, the canonical URL is checked for
+//! decorations and, optionally, the rules of platforms are applied.
+//!
+//! `lint_dir` checks every `*.html` file below a directory in parallel,
+//! e.g. the output of a static site build. The resulting `LintReport`
+//! renders a human readable summary as well as JSON, SARIF and JUnit XML
+//! reports for CI systems.
+//!
+//! # Usage
+//!
+//! ```rust,no_run
+//! use ogp::lint::{lint_dir, LintOptions};
+//!
+//! let report = lint_dir("dist", &LintOptions::default()).unwrap();
+//! std::fs::write("ogp.sarif", report.to_sarif()).unwrap();
+//!
+//! println!("{}", report.summary());
+//! if !report.is_ok() {
+//!   std::process::exit(1);
+//! }
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::json;
+use url::Url;
+
+use crate::{
+  canonical::UrlNormalizer,
+  error::Error,
+  extract::{Extractor, ParseMode},
+  platform::{validate_for, Platform, Severity},
+  utils::escape_html,
+  validator::Validatable,
+  Result,
+};
+
+/// What to check on each page.
+#[derive(Debug, Clone, Default)]
+pub struct LintOptions {
+  /// How to treat markup mistakes. In lenient mode they are reported as
+  /// warnings, in strict mode the first one is an error.
+  pub mode: ParseMode,
+
+  /// The URL pages are served from, to resolve relative URLs. For
+  /// `lint_dir`, the path of each file is resolved against it.
+  pub base_url: Option<Url>,
+
+  /// Platforms whose rules are applied, too.
+  pub platforms: Vec<Platform>,
+
+  /// Whether all URLs must use https.
+  pub https_only: bool,
+
+  /// Whether warnings fail the lint, too.
+  pub deny_warnings: bool,
+}
+
+/// A problem found on a page.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+  /// The ID of the check that failed, e.g. `required-property`.
+  pub rule: String,
+
+  pub severity: Severity,
+
+  /// A human readable description of the problem.
+  pub message: String,
+
+  /// The line of the page the problem was found on, if known.
+  pub line: Option<usize>,
+}
+
+/// The diagnostics of a single page.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PageReport {
+  /// The path of the page, relative to the linted directory.
+  pub path: PathBuf,
+
+  pub diagnostics: Vec<Diagnostic>,
+}
+
+impl PageReport {
+  pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+    self
+      .diagnostics
+      .iter()
+      .filter(|d| d.severity == Severity::Error)
+  }
+
+  pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+    self
+      .diagnostics
+      .iter()
+      .filter(|d| d.severity == Severity::Warning)
+  }
+}
+
+/// The diagnostics of all linted pages.
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct LintReport {
+  pub pages: Vec<PageReport>,
+
+  /// Whether warnings fail the lint, too.
+  #[serde(skip)]
+  pub deny_warnings: bool,
+}
+
+/// Lints the Open Graph markup of a single page.
+pub fn lint_html(html: &str, options: &LintOptions) -> Vec<Diagnostic> {
+  lint_page(html, options.base_url.clone(), options)
+}
+
+/// Lints every `*.html` file below `dir` in parallel.
+pub fn lint_dir(
+  dir: impl AsRef<Path>,
+  options: &LintOptions,
+) -> Result<LintReport> {
+  let dir = dir.as_ref();
+  let mut paths = Vec::new();
+  find_html_files(dir, &mut paths)?;
+  paths.sort();
+
+  let threads = std::thread::available_parallelism()
+    .map_or(1, usize::from)
+    .min(paths.len().max(1));
+  let chunk_size = paths.len().div_ceil(threads).max(1);
+
+  let pages = std::thread::scope(|scope| {
+    let workers: Vec<_> = paths
+      .chunks(chunk_size)
+      .map(|chunk| {
+        scope.spawn(move || {
+          chunk
+            .iter()
+            .map(|path| lint_file(dir, path, options))
+            .collect::<Vec<_>>()
+        })
+      })
+      .collect();
+
+    workers
+      .into_iter()
+      .map(|worker| {
+        worker
+          .join()
+          .map_err(|_| Error::Generic("Lint worker panicked".into()))
+      })
+      .collect::<Result<Vec<_>>>()
+  })?;
+
+  Ok(LintReport {
+    pages: pages.into_iter().flatten().collect(),
+    deny_warnings: options.deny_warnings,
+  })
+}
+
+fn find_html_files(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+  for entry in std::fs::read_dir(dir)? {
+    let entry = entry?;
+    let path = entry.path();
+    let file_type = entry.file_type()?;
+    if file_type.is_dir() {
+      find_html_files(&path, paths)?;
+    } else if file_type.is_symlink() && path.is_dir() {
+      // Symlinked directories are not followed, as they may form a cycle.
+      continue;
+    } else if path
+      .extension()
+      .is_some_and(|ext| ext.eq_ignore_ascii_case("html"))
+    {
+      paths.push(path);
+    }
+  }
+  Ok(())
+}
+
+/// Lints a single file. A file that cannot be read, e.g. because it is not
+/// valid UTF-8, is reported as an error instead of failing the whole lint.
+fn lint_file(dir: &Path, path: &Path, options: &LintOptions) -> PageReport {
+  let relative = path.strip_prefix(dir).unwrap_or(path).to_path_buf();
+  let html = match std::fs::read_to_string(path) {
+    Ok(html) => html,
+    Err(err) => {
+      return PageReport {
+        diagnostics: vec![Diagnostic {
+          rule: "read-error".into(),
+          severity: Severity::Error,
+          message: format!("Failed to read {}: {}", path.display(), err),
+          line: None,
+        }],
+        path: relative,
+      };
+    }
+  };
+
+  let page_url = options.base_url.as_ref().and_then(|base| {
+    let path = relative
+      .components()
+      .map(|c| c.as_os_str().to_string_lossy())
+      .collect::<Vec<_>>()
+      .join("/");
+    base.join(&path).ok()
+  });
+
+  PageReport {
+    diagnostics: lint_page(&html, page_url, options),
+    path: relative,
+  }
+}
+
+fn lint_page(
+  html: &str,
+  page_url: Option<Url>,
+  options: &LintOptions,
+) -> Vec<Diagnostic> {
+  let mut diagnostics = Vec::new();
+  let mut report = |rule: &str, severity, property: Option<&str>, message| {
+    diagnostics.push(Diagnostic {
+      rule: rule.into(),
+      severity,
+      message,
+      line: property.and_then(|property| line_of(html, property)),
+    });
+  };
+
+  let mut extractor = Extractor::new(options.mode);
+  if let Some(page_url) = page_url {
+    extractor.set_page_url(page_url);
+  }
+
+  let extraction = match extractor.extract(html) {
+    Ok(extraction) => extraction,
+    Err(err) => {
+      report("markup", Severity::Error, None, err.to_string());
+      return diagnostics;
+    }
+  };
+  let metadata = &extraction.metadata;
+
+  for warning in &extraction.warnings {
+    let property = Some(warning.property.as_str());
+    report("markup", Severity::Warning, property, warning.to_string());
+  }
+
+  for property in metadata.missing_properties() {
+    let err = Error::MissingRequiredProperty(property.into());
+    report("required-property", Severity::Error, None, err.to_string());
+  }
+
+  let media = metadata
+    .images
+    .iter()
+    .map(|image| ("og:image", image.validate()))
+    .chain(metadata.videos.iter().map(|v| ("og:video", v.validate())))
+    .chain(metadata.audios.iter().map(|a| ("og:audio", a.validate())));
+  for (property, result) in media {
+    if let Err(err) = result {
+      report(
+        "invalid-media",
+        Severity::Error,
+        Some(property),
+        err.to_string(),
+      );
+    }
+  }
+
+  if let Err(err) = UrlNormalizer::default().lint(metadata) {
+    report(
+      "canonical-url",
+      Severity::Warning,
+      Some("og:url"),
+      err.to_string(),
+    );
+  }
+
+  if options.https_only {
+    if let Err(err) = metadata.validate_https_only() {
+      report("https-only", Severity::Error, None, err.to_string());
+    }
+  }
+
+  for platform_report in validate_for(metadata, &options.platforms) {
+    let rule = format!("platform/{}", platform_report.platform).to_lowercase();
+    for finding in &platform_report.findings {
+      report(
+        &rule,
+        finding.severity,
+        Some(finding.property.as_str()),
+        format!("{}: {}", platform_report.platform, finding.message),
+      );
+    }
+  }
+
+  diagnostics
+}
+
+/// Returns the 1-based line the meta tag of `property` is declared on.
+fn line_of(html: &str, property: &str) -> Option<usize> {
+  let needle = format!("\"{}\"", property);
+  let offset = html
+    .find(&needle)
+    .or_else(|| html.find(&needle.replace('"', "'")))?;
+  Some(html[..offset].matches('\n').count() + 1)
+}
+
+impl LintReport {
+  pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+    self.pages.iter().flat_map(PageReport::errors)
+  }
+
+  pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+    self.pages.iter().flat_map(PageReport::warnings)
+  }
+
+  /// Checks whether the lint passed, honoring `deny_warnings`.
+  pub fn is_ok(&self) -> bool {
+    self.errors().next().is_none()
+      && !(self.deny_warnings && self.warnings().next().is_some())
+  }
+
+  /// A human readable report, one line per diagnostic, followed by totals.
+  pub fn summary(&self) -> String {
+    let mut lines = Vec::new();
+    for page in &self.pages {
+      for diagnostic in &page.diagnostics {
+        let severity = match diagnostic.severity {
+          Severity::Error => "error",
+          Severity::Warning => "warning",
+          Severity::Hint => "hint",
+        };
+        let location = match diagnostic.line {
+          Some(line) => format!("{}:{}", page.path.display(), line),
+          None => page.path.display().to_string(),
+        };
+        lines.push(format!(
+          "{}: {}: {} [{}]",
+          location, severity, diagnostic.message, diagnostic.rule
+        ));
+      }
+    }
+
+    let failed = self
+      .pages
+      .iter()
+      .filter(|page| page.errors().next().is_some())
+      .count();
+    lines.push(format!(
+      "{} page(s) checked, {} failed: {} error(s), {} warning(s)",
+      self.pages.len(),
+      failed,
+      self.errors().count(),
+      self.warnings().count()
+    ));
+    lines.join("\n")
+  }
+
+  /// A JSON report of all pages and their diagnostics.
+  pub fn to_json(&self) -> String {
+    serde_json::to_string_pretty(self).unwrap_or_default()
+  }
+
+  /// A SARIF 2.1.0 report, e.g. for GitHub code scanning.
+  pub fn to_sarif(&self) -> String {
+    let mut rules: Vec<&str> = self
+      .pages
+      .iter()
+      .flat_map(|page| page.diagnostics.iter().map(|d| d.rule.as_str()))
+      .collect();
+    rules.sort();
+    rules.dedup();
+
+    let results: Vec<_> = self
+      .pages
+      .iter()
+      .flat_map(|page| {
+        page.diagnostics.iter().map(move |diagnostic| {
+          let mut location = json!({
+            "artifactLocation": { "uri": uri(&page.path) }
+          });
+          if let Some(line) = diagnostic.line {
+            location["region"] = json!({ "startLine": line });
+          }
+
+          json!({
+            "ruleId": diagnostic.rule,
+            "level": match diagnostic.severity {
+              Severity::Error => "error",
+              Severity::Warning => "warning",
+              Severity::Hint => "note",
+            },
+            "message": { "text": diagnostic.message },
+            "locations": [{ "physicalLocation": location }],
+          })
+        })
+      })
+      .collect();
+
+    let sarif = json!({
+      "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+      "version": "2.1.0",
+      "runs": [{
+        "tool": {
+          "driver": {
+            "name": "ogp",
+            "version": env!("CARGO_PKG_VERSION"),
+            "informationUri": env!("CARGO_PKG_REPOSITORY"),
+            "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+          }
+        },
+        "results": results,
+      }],
+    });
+
+    serde_json::to_string_pretty(&sarif).unwrap_or_default()
+  }
+
+  /// A JUnit XML report with one test case per page.
+  pub fn to_junit(&self) -> String {
+    let failures = self
+      .pages
+      .iter()
+      .filter(|page| page.errors().next().is_some())
+      .count();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+      "<testsuites name=\"ogp\" tests=\"{0}\" failures=\"{1}\">\n\
+      \x20 <testsuite name=\"ogp\" tests=\"{0}\" failures=\"{1}\">\n",
+      self.pages.len(),
+      failures
+    ));
+
+    for page in &self.pages {
+      let name = escape_html(&uri(&page.path));
+      let errors: Vec<&Diagnostic> = page.errors().collect();
+      let warnings: Vec<&Diagnostic> = page.warnings().collect();
+      let format = |diagnostics: &[&Diagnostic]| {
+        diagnostics
+          .iter()
+          .map(|d| escape_html(&format!("{} [{}]", d.message, d.rule)))
+          .collect::<Vec<_>>()
+          .join("\n")
+      };
+
+      xml.push_str(&format!(
+        "    <testcase classname=\"ogp\" name=\"{}\"",
+        name
+      ));
+      if errors.is_empty() && warnings.is_empty() {
+        xml.push_str(" />\n");
+        continue;
+      }
+
+      xml.push_str(">\n");
+      if let Some(first) = errors.first() {
+        xml.push_str(&format!(
+          "      <failure message=\"{}\" type=\"error\">{}</failure>\n",
+          escape_html(&first.message),
+          format(&errors)
+        ));
+      }
+      if !warnings.is_empty() {
+        xml.push_str(&format!(
+          "      <system-out>{}</system-out>\n",
+          format(&warnings)
+        ));
+      }
+      xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+  }
+}
+
+/// A relative path with `/` separators, as used in report URIs.
+fn uri(path: &Path) -> String {
+  path
+    .components()
+    .map(|c| c.as_os_str().to_string_lossy())
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const VALID: &str = r#"<html><head>
+    <meta property="og:title" content="The Rock">
+    <meta property="og:url" content="https://example.com/rock">
+    <meta property="og:description" content="A movie">
+    <meta property="og:image" content="https://example.com/rock.jpg">
+  </head></html>"#;
+
+  const INVALID: &str = r#"<html><head>
+    <meta property="og:title" content="The Rock">
+    <meta property="og:url" content="https://example.com/rock?utm_source=x">
+    <meta property="og:image" content="https://example.com/rock.bmp">
+  </head></html>"#;
+
+  fn report() -> LintReport {
+    let options = LintOptions::default();
+    let page = |path: &str, html| PageReport {
+      path: path.into(),
+      diagnostics: lint_html(html, &options),
+    };
+
+    LintReport {
+      pages: vec![page("index.html", VALID), page("news/rock.html", INVALID)],
+      deny_warnings: false,
+    }
+  }
+
+  #[test]
+  fn lints_single_page() {
+    assert!(lint_html(VALID, &LintOptions::default()).is_empty());
+
+    let diagnostics = lint_html(INVALID, &LintOptions::default());
+    let rules: Vec<_> = diagnostics.iter().map(|d| d.rule.as_str()).collect();
+    assert_eq!(
+      rules,
+      ["required-property", "invalid-media", "canonical-url"]
+    );
+    assert_eq!(diagnostics[1].line, Some(4));
+    assert_eq!(diagnostics[2].severity, Severity::Warning);
+
+    let html = r#"<meta property="og:title" content="The Rock">"#;
+    let messages: Vec<_> = lint_html(html, &LintOptions::default())
+      .into_iter()
+      .filter(|d| d.rule == "required-property")
+      .map(|d| d.message)
+      .collect();
+    assert_eq!(messages.len(), 2);
+    assert!(messages[0].contains("url"));
+    assert!(messages[1].contains("description"));
+  }
+
+  #[test]
+  fn reports_markup_errors() {
+    let html = r#"<meta name="og:title" content="The Rock">"#;
+    let diagnostics = lint_html(html, &LintOptions::default());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].rule, "markup");
+  }
+
+  #[test]
+  fn summarizes_report() {
+    let report = report();
+    assert!(!report.is_ok());
+    assert!(report
+      .summary()
+      .ends_with("2 page(s) checked, 1 failed: 2 error(s), 1 warning(s)"));
+    assert!(report.summary().contains("news/rock.html:4: error:"));
+  }
+
+  #[test]
+  fn renders_sarif_and_junit() {
+    let report = report();
+
+    let sarif: serde_json::Value =
+      serde_json::from_str(&report.to_sarif()).unwrap();
+    let results = &sarif["runs"][0]["results"];
+    assert_eq!(results.as_array().map(Vec::len), Some(3));
+    assert_eq!(
+      results[1]["locations"][0]["physicalLocation"]["region"]["startLine"],
+      4
+    );
+
+    let junit = report.to_junit();
+    assert!(junit.contains(r#"<testsuites name="ogp" tests="2" failures="1">"#));
+    assert!(junit.contains(r#"<testcase classname="ogp" name="index.html" />"#));
+    assert!(junit.contains("<failure message=\"Missing required property"));
+  }
+
+  #[test]
+  fn lints_directory() {
+    let dir =
+      std::env::temp_dir().join(format!("ogp-lint-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("news")).unwrap();
+    std::fs::write(dir.join("index.html"), VALID).unwrap();
+    std::fs::write(dir.join("news/rock.html"), INVALID).unwrap();
+    std::fs::write(dir.join("robots.txt"), "").unwrap();
+    std::fs::write(dir.join("latin1.html"), b"<title>Caf\xe9</title>").unwrap();
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&dir, dir.join("news/loop")).unwrap();
+
+    let report = lint_dir(&dir, &LintOptions::default());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let report = report.unwrap();
+    let paths: Vec<_> = report.pages.iter().map(|p| uri(&p.path)).collect();
+    assert_eq!(paths, ["index.html", "latin1.html", "news/rock.html"]);
+    assert_eq!(report.errors().count(), 3);
+
+    let unreadable = &report.pages[1].diagnostics;
+    assert_eq!(unreadable.len(), 1);
+    assert_eq!(unreadable[0].rule, "read-error");
+    assert!(unreadable[0].message.contains("latin1.html"));
+  }
+}
@@ -1,74 +1,539 @@
-// NOTE: This main entrypoint exists for dev purposes only
-// and will be removed with the first release.
+//! The `ogp` command-line tool.
+//!
+//! ```text
+//! ogp extract page.html            # HTML -> JSON
+//! ogp render metadata.toml         # JSON/TOML -> meta tags
+//! ogp validate page.html           # lint, exits with 1 on errors
+//! ogp lint dist --format sarif     # lint all pages of a site build
+//! ogp diff old.html new.html       # changed properties, exits with 1
+//! ogp explain article              # properties of an object type
+//! ```
+//!
+//! Inputs are read from stdin when no file (or `-`) is given.
+//!
+//! Build with `cargo build --features cli`.
 
-use chrono::{DateTime, Utc};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use std::str::FromStr;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::Value;
 use url::Url;
 
 use ogp::{
-  convert::ToHTML,
-  metadata::{Image, OgMetadataBuilder},
-  Result,
+  diff::MetadataDiff,
+  error::Error,
+  extract::{Extractor, ParseMode},
+  inject::render_tags,
+  lint::{lint_dir, lint_html, LintOptions, LintReport, PageReport},
+  object_type::ObjectType,
+  platform::Platform,
+  template, Result,
 };
 
-fn main() -> Result<()> {
-  let date: DateTime<Utc> = chrono::offset::Utc::now();
-  let published_time = &date.to_string();
-
-  let mut base_builder = OgMetadataBuilder::default();
-
-  base_builder
-    .set_title("Open Graph Protocol coming to Rust")
-    .set_url("https://github.com/ekkolon/ogp")
-    .set_site_name("OGP")
-    .set_locale("en_US")
-    .add_locale_alternate("de_DE")
-    .set_description(
-      "The current Open Graph Protocol crate is very outdated. \
-      That's why we're implementing this one.",
-    )
-    .add_image(Image {
-      url: Some(
-        Url::from_str("https://images.unsplash.com/photo-1683009427540")
-          .unwrap(),
-      ),
-      alt: Some("A beautiful image".into()),
-      ..Default::default()
-    })
-    .add_image(Image {
-      url: Some(
-        Url::from_str("https://images.unsplash.com/photo-1683009427540")
-          .unwrap(),
-      ),
-      alt: Some("Another beautiful image".into()),
-      ..Default::default()
-    });
+#[derive(Parser)]
+#[command(name = "ogp", version, about = "Open Graph protocol tools")]
+struct Cli {
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+  /// Extract Open Graph metadata from HTML and print it as JSON.
+  Extract {
+    /// The HTML file to read. Reads stdin if omitted or `-`.
+    input: Option<PathBuf>,
+
+    /// Tolerate common markup mistakes and report them as warnings.
+    #[arg(long)]
+    lenient: bool,
+
+    /// The URL the page was fetched from, to resolve relative URLs.
+    #[arg(long)]
+    url: Option<Url>,
+
+    /// Print compact instead of pretty JSON.
+    #[arg(long)]
+    compact: bool,
+  },
+
+  /// Render meta tags from metadata in JSON or TOML.
+  Render {
+    /// The JSON or TOML file to read. Reads stdin if omitted or `-`.
+    input: Option<PathBuf>,
+
+    /// The input format. Defaults to the file extension, or JSON.
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+  },
+
+  /// Lint the Open Graph markup of an HTML file.
+  ///
+  /// Exits with 1 if errors were found.
+  Validate {
+    /// The HTML file to read. Reads stdin if omitted or `-`.
+    input: Option<PathBuf>,
+
+    /// Report markup mistakes as warnings instead of failing on the first.
+    #[arg(long)]
+    lenient: bool,
+
+    /// The URL the page was fetched from, to resolve relative URLs.
+    #[arg(long)]
+    url: Option<Url>,
+
+    /// Also check the rules of a platform, e.g. `facebook` or `x`.
+    #[arg(long = "platform", value_parser = Platform::from_str)]
+    platforms: Vec<Platform>,
+
+    /// Require all URLs to use https.
+    #[arg(long)]
+    https_only: bool,
+
+    /// Exit with 1 on warnings, too.
+    #[arg(long)]
+    deny_warnings: bool,
+  },
+
+  /// Lint every `*.html` file below a directory, e.g. a site build.
+  ///
+  /// Exits with 1 if errors were found.
+  Lint {
+    /// The directory to walk.
+    dir: PathBuf,
+
+    /// Report markup mistakes as warnings instead of failing on the first.
+    #[arg(long)]
+    lenient: bool,
+
+    /// The URL the directory is served from, to resolve relative URLs.
+    #[arg(long)]
+    url: Option<Url>,
+
+    /// Also check the rules of a platform, e.g. `facebook` or `x`.
+    #[arg(long = "platform", value_parser = Platform::from_str)]
+    platforms: Vec<Platform>,
+
+    /// Require all URLs to use https.
+    #[arg(long)]
+    https_only: bool,
+
+    /// Exit with 1 on warnings, too.
+    #[arg(long)]
+    deny_warnings: bool,
+
+    /// The report format.
+    #[arg(long, value_enum, default_value = "summary")]
+    format: ReportFormat,
 
-  let mut article_builder = base_builder.article();
-
-  let article = article_builder
-    .set_author("Nelson Dominguez")
-    .set_published_time(published_time)
-    .set_section("Technology")
-    .add_tags(&[
-      "Cross platform",
-      "Editor",
-      "Web",
-      "Rich-content",
-      "Developers",
-    ]);
-
-  let html = &article.to_html();
-
-  println!(
-    "ARTICLE:raw -> {}",
-    serde_json::to_string_pretty(&article).unwrap()
-  );
-
-  println!(
-    "ARTICLE:html -> {}",
-    serde_json::to_string_pretty(&html).unwrap()
-  );
-
-  Ok(())
+    /// Write the report to a file and print the summary instead.
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+  },
+
+  /// Compare the Open Graph metadata of two HTML files.
+  ///
+  /// Exits with 1 if the metadata differs.
+  Diff {
+    /// The old HTML file, or a URL resolved against `--fixtures`.
+    old: String,
+
+    /// The new HTML file, or a URL resolved against `--fixtures`.
+    new: String,
+
+    /// A directory of pages saved by URL, e.g. `example.com/blog/index.html`
+    /// for `https://example.com/blog/`.
+    #[arg(long)]
+    fixtures: Option<PathBuf>,
+
+    /// Tolerate common markup mistakes.
+    #[arg(long)]
+    lenient: bool,
+
+    /// Print JSON instead of text.
+    #[arg(long)]
+    json: bool,
+  },
+
+  /// List the properties of an object type, or all object types.
+  Explain {
+    /// The object type, e.g. `article` or `video.movie`.
+    #[arg(value_parser = ObjectType::from_str)]
+    object_type: Option<ObjectType>,
+
+    /// Print JSON instead of a table.
+    #[arg(long)]
+    json: bool,
+  },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+  Json,
+  Toml,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ReportFormat {
+  Summary,
+  Json,
+  Sarif,
+  Junit,
+}
+
+fn main() -> ExitCode {
+  let cli = Cli::parse();
+
+  let result = match cli.command {
+    Command::Extract {
+      input,
+      lenient,
+      url,
+      compact,
+    } => extract(input.as_deref(), lenient, url, compact),
+    Command::Render { input, format } => render(input.as_deref(), format),
+    Command::Validate {
+      input,
+      lenient,
+      url,
+      platforms,
+      https_only,
+      deny_warnings,
+    } => validate(
+      input.as_deref(),
+      lint_options(lenient, url, platforms, https_only, deny_warnings),
+    ),
+    Command::Lint {
+      dir,
+      lenient,
+      url,
+      platforms,
+      https_only,
+      deny_warnings,
+      format,
+      output,
+    } => lint(
+      &dir,
+      lint_options(lenient, url, platforms, https_only, deny_warnings),
+      format,
+      output.as_deref(),
+    ),
+    Command::Diff {
+      old,
+      new,
+      fixtures,
+      lenient,
+      json,
+    } => diff(&old, &new, fixtures.as_deref(), lenient, json),
+    Command::Explain { object_type, json } => explain(object_type, json),
+  };
+
+  match result {
+    Ok(code) => code,
+    Err(err) => {
+      eprintln!("error: {}", err);
+      ExitCode::from(2)
+    }
+  }
+}
+
+/// Reads a file, or stdin for `None` and `-`.
+fn read_input(input: Option<&Path>) -> Result<String> {
+  match input {
+    Some(path) if path != Path::new("-") => Ok(std::fs::read_to_string(path)?),
+    _ => {
+      let mut contents = String::new();
+      std::io::stdin().read_to_string(&mut contents)?;
+      Ok(contents)
+    }
+  }
+}
+
+fn extractor(lenient: bool, url: Option<Url>) -> Extractor {
+  let mode = if lenient {
+    ParseMode::Lenient
+  } else {
+    ParseMode::Strict
+  };
+
+  let mut extractor = Extractor::new(mode);
+  if let Some(url) = url {
+    extractor.set_page_url(url);
+  }
+  extractor
+}
+
+fn to_json(value: &impl serde::Serialize, compact: bool) -> Result<String> {
+  let json = if compact {
+    serde_json::to_string(value)
+  } else {
+    serde_json::to_string_pretty(value)
+  };
+  json.map_err(|err| Error::Generic(err.to_string()))
+}
+
+fn extract(
+  input: Option<&Path>,
+  lenient: bool,
+  url: Option<Url>,
+  compact: bool,
+) -> Result<ExitCode> {
+  let html = read_input(input)?;
+  let extraction = extractor(lenient, url).extract(&html)?;
+
+  for warning in &extraction.warnings {
+    eprintln!("warning: {}", warning);
+  }
+
+  println!("{}", to_json(&extraction, compact)?);
+  Ok(ExitCode::SUCCESS)
+}
+
+fn render(input: Option<&Path>, format: Option<Format>) -> Result<ExitCode> {
+  let contents = read_input(input)?;
+  let is_toml = input
+    .and_then(Path::extension)
+    .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+  let format =
+    format.unwrap_or(if is_toml { Format::Toml } else { Format::Json });
+
+  let value: Value = match format {
+    Format::Json => serde_json::from_str(&contents)
+      .map_err(|err| Error::Generic(err.to_string()))?,
+    Format::Toml => toml::from_str(&contents)
+      .map_err(|err| Error::Generic(err.to_string()))?,
+  };
+
+  let properties = properties(value)?;
+  if !properties.is_empty() {
+    println!("{}", render_tags(&properties));
+  }
+  Ok(ExitCode::SUCCESS)
+}
+
+/// The properties of a metadata value, in their flat form. Accepts the
+/// output of `ogp extract`, too, whose `properties` include type-specific
+/// ones such as `book:isbn`.
+fn properties(value: Value) -> Result<Vec<(String, String)>> {
+  match value {
+    Value::Object(mut object) if object.contains_key("metadata") => {
+      let value = object
+        .remove("properties")
+        .or_else(|| object.remove("metadata"))
+        .unwrap_or_default();
+      template::properties_of(value)
+    }
+    value => template::properties_of(value),
+  }
+}
+
+fn lint_options(
+  lenient: bool,
+  url: Option<Url>,
+  platforms: Vec<Platform>,
+  https_only: bool,
+  deny_warnings: bool,
+) -> LintOptions {
+  LintOptions {
+    mode: if lenient {
+      ParseMode::Lenient
+    } else {
+      ParseMode::Strict
+    },
+    base_url: url,
+    platforms,
+    https_only,
+    deny_warnings,
+  }
+}
+
+fn validate(input: Option<&Path>, options: LintOptions) -> Result<ExitCode> {
+  let html = read_input(input)?;
+  let report = LintReport {
+    pages: vec![PageReport {
+      path: input.unwrap_or(Path::new("-")).to_path_buf(),
+      diagnostics: lint_html(&html, &options),
+    }],
+    deny_warnings: options.deny_warnings,
+  };
+
+  println!("{}", report.summary());
+  Ok(exit_code(&report))
+}
+
+fn lint(
+  dir: &Path,
+  options: LintOptions,
+  format: ReportFormat,
+  output: Option<&Path>,
+) -> Result<ExitCode> {
+  let report = lint_dir(dir, &options)?;
+
+  let contents = match format {
+    ReportFormat::Summary => report.summary(),
+    ReportFormat::Json => report.to_json(),
+    ReportFormat::Sarif => report.to_sarif(),
+    ReportFormat::Junit => report.to_junit(),
+  };
+
+  match output {
+    Some(path) => {
+      std::fs::write(path, contents)?;
+      // Machine-readable reports go to a file, the summary to the console.
+      println!("{}", report.summary());
+    }
+    None => println!("{}", contents.trim_end()),
+  }
+  Ok(exit_code(&report))
+}
+
+fn exit_code(report: &LintReport) -> ExitCode {
+  if report.is_ok() {
+    ExitCode::SUCCESS
+  } else {
+    ExitCode::FAILURE
+  }
+}
+
+/// Reads an HTML file, or the fixture of a URL. Returns the page URL, too.
+fn read_page(
+  input: &str,
+  fixtures: Option<&Path>,
+) -> Result<(String, Option<Url>)> {
+  let url = Url::parse(input)
+    .ok()
+    .filter(|url| matches!(url.scheme(), "http" | "https"));
+  let Some(url) = url else {
+    return Ok((read_input(Some(Path::new(input)))?, None));
+  };
+
+  let Some(fixtures) = fixtures else {
+    return Err(Error::Generic(format!(
+      "'{}' is a URL, pass --fixtures to resolve it",
+      input
+    )));
+  };
+
+  let mut path = fixtures.join(url.host_str().unwrap_or_default());
+  path.extend(url.path_segments().into_iter().flatten());
+  if url.path().ends_with('/') {
+    path.push("index.html");
+  }
+  Ok((std::fs::read_to_string(path)?, Some(url)))
+}
+
+fn diff(
+  old: &str,
+  new: &str,
+  fixtures: Option<&Path>,
+  lenient: bool,
+  json: bool,
+) -> Result<ExitCode> {
+  let extract = |input: &str| -> Result<_> {
+    let (html, url) = read_page(input, fixtures)?;
+    extractor(lenient, url).extract(&html)
+  };
+  let diff = MetadataDiff::between_extractions(&extract(old)?, &extract(new)?);
+
+  if json {
+    println!("{}", diff.to_json());
+  } else {
+    println!("{}", diff.to_text());
+  }
+
+  if diff.is_empty() {
+    Ok(ExitCode::SUCCESS)
+  } else {
+    Ok(ExitCode::FAILURE)
+  }
+}
+
+fn explain(object_type: Option<ObjectType>, json: bool) -> Result<ExitCode> {
+  let Some(object_type) = object_type else {
+    let types: Vec<&str> = ObjectType::ALL.iter().map(|t| t.as_str()).collect();
+    if json {
+      println!("{}", to_json(&types, false)?);
+    } else {
+      for object_type in types {
+        println!("{}", object_type);
+      }
+    }
+    return Ok(ExitCode::SUCCESS);
+  };
+
+  let properties = object_type.properties();
+  if json {
+    println!("{}", to_json(&properties, false)?);
+    return Ok(ExitCode::SUCCESS);
+  }
+
+  let width = properties.iter().map(|p| p.name.len()).max().unwrap_or(0);
+  let type_width = properties
+    .iter()
+    .map(|p| p.value_type.len())
+    .max()
+    .unwrap_or(0);
+
+  for property in properties {
+    println!(
+      "{:width$}  {:type_width$}  {}{}",
+      property.name,
+      property.value_type,
+      property.description,
+      if property.required { " (required)" } else { "" },
+    );
+  }
+  Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Renders the JSON printed by `ogp extract` for `html`.
+  fn extract_and_render(html: &str) -> String {
+    let extraction = extractor(false, None).extract(html).unwrap();
+    let json = to_json(&extraction, false).unwrap();
+    render_tags(&properties(serde_json::from_str(&json).unwrap()).unwrap())
+  }
+
+  #[test]
+  fn renders_extracted_metadata() {
+    let article = r#"<head>
+      <meta property="og:type" content="article">
+      <meta property="og:title" content="Launch">
+      <meta property="article:tag" content="rust">
+    </head>"#;
+    assert_eq!(
+      extract_and_render(article),
+      "<meta property=\"og:type\" content=\"article\" />\n\
+       <meta property=\"og:title\" content=\"Launch\" />\n\
+       <meta property=\"article:tag\" content=\"rust\" />"
+    );
+
+    let book = r#"<head>
+      <meta property="og:type" content="book">
+      <meta property="og:title" content="Dune">
+      <meta property="book:isbn" content="978-0-441-17271-9">
+    </head>"#;
+    assert!(extract_and_render(book).contains(
+      r#"<meta property="book:isbn" content="978-0-441-17271-9" />"#
+    ));
+  }
+
+  #[test]
+  fn renders_metadata_by_object_type() {
+    let value = serde_json::json!({
+      "og:type": "book",
+      "og:title": "Dune",
+      "book:isbn": "978-0-441-17271-9",
+    });
+    let tags = render_tags(&properties(value).unwrap());
+    assert!(tags.contains(
+      r#"<meta property="book:isbn" content="978-0-441-17271-9" />"#
+    ));
+  }
 }
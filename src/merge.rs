@@ -0,0 +1,430 @@
+//! Merging of Open Graph metadata from multiple sources.
+//!
+//! Pages usually combine metadata from several layers, e.g. site-wide
+//! defaults (site name, locale, fallback image), section-level overrides
+//! and per-page values. A `MergePolicy` decides, per property, how the
+//! layers are combined:
+//!
+//! - scalar properties such as `og:title` use `ScalarPolicy::FirstWins` or
+//!   `ScalarPolicy::LastWins`
+//! - list properties such as `og:image` or `og:locale:alternate` use
+//!   `ListPolicy::Append` or `ListPolicy::Replace`
+//!
+//! Sources are passed in order, from the lowest to the highest precedence
+//! for last-wins. The result records which source each final value came
+//! from.
+//!
+//! `og:type` counts as unset when it is the default `website`, so that a
+//! page-level type is not shadowed by site-wide defaults.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ogp::merge::{ListPolicy, MergePolicy};
+//! use ogp::metadata::OgMetadata;
+//!
+//! let site = OgMetadata {
+//!   site_name: Some("Example".into()),
+//!   title: Some("Example".into()),
+//!   ..Default::default()
+//! };
+//! let page = OgMetadata {
+//!   title: Some("Release notes".into()),
+//!   ..Default::default()
+//! };
+//!
+//! let policy = MergePolicy::default().with_list_policy("og:image", ListPolicy::Append);
+//! let merged = policy.merge(&[("site", &site), ("page", &page)]);
+//!
+//! assert_eq!(merged.metadata.title.as_deref(), Some("Release notes"));
+//! assert_eq!(merged.source_of("og:title"), Some("page"));
+//! assert_eq!(merged.source_of("og:site_name"), Some("site"));
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  metadata::OgMetadata,
+  object_type::{retype::ObjectMetadata, ObjectType},
+};
+
+/// Scalar properties that can be merged.
+pub const SCALAR_PROPERTIES: [&str; 7] = [
+  "og:type",
+  "og:url",
+  "og:title",
+  "og:description",
+  "og:site_name",
+  "og:determiner",
+  "og:locale",
+];
+
+/// List properties that can be merged.
+pub const LIST_PROPERTIES: [&str; 4] =
+  ["og:locale:alternate", "og:image", "og:video", "og:audio"];
+
+/// How to merge a scalar property set by more than one source.
+#[derive(
+  Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ScalarPolicy {
+  /// The first source that sets the property wins.
+  FirstWins,
+
+  /// The last source that sets the property wins.
+  #[default]
+  LastWins,
+}
+
+/// How to merge a list property set by more than one source.
+#[derive(
+  Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ListPolicy {
+  /// Items of all sources are concatenated in order.
+  /// Items declared more than once are kept only once.
+  Append,
+
+  /// The last source that sets the property replaces all earlier items.
+  #[default]
+  Replace,
+}
+
+/// Merge policies for all properties, with per-property overrides.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct MergePolicy {
+  /// The policy for scalar properties without an override.
+  pub scalar: ScalarPolicy,
+
+  /// The policy for list properties without an override.
+  pub list: ListPolicy,
+
+  /// Per-property overrides for scalar properties, e.g. `og:site_name`.
+  pub scalar_overrides: BTreeMap<String, ScalarPolicy>,
+
+  /// Per-property overrides for list properties, e.g. `og:image`.
+  pub list_overrides: BTreeMap<String, ListPolicy>,
+}
+
+/// The result of a merge.
+#[derive(Debug, Clone)]
+pub struct Merged<T> {
+  /// The merged metadata.
+  pub metadata: T,
+
+  /// For each property, the names of the sources its final value came
+  /// from. Scalar properties have a single source, list properties one
+  /// source per item.
+  pub sources: BTreeMap<&'static str, Vec<String>>,
+}
+
+impl<T> Merged<T> {
+  /// The source a scalar property came from, or the source of the first
+  /// item of a list property.
+  pub fn source_of(&self, property: &str) -> Option<&str> {
+    self.sources_of(property).first().map(String::as_str)
+  }
+
+  /// The sources of all items of a list property, in order.
+  pub fn sources_of(&self, property: &str) -> &[String] {
+    self.sources.get(property).map_or(&[], Vec::as_slice)
+  }
+}
+
+impl MergePolicy {
+  pub fn new(scalar: ScalarPolicy, list: ListPolicy) -> Self {
+    MergePolicy {
+      scalar,
+      list,
+      ..Default::default()
+    }
+  }
+
+  /// Overrides the policy of a single scalar property, e.g. `og:site_name`.
+  pub fn with_scalar_policy(
+    mut self,
+    property: impl Into<String>,
+    policy: ScalarPolicy,
+  ) -> Self {
+    self.scalar_overrides.insert(property.into(), policy);
+    self
+  }
+
+  /// Overrides the policy of a single list property, e.g. `og:image`.
+  pub fn with_list_policy(
+    mut self,
+    property: impl Into<String>,
+    policy: ListPolicy,
+  ) -> Self {
+    self.list_overrides.insert(property.into(), policy);
+    self
+  }
+
+  /// The policy used for the scalar `property`.
+  pub fn scalar_policy(&self, property: &str) -> ScalarPolicy {
+    self
+      .scalar_overrides
+      .get(property)
+      .copied()
+      .unwrap_or(self.scalar)
+  }
+
+  /// The policy used for the list `property`.
+  pub fn list_policy(&self, property: &str) -> ListPolicy {
+    self
+      .list_overrides
+      .get(property)
+      .copied()
+      .unwrap_or(self.list)
+  }
+
+  /// Merges named sources, ordered from the lowest to the highest
+  /// precedence for last-wins.
+  pub fn merge(&self, sources: &[(&str, &OgMetadata)]) -> Merged<OgMetadata> {
+    let mut merger = Merger {
+      policy: self,
+      sources,
+      provenance: BTreeMap::new(),
+    };
+
+    let object_type = merger.scalar("og:type", |m| {
+      (m.object_type != ObjectType::Website).then(|| m.object_type.clone())
+    });
+
+    let metadata = OgMetadata {
+      object_type: object_type.unwrap_or_default(),
+      url: merger.scalar("og:url", |m| m.url.clone()),
+      title: merger.scalar("og:title", |m| m.title.clone()),
+      description: merger.scalar("og:description", |m| m.description.clone()),
+      site_name: merger.scalar("og:site_name", |m| m.site_name.clone()),
+      determiner: merger.scalar("og:determiner", |m| m.determiner.clone()),
+      locale: merger.scalar("og:locale", |m| m.locale.clone()),
+      locale_alternate: Some(merger.list(
+        "og:locale:alternate",
+        |m| m.locale_alternate.as_deref().unwrap_or_default(),
+        |locale| Some(locale.clone()),
+      ))
+      .filter(|locales| !locales.is_empty()),
+      images: merger.list(
+        "og:image",
+        |m| &m.images,
+        |image| image.url.as_ref().map(|url| url.to_string()),
+      ),
+      videos: merger.list(
+        "og:video",
+        |m| &m.videos,
+        |video| video.url.as_ref().map(|url| url.to_string()),
+      ),
+      audios: merger.list(
+        "og:audio",
+        |m| &m.audios,
+        |audio| audio.url.as_ref().map(|url| url.to_string()),
+      ),
+    };
+
+    Merged {
+      metadata,
+      sources: merger.provenance,
+    }
+  }
+
+  /// Overlays an object type struct on top of named default sources.
+  ///
+  /// The object's own metadata is merged as the last source, named
+  /// `name`. Its type-specific properties and object type are kept.
+  pub fn overlay<T: ObjectMetadata>(
+    &self,
+    defaults: &[(&str, &OgMetadata)],
+    name: &str,
+    mut object: T,
+  ) -> Merged<T> {
+    let root = object.root();
+    let mut sources = defaults.to_vec();
+    sources.push((name, &root));
+
+    let merged = self.merge(&sources);
+    object.set_root(merged.metadata);
+
+    Merged {
+      metadata: object,
+      sources: merged.sources,
+    }
+  }
+}
+
+/// Merges the properties of `sources` one by one, recording provenance.
+struct Merger<'a> {
+  policy: &'a MergePolicy,
+  sources: &'a [(&'a str, &'a OgMetadata)],
+  provenance: BTreeMap<&'static str, Vec<String>>,
+}
+
+impl Merger<'_> {
+  fn scalar<V>(
+    &mut self,
+    property: &'static str,
+    value: impl Fn(&OgMetadata) -> Option<V>,
+  ) -> Option<V> {
+    let mut values = self
+      .sources
+      .iter()
+      .filter_map(|(name, metadata)| value(metadata).map(|v| (*name, v)));
+
+    let (name, value) = match self.policy.scalar_policy(property) {
+      ScalarPolicy::FirstWins => values.next(),
+      ScalarPolicy::LastWins => values.last(),
+    }?;
+
+    self.provenance.insert(property, vec![name.to_string()]);
+    Some(value)
+  }
+
+  /// Merges a list property. Items with the same `key` are only kept once
+  /// when appending.
+  fn list<V: Clone>(
+    &mut self,
+    property: &'static str,
+    items: impl Fn(&OgMetadata) -> &[V],
+    key: impl Fn(&V) -> Option<String>,
+  ) -> Vec<V> {
+    let mut merged: Vec<V> = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+
+    for (name, metadata) in self.sources {
+      let items = items(metadata);
+      if items.is_empty() {
+        continue;
+      }
+
+      if self.policy.list_policy(property) == ListPolicy::Replace {
+        merged.clear();
+        names.clear();
+      }
+
+      for item in items {
+        let is_duplicate = key(item).is_some_and(|item_key| {
+          merged
+            .iter()
+            .any(|other| key(other) == Some(item_key.clone()))
+        });
+        if !is_duplicate {
+          merged.push(item.clone());
+          names.push(name.to_string());
+        }
+      }
+    }
+
+    if !names.is_empty() {
+      self.provenance.insert(property, names);
+    }
+    merged
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr;
+
+  use super::*;
+  use crate::metadata::{Image, OgMetadataBuilder};
+  use crate::object_type::article::ArticleMetadata;
+
+  fn site() -> OgMetadata {
+    OgMetadata {
+      site_name: Some("Example".into()),
+      title: Some("Example".into()),
+      locale: Some("en_US".into()),
+      locale_alternate: Some(vec!["de_DE".into()]),
+      images: vec![Image::from_str("https://example.com/logo.png").unwrap()],
+      ..Default::default()
+    }
+  }
+
+  fn page() -> OgMetadata {
+    OgMetadata {
+      object_type: ObjectType::Article,
+      title: Some("Release notes".into()),
+      locale_alternate: Some(vec!["fr_FR".into(), "de_DE".into()]),
+      images: vec![Image::from_str("https://example.com/hero.png").unwrap()],
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn merges_scalars_with_policies() {
+    let (site, page) = (site(), page());
+    let sources = [("site", &site), ("page", &page)];
+
+    let merged = MergePolicy::default().merge(&sources);
+    assert_eq!(merged.metadata.object_type, ObjectType::Article);
+    assert_eq!(merged.metadata.title.as_deref(), Some("Release notes"));
+    assert_eq!(merged.metadata.locale.as_deref(), Some("en_US"));
+    assert_eq!(merged.source_of("og:title"), Some("page"));
+    assert_eq!(merged.source_of("og:locale"), Some("site"));
+    assert_eq!(merged.source_of("og:description"), None);
+
+    let policy = MergePolicy::default()
+      .with_scalar_policy("og:title", ScalarPolicy::FirstWins);
+    let merged = policy.merge(&sources);
+    assert_eq!(merged.metadata.title.as_deref(), Some("Example"));
+    assert_eq!(merged.source_of("og:title"), Some("site"));
+  }
+
+  #[test]
+  fn merges_lists_with_policies() {
+    let (site, page) = (site(), page());
+    let sources = [("site", &site), ("page", &page)];
+
+    let merged = MergePolicy::new(ScalarPolicy::LastWins, ListPolicy::Append)
+      .merge(&sources);
+    assert_eq!(
+      merged.metadata.locale_alternate,
+      Some(vec!["de_DE".to_string(), "fr_FR".to_string()])
+    );
+    assert_eq!(merged.metadata.images.len(), 2);
+    assert_eq!(merged.sources_of("og:image"), ["site", "page"]);
+
+    let merged = MergePolicy::default().merge(&sources);
+    assert_eq!(merged.metadata.images.len(), 1);
+    assert_eq!(merged.sources_of("og:image"), ["page"]);
+    assert_eq!(merged.sources_of("og:locale:alternate"), ["page", "page"]);
+  }
+
+  #[test]
+  fn keeps_fallback_list_when_later_sources_are_empty() {
+    let site = site();
+    let page = OgMetadata::default();
+
+    let merged =
+      MergePolicy::default().merge(&[("site", &site), ("page", &page)]);
+    assert_eq!(merged.metadata.images.len(), 1);
+    assert_eq!(merged.source_of("og:image"), Some("site"));
+  }
+
+  #[test]
+  fn overlays_object_type_struct() {
+    let site = site();
+    let mut builder = OgMetadataBuilder::new();
+    builder.set_title("Release notes");
+
+    let mut article = builder.article();
+    article.set_author("Jane Doe");
+
+    let merged =
+      MergePolicy::default().overlay(&[("site", &site)], "page", article);
+    let root = merged.metadata.root();
+    assert_eq!(root.object_type, ObjectType::Article);
+    assert_eq!(root.site_name.as_deref(), Some("Example"));
+    assert_eq!(root.title.as_deref(), Some("Release notes"));
+    assert_eq!(
+      merged.metadata.type_specific_properties(),
+      vec!["article:author"]
+    );
+    assert_eq!(merged.source_of("og:type"), Some("page"));
+  }
+}
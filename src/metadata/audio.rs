@@ -5,39 +5,106 @@ use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::utils::validate_http_url;
-use crate::validator::Validatable;
+use super::{MediaKind, MediaType};
+
+use crate::utils::resolve_http_url;
+use crate::validator::{MediaTypeValidator, SecureURLValidator, Validatable};
 use crate::{error, Result};
 
-/// `Image` contains Open Graph metadata for the `audio` metatag(s).
+/// `Audio` contains Open Graph metadata for the `audio` metatag(s).
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Audio {
   /// The URL of the audio that appears when someone shares the content.
   /// Equivalent to `og:audio` | "og:audio:url".
   #[serde(alias = "og:audio:url")]
+  #[cfg_attr(
+    feature = "schemars",
+    schemars(regex(pattern = crate::schema::HTTP_URL_PATTERN))
+  )]
   pub url: Option<Url>,
 
   /// https:// URL for the audio.
+  #[cfg_attr(
+    feature = "schemars",
+    schemars(regex(pattern = crate::schema::HTTPS_URL_PATTERN))
+  )]
   pub secure_url: Option<Url>,
 
-  /// Equivalent to `og:audio`.
-  pub mimetype: Option<String>,
+  /// A MIME type for this audio file.
+  #[cfg_attr(
+    feature = "schemars",
+    schemars(schema_with = "crate::schema::audio_media_type")
+  )]
+  pub mimetype: Option<MediaType>,
+
+  /// The URL as originally declared, if it had to be resolved against
+  /// a base URL. Kept for diagnostics only and never serialized.
+  #[serde(skip)]
+  pub original_url: Option<String>,
 }
 
-impl FromStr for Audio {
-  type Err = error::Error;
-  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-    validate_http_url(s)
+impl Audio {
+  /// Creates an `Audio` from a URL that may be relative or protocol-relative,
+  /// resolving it against `base`.
+  ///
+  /// If the URL had to be resolved, the original value is kept in
+  /// `original_url`.
+  pub fn from_str_with_base(s: &str, base: Option<&Url>) -> Result<Self> {
+    resolve_http_url(s, base)
       .map(|url| Audio {
         url: Some(url),
+        original_url: Url::from_str(s).is_err().then(|| s.into()),
         ..Default::default()
       })
       .map_err(|err| error::Error::UrlParseError(s.into()))
   }
+
+  /// Sets the MIME type inferred from the URL's file extension,
+  /// unless a MIME type has already been set.
+  pub fn infer_mimetype(&mut self) -> Option<&MediaType> {
+    if self.mimetype.is_none() {
+      self.mimetype = self.inferred_media_type();
+    }
+
+    self.mimetype.as_ref()
+  }
+}
+
+impl FromStr for Audio {
+  type Err = error::Error;
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    Audio::from_str_with_base(s, None)
+  }
 }
 
 impl Validatable for Audio {
   fn validate(&self) -> Result<()> {
-    Ok(())
+    self.validate_secure_url()?;
+    self.validate_media_type()
+  }
+}
+
+impl MediaTypeValidator for Audio {
+  fn media_kind(&self) -> MediaKind {
+    MediaKind::Audio
+  }
+
+  fn media_type(&self) -> Option<MediaType> {
+    self.mimetype.clone()
+  }
+
+  fn media_url(&self) -> Option<Url> {
+    self.url.clone()
+  }
+}
+
+impl SecureURLValidator for Audio {
+  fn secure_url(&self) -> Option<Url> {
+    self.secure_url.clone()
+  }
+
+  fn url(&self) -> Option<Url> {
+    self.url.clone()
   }
 }
@@ -1,6 +1,7 @@
 use std::str::FromStr;
 
 use crate::{
+  canonical::UrlNormalizer,
   error::Error,
   metadata::{Audio, Image, OgMetadata, Video},
   object_type::{Determiner, ObjectType},
@@ -11,9 +12,14 @@ use serde::{de::IntoDeserializer, Deserialize, Serialize};
 use url::Url;
 
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct OgMetadataBuilder {
   #[serde(flatten)]
   metadata: OgMetadata,
+
+  /// Base URL used to resolve relative image, video and audio URLs.
+  #[serde(skip)]
+  base_url: Option<Url>,
 }
 
 impl OgMetadataBuilder {
@@ -23,6 +29,10 @@ impl OgMetadataBuilder {
 
   pub fn with_type(object_type: ObjectType) -> OgMetadataBuilder {
     OgMetadataBuilder {
+      metadata: OgMetadata {
+        object_type,
+        ..Default::default()
+      },
       ..Default::default()
     }
   }
@@ -52,6 +62,34 @@ impl OgMetadataBuilder {
     }
   }
 
+  /// Sets the canonical URL in its normalized form, e.g. without tracking
+  /// parameters. See `UrlNormalizer` for the applied rules.
+  pub fn set_normalized_url(
+    &mut self,
+    url: impl Into<String>,
+    normalizer: &UrlNormalizer,
+  ) -> &mut Self {
+    match normalizer.normalize_str(&url.into()) {
+      Err(err) => panic!("error: {}", err),
+      Ok(url) => {
+        self.metadata.url.insert(url.into());
+        self
+      }
+    }
+  }
+
+  /// Sets the base URL that relative and protocol-relative URLs passed to
+  /// `add_image_url`, `add_video_url` and `add_audio_url` are resolved against.
+  pub fn set_base_url(&mut self, base_url: impl Into<String>) -> &mut Self {
+    match validate_http_url(&base_url.into()) {
+      Err(err) => panic!("error: {}", err),
+      Ok(url) => {
+        self.base_url.insert(url);
+        self
+      }
+    }
+  }
+
   pub fn set_image(&mut self, image: impl Into<String>) -> &mut Self {
     // self.image = Some(image.into());
     self
@@ -76,9 +114,10 @@ impl OgMetadataBuilder {
     &mut self,
     image_url: impl Into<String>,
   ) -> Result<&mut Self> {
-    match Image::from_str(&image_url.into()) {
+    match Image::from_str_with_base(&image_url.into(), self.base_url.as_ref()) {
       Err(err) => Err(err),
-      Ok(img) => {
+      Ok(mut img) => {
+        img.infer_mimetype();
         self.metadata.images.push(img);
         Ok(self)
       }
@@ -94,9 +133,10 @@ impl OgMetadataBuilder {
     &mut self,
     video_url: impl Into<String>,
   ) -> Result<&mut Self> {
-    match Video::from_str(&video_url.into()) {
+    match Video::from_str_with_base(&video_url.into(), self.base_url.as_ref()) {
       Err(err) => Err(err),
-      Ok(video) => {
+      Ok(mut video) => {
+        video.infer_mimetype();
         self.metadata.videos.push(video);
         Ok(self)
       }
@@ -112,9 +152,10 @@ impl OgMetadataBuilder {
     &mut self,
     audio_url: impl Into<String>,
   ) -> Result<&mut Self> {
-    match Audio::from_str(&audio_url.into()) {
+    match Audio::from_str_with_base(&audio_url.into(), self.base_url.as_ref()) {
       Err(err) => Err(err),
-      Ok(audio) => {
+      Ok(mut audio) => {
+        audio.infer_mimetype();
         self.metadata.audios.push(audio);
         Ok(self)
       }
@@ -164,6 +205,26 @@ impl OgMetadataBuilder {
   pub fn get_metadata(&self) -> OgMetadata {
     self.metadata.clone()
   }
+
+  /// Replaces the metadata, keeping the base URL.
+  pub(crate) fn set_metadata(&mut self, metadata: OgMetadata) {
+    self.metadata = metadata;
+  }
+}
+
+impl From<OgMetadata> for OgMetadataBuilder {
+  fn from(metadata: OgMetadata) -> Self {
+    OgMetadataBuilder {
+      metadata,
+      ..Default::default()
+    }
+  }
+}
+
+impl From<OgMetadataBuilder> for OgMetadata {
+  fn from(builder: OgMetadataBuilder) -> Self {
+    builder.metadata
+  }
 }
 
 pub trait Build<T> {
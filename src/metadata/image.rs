@@ -5,58 +5,128 @@ use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use super::{MediaKind, MediaType};
+
 use crate::error::{self, Error};
-use crate::utils::validate_http_url;
-use crate::validator::{DimensionsValidator, Validatable};
+use crate::utils::resolve_http_url;
+use crate::validator::{
+  DimensionsValidator, MediaTypeValidator, SecureURLValidator, Validatable,
+};
 use crate::Result;
 
 /// `Image` contains Open Graph metadata for the `image` metatag(s).
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+  feature = "schemars",
+  schemars(transform = crate::schema::both_dimensions)
+)]
 pub struct Image {
   /// An image URL which should represent your object within the graph.
   /// This image appears when someone shares the content.
   ///
   /// Represents both the `og:image` | "og:image:url" property.
   #[serde(alias = "og:image:url")]
+  #[cfg_attr(
+    feature = "schemars",
+    schemars(regex(pattern = crate::schema::IMAGE_URL_PATTERN))
+  )]
   pub url: Option<Url>,
 
   /// An alternate url to use if the webpage requires HTTPS.
-  // #[serde(rename = "og:image:secure_url")]
+  #[cfg_attr(
+    feature = "schemars",
+    schemars(regex(pattern = crate::schema::HTTPS_URL_PATTERN))
+  )]
   pub secure_url: Option<Url>,
 
   /// A MIME type for this image.
   #[serde(rename = "type")]
-  pub mimetype: Option<String>,
+  #[cfg_attr(
+    feature = "schemars",
+    schemars(schema_with = "crate::schema::image_media_type")
+  )]
+  pub mimetype: Option<MediaType>,
 
   /// A description of what is in the image (not a caption).
   /// If the page specifies an og:image it should specify `og:image:alt`
-  // #[serde(rename = "og:image:alt")]
   pub alt: Option<String>,
 
   /// The number of pixels wide.
-  // #[serde(rename = "og:image:width")]
   pub width: Option<u32>,
 
   /// The number of pixels high.
-  // #[serde(rename = "og:image:height")]
   pub height: Option<u32>,
+
+  /// The URL as originally declared, if it had to be resolved against
+  /// a base URL. Kept for diagnostics only and never serialized.
+  #[serde(skip)]
+  pub original_url: Option<String>,
 }
 
-impl FromStr for Image {
-  type Err = error::Error;
-  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-    validate_http_url(s)
+impl Image {
+  /// Creates an `Image` from a URL that may be relative or protocol-relative,
+  /// resolving it against `base`.
+  ///
+  /// If the URL had to be resolved, the original value is kept in
+  /// `original_url`.
+  pub fn from_str_with_base(s: &str, base: Option<&Url>) -> Result<Self> {
+    resolve_http_url(s, base)
       .map(|url| Image {
         url: Some(url),
+        original_url: Url::from_str(s).is_err().then(|| s.into()),
         ..Default::default()
       })
       .map_err(|err| error::Error::UrlParseError(s.into()))
   }
+
+  /// Sets the MIME type inferred from the URL's file extension,
+  /// unless a MIME type has already been set.
+  pub fn infer_mimetype(&mut self) -> Option<&MediaType> {
+    if self.mimetype.is_none() {
+      self.mimetype = self.inferred_media_type();
+    }
+
+    self.mimetype.as_ref()
+  }
+}
+
+impl FromStr for Image {
+  type Err = error::Error;
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    Image::from_str_with_base(s, None)
+  }
 }
 
 impl Validatable for Image {
   fn validate(&self) -> Result<()> {
-    self.validate_dimensions()
+    self.validate_dimensions()?;
+    self.validate_secure_url()?;
+    self.validate_media_type()
+  }
+}
+
+impl MediaTypeValidator for Image {
+  fn media_kind(&self) -> MediaKind {
+    MediaKind::Image
+  }
+
+  fn media_type(&self) -> Option<MediaType> {
+    self.mimetype.clone()
+  }
+
+  fn media_url(&self) -> Option<Url> {
+    self.url.clone()
+  }
+}
+
+impl SecureURLValidator for Image {
+  fn secure_url(&self) -> Option<Url> {
+    self.secure_url.clone()
+  }
+
+  fn url(&self) -> Option<Url> {
+    self.url.clone()
   }
 }
 
@@ -0,0 +1,267 @@
+//! Typed MIME types for the `og:image:type`, `og:video:type` and
+//! `og:audio:type` meta tags.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Error, Result};
+
+/// Media types allowed for `og:image:type`.
+pub const IMAGE_MEDIA_TYPES: [&str; 5] = [
+  "image/jpeg",
+  "image/png",
+  "image/gif",
+  "image/webp",
+  "image/avif",
+];
+
+/// Media types allowed for `og:video:type`.
+pub const VIDEO_MEDIA_TYPES: [&str; 4] = [
+  "video/mp4",
+  "video/webm",
+  "video/ogg",
+  "application/x-shockwave-flash",
+];
+
+/// Media types allowed for `og:audio:type`.
+pub const AUDIO_MEDIA_TYPES: [&str; 7] = [
+  "audio/mpeg",
+  "audio/ogg",
+  "audio/wav",
+  "audio/aac",
+  "audio/mp4",
+  "audio/webm",
+  "audio/flac",
+];
+
+/// File extensions and the media type they map to, per kind of media.
+const EXTENSIONS: [(&str, MediaKind, &str); 20] = [
+  ("jpg", MediaKind::Image, "image/jpeg"),
+  ("jpeg", MediaKind::Image, "image/jpeg"),
+  ("png", MediaKind::Image, "image/png"),
+  ("gif", MediaKind::Image, "image/gif"),
+  ("webp", MediaKind::Image, "image/webp"),
+  ("avif", MediaKind::Image, "image/avif"),
+  ("mp4", MediaKind::Video, "video/mp4"),
+  ("m4v", MediaKind::Video, "video/mp4"),
+  ("webm", MediaKind::Video, "video/webm"),
+  ("ogv", MediaKind::Video, "video/ogg"),
+  ("swf", MediaKind::Video, "application/x-shockwave-flash"),
+  ("mp3", MediaKind::Audio, "audio/mpeg"),
+  ("ogg", MediaKind::Audio, "audio/ogg"),
+  ("oga", MediaKind::Audio, "audio/ogg"),
+  ("wav", MediaKind::Audio, "audio/wav"),
+  ("aac", MediaKind::Audio, "audio/aac"),
+  ("m4a", MediaKind::Audio, "audio/mp4"),
+  ("flac", MediaKind::Audio, "audio/flac"),
+  ("webm", MediaKind::Audio, "audio/webm"),
+  ("weba", MediaKind::Audio, "audio/webm"),
+];
+
+/// File extensions of media not allowed by the protocol, e.g. `bmp`.
+const OTHER_MEDIA_EXTENSIONS: [&str; 10] = [
+  "bmp", "tif", "tiff", "ico", "svg", "svgz", "heic", "heif", "jxl", "psd",
+];
+
+/// Whether `ext` is the file extension of a media file, as opposed to e.g.
+/// `php` or `ashx` for dynamically generated media.
+pub(crate) fn is_media_ext(ext: &str) -> bool {
+  let ext = ext.to_lowercase();
+  EXTENSIONS.iter().any(|(e, _, _)| *e == ext)
+    || OTHER_MEDIA_EXTENSIONS.contains(&ext.as_str())
+}
+
+/// The kind of media a `MediaType` describes.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaKind {
+  Image,
+  Video,
+  Audio,
+}
+
+impl MediaKind {
+  /// The media types allowed for this kind of media.
+  pub fn allowed_types(&self) -> &'static [&'static str] {
+    match self {
+      MediaKind::Image => &IMAGE_MEDIA_TYPES,
+      MediaKind::Video => &VIDEO_MEDIA_TYPES,
+      MediaKind::Audio => &AUDIO_MEDIA_TYPES,
+    }
+  }
+
+  /// Checks whether `media_type` is allowed for this kind of media.
+  pub fn allows(&self, media_type: &MediaType) -> bool {
+    self.allowed_types().contains(&media_type.as_str())
+  }
+
+  /// Infers the media type from a file extension, e.g. `png` -> `image/png`.
+  pub fn media_type_for_ext(&self, ext: &str) -> Option<MediaType> {
+    let ext = ext.to_lowercase();
+    EXTENSIONS
+      .iter()
+      .find(|(e, kind, _)| *e == ext && kind == self)
+      .map(|(_, _, media_type)| MediaType(media_type.to_string()))
+  }
+}
+
+impl fmt::Display for MediaKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      MediaKind::Image => write!(f, "image"),
+      MediaKind::Video => write!(f, "video"),
+      MediaKind::Audio => write!(f, "audio"),
+    }
+  }
+}
+
+/// A MIME type in the format `type/subtype`, e.g. `image/png`.
+///
+/// The value is stored lowercased and without parameters, so
+/// `Image/PNG; charset=binary` becomes `image/png`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct MediaType(String);
+
+impl MediaType {
+  /// The top-level type, e.g. `image`.
+  pub fn type_(&self) -> &str {
+    self.0.split('/').next().unwrap_or_default()
+  }
+
+  /// The subtype, e.g. `png`.
+  pub fn subtype(&self) -> &str {
+    self.0.split('/').nth(1).unwrap_or_default()
+  }
+
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+
+  /// Creates a media type from a known valid, lowercase `type/subtype`.
+  pub(crate) fn new_unchecked(essence: &str) -> Self {
+    MediaType(essence.into())
+  }
+}
+
+impl FromStr for MediaType {
+  type Err = Error;
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    let essence = s.split(';').next().unwrap_or_default().trim();
+    let is_token = |part: &str| {
+      !part.is_empty()
+        && part
+          .chars()
+          .all(|c| c.is_ascii_alphanumeric() || "!#$&-^_.+".contains(c))
+    };
+
+    match essence.split_once('/') {
+      Some((type_, subtype)) if is_token(type_) && is_token(subtype) => {
+        Ok(MediaType(essence.to_lowercase()))
+      }
+      _ => Err(Error::InvalidMediaType(s.into())),
+    }
+  }
+}
+
+impl TryFrom<String> for MediaType {
+  type Error = Error;
+  fn try_from(value: String) -> Result<Self> {
+    MediaType::from_str(&value)
+  }
+}
+
+impl From<MediaType> for String {
+  fn from(value: MediaType) -> Self {
+    value.0
+  }
+}
+
+impl fmt::Display for MediaType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::metadata::{Audio, Image, Video};
+  use crate::validator::{MediaTypeValidator, Validatable};
+
+  #[test]
+  fn parses_media_type() {
+    let media_type = MediaType::from_str("Image/PNG; charset=binary").unwrap();
+    assert_eq!(media_type.as_str(), "image/png");
+    assert_eq!(media_type.type_(), "image");
+    assert_eq!(media_type.subtype(), "png");
+
+    assert!(MediaType::from_str("png").is_err());
+    assert!(MediaType::from_str("image/").is_err());
+  }
+
+  #[test]
+  fn infers_media_type_per_kind() {
+    let webm = |kind: MediaKind| kind.media_type_for_ext("WEBM").unwrap();
+    assert_eq!(webm(MediaKind::Video).as_str(), "video/webm");
+    assert_eq!(webm(MediaKind::Audio).as_str(), "audio/webm");
+    assert!(MediaKind::Image.media_type_for_ext("mp3").is_none());
+  }
+
+  #[test]
+  fn validates_declared_media_type() {
+    let mut image = Image::from_str("https://example.com/cover.png").unwrap();
+    image.mimetype = Some(MediaType::from_str("image/png").unwrap());
+    assert!(image.validate().is_ok());
+
+    image.mimetype = Some(MediaType::from_str("image/jpeg").unwrap());
+    assert!(matches!(
+      image.validate(),
+      Err(Error::MediaTypeMismatch(_, _))
+    ));
+
+    image.mimetype = Some(MediaType::from_str("video/mp4").unwrap());
+    assert!(matches!(
+      image.validate(),
+      Err(Error::UnsupportedMediaType(_, _))
+    ));
+  }
+
+  #[test]
+  fn validates_image_extension() {
+    let image = Image::from_str("https://example.com/cover.bmp").unwrap();
+    assert!(matches!(
+      image.validate(),
+      Err(Error::InvalidImageExtension(_))
+    ));
+
+    let image = Image::from_str("https://example.com/photo-123").unwrap();
+    assert!(image.validate().is_ok());
+
+    let image = Image::from_str("https://example.com/clip.mp4").unwrap();
+    assert!(image.validate().is_err());
+
+    // Dynamically generated images.
+    for url in [
+      "https://example.com/og.php?id=1",
+      "https://example.com/og.ashx",
+    ] {
+      let image = Image::from_str(url).unwrap();
+      assert!(image.validate().is_ok(), "{}", url);
+    }
+  }
+
+  #[test]
+  fn infers_missing_media_type() {
+    let mut video = Video::from_str("https://example.com/movie.swf").unwrap();
+    assert_eq!(
+      video.infer_mimetype().map(MediaType::as_str),
+      Some("application/x-shockwave-flash")
+    );
+
+    let mut audio = Audio::from_str("https://example.com/sound").unwrap();
+    assert!(audio.infer_mimetype().is_none());
+  }
+}
@@ -1,15 +1,29 @@
 //! Models for representing Open Graph data
 
-use crate::{object_type::ObjectType, validator::Validatable};
+use crate::{
+  convert::ToHTML,
+  object_type::ObjectType,
+  validator::{SecureURLValidator, Validatable},
+};
 use serde::{Deserialize, Serialize};
 
 pub use self::{
-  audio::Audio, builder::OgMetadataBuilder, image::Image, video::Video,
+  audio::Audio,
+  builder::OgMetadataBuilder,
+  image::Image,
+  media_type::{
+    MediaKind, MediaType, AUDIO_MEDIA_TYPES, IMAGE_MEDIA_TYPES,
+    VIDEO_MEDIA_TYPES,
+  },
+  video::Video,
 };
 
+pub(crate) use self::media_type::is_media_ext;
+
 mod audio;
 mod builder;
 mod image;
+mod media_type;
 mod video;
 
 use std::str::FromStr;
@@ -25,6 +39,7 @@ use url::Url;
 
 /// `GeneralSiteInfo` contains Open Graph metadata shared by all object types.
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct OgMetadata {
   /// The type of your object, e.g., "video.movie".
   ///
@@ -40,14 +55,20 @@ pub struct OgMetadata {
   /// aggregate at this URL. For example, mobile domain URLs should point to the
   /// desktop version of the URL as the canonical URL to aggregate Likes and
   /// Shares across different versions of the page.
+  #[cfg_attr(
+    feature = "schemars",
+    schemars(required, url, regex(pattern = crate::schema::HTTP_URL_PATTERN))
+  )]
   #[serde(rename = "og:url")]
   pub url: Option<String>,
 
   /// The title of your article without any branding such as your site name.
+  #[cfg_attr(feature = "schemars", schemars(required))]
   #[serde(rename = "og:title")]
   pub title: Option<String>,
 
   /// A one to two sentence description of your object.
+  #[cfg_attr(feature = "schemars", schemars(required))]
   #[serde(rename = "og:description")]
   pub description: Option<String>,
 
@@ -66,10 +87,18 @@ pub struct OgMetadata {
   /// The locale these tags are marked up in. Of the format `language_TERRITORY`.
   ///
   /// Default is `en_US`.
+  #[cfg_attr(
+    feature = "schemars",
+    schemars(regex(pattern = crate::schema::LOCALE_PATTERN))
+  )]
   #[serde(rename = "og:locale")]
   pub locale: Option<String>,
 
   /// An array of other locales this page is available in.
+  #[cfg_attr(
+    feature = "schemars",
+    schemars(inner(regex(pattern = crate::schema::LOCALE_PATTERN)))
+  )]
   #[serde(rename = "og:locale:alternate")]
   pub locale_alternate: Option<Vec<String>>,
 
@@ -86,20 +115,58 @@ pub struct OgMetadata {
   pub audios: Vec<Audio>,
 }
 
-impl Validatable for OgMetadata {
-  fn validate(&self) -> Result<()> {
-    let Some(title) = self.title.as_ref() else {
-      return Err(Error::MissingRequiredProperty("title".into()));
-    };
-
-    let Some(url) = self.url.as_ref() else {
-      return Err(Error::MissingRequiredProperty("url".into()));
-    };
+impl OgMetadata {
+  /// The required properties that are not set, e.g. `["title", "url"]`.
+  /// `validate` reports the first of them.
+  pub fn missing_properties(&self) -> Vec<&'static str> {
+    [
+      ("title", self.title.is_none()),
+      ("url", self.url.is_none()),
+      ("description", self.description.is_none()),
+    ]
+    .into_iter()
+    .filter(|(_, missing)| *missing)
+    .map(|(property, _)| property)
+    .collect()
+  }
 
-    let Some(description) = self.description.as_ref() else {
-      return Err(Error::MissingRequiredProperty("description".into()));
-    };
+  /// Validates that the canonical URL and all image, video and audio URLs
+  /// use the "https" scheme.
+  ///
+  /// Use this when the site is configured as HTTPS-only.
+  pub fn validate_https_only(&self) -> Result<()> {
+    if let Some(url) = self.url.as_ref() {
+      let scheme = validate_http_url(url)?.scheme().to_string();
+      if scheme != "https" {
+        return Err(Error::InvalidHttpsUrlScheme(scheme));
+      }
+    }
+
+    for image in &self.images {
+      image.validate_https_only()?;
+    }
+
+    for video in &self.videos {
+      video.validate_https_only()?;
+    }
+
+    for audio in &self.audios {
+      audio.validate_https_only()?;
+    }
 
     Ok(())
   }
 }
+
+impl ToHTML for OgMetadata {}
+
+impl Validatable for OgMetadata {
+  fn validate(&self) -> Result<()> {
+    match self.missing_properties().first() {
+      Some(property) => {
+        Err(Error::MissingRequiredProperty(property.to_string()))
+      }
+      None => Ok(()),
+    }
+  }
+}
@@ -5,54 +5,126 @@ use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::utils::validate_http_url;
-use crate::validator::{DimensionsValidator, Validatable};
+use super::{MediaKind, MediaType};
+
+use crate::utils::resolve_http_url;
+use crate::validator::{
+  DimensionsValidator, MediaTypeValidator, SecureURLValidator, Validatable,
+};
 use crate::{error, Result};
 
-/// `Image` contains Open Graph metadata for the `video` metatag(s).
+/// `Video` contains Open Graph metadata for the `video` metatag(s).
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+  feature = "schemars",
+  schemars(transform = crate::schema::both_dimensions)
+)]
 pub struct Video {
   /// The URL of the video that appears when someone shares the content.
   /// Equivalent to `og:video` | "og:video:url".
   #[serde(alias = "og:video:url")]
+  #[cfg_attr(
+    feature = "schemars",
+    schemars(regex(pattern = crate::schema::HTTP_URL_PATTERN))
+  )]
   pub url: Option<Url>,
 
-  /// https:// URL for the video.  #[serde(rename = "og:video:secure_url")]
+  /// https:// URL for the video.
+  #[cfg_attr(
+    feature = "schemars",
+    schemars(regex(pattern = crate::schema::HTTPS_URL_PATTERN))
+  )]
   pub secure_url: Option<Url>,
 
-  /// Equivalent to `og:video`.
-  pub mimetype: Option<String>,
+  /// A MIME type for this video.
+  #[cfg_attr(
+    feature = "schemars",
+    schemars(schema_with = "crate::schema::video_media_type")
+  )]
+  pub mimetype: Option<MediaType>,
 
-  /// Equivalent to `og:video`.
+  /// A description of what is in the video.
   pub alt: Option<String>,
 
-  /// Equivalent to `og:video`.
+  /// The number of pixels wide.
   pub width: Option<u32>,
 
-  /// Equivalent to `og:video`.
+  /// The number of pixels high.
   pub height: Option<u32>,
+
+  /// The URL as originally declared, if it had to be resolved against
+  /// a base URL. Kept for diagnostics only and never serialized.
+  #[serde(skip)]
+  pub original_url: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct ParseVideoUrlError;
 
-impl FromStr for Video {
-  type Err = error::Error;
-  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-    validate_http_url(s)
+impl Video {
+  /// Creates a `Video` from a URL that may be relative or protocol-relative,
+  /// resolving it against `base`.
+  ///
+  /// If the URL had to be resolved, the original value is kept in
+  /// `original_url`.
+  pub fn from_str_with_base(s: &str, base: Option<&Url>) -> Result<Self> {
+    resolve_http_url(s, base)
       .map(|url| Video {
         url: Some(url),
+        original_url: Url::from_str(s).is_err().then(|| s.into()),
         ..Default::default()
       })
       .map_err(|err| error::Error::UrlParseError(s.into()))
   }
+
+  /// Sets the MIME type inferred from the URL's file extension,
+  /// unless a MIME type has already been set.
+  pub fn infer_mimetype(&mut self) -> Option<&MediaType> {
+    if self.mimetype.is_none() {
+      self.mimetype = self.inferred_media_type();
+    }
+
+    self.mimetype.as_ref()
+  }
+}
+
+impl FromStr for Video {
+  type Err = error::Error;
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    Video::from_str_with_base(s, None)
+  }
 }
 
 impl Validatable for Video {
   fn validate(&self) -> Result<()> {
-    self.validate_dimensions().unwrap();
+    self.validate_dimensions()?;
+    self.validate_secure_url()?;
+    self.validate_media_type()
+  }
+}
+
+impl MediaTypeValidator for Video {
+  fn media_kind(&self) -> MediaKind {
+    MediaKind::Video
+  }
+
+  fn media_type(&self) -> Option<MediaType> {
+    self.mimetype.clone()
+  }
+
+  fn media_url(&self) -> Option<Url> {
+    self.url.clone()
+  }
+}
+
+impl SecureURLValidator for Video {
+  fn secure_url(&self) -> Option<Url> {
+    self.secure_url.clone()
+  }
 
-    Ok(())
+  fn url(&self) -> Option<Url> {
+    self.url.clone()
   }
 }
 
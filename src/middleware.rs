@@ -0,0 +1,287 @@
+//! Tower middleware injecting Open Graph tags into HTML responses.
+//!
+//! Handlers attach the metadata of a page to their response as an
+//! extension, either as `OgMetadata` or, for object types, as `OgTags`. The
+//! `OgLayer` renders the properties and splices them into the `<head>` of
+//! `text/html` responses using a `TagInjector`. Responses without metadata,
+//! other content types and compressed bodies are passed through untouched.
+//! A body that cannot be read is answered with a 500 Internal Server Error,
+//! as in the `actix` integration.
+//!
+//! The layer works with any `tower` based framework, such as Axum. This
+//! module is only available with the `tower` feature enabled.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use axum::{response::Html, routing::get, Extension, Router};
+//! use ogp::metadata::OgMetadata;
+//! use ogp::middleware::OgLayer;
+//!
+//! async fn home() -> (Extension<OgMetadata>, Html<&'static str>) {
+//!   let metadata = OgMetadata {
+//!     title: Some("Home".into()),
+//!     ..Default::default()
+//!   };
+//!   let html = "<html><head><title>Home</title></head></html>";
+//!   (Extension(metadata), Html(html))
+//! }
+//!
+//! let app: Router = Router::new()
+//!   .route("/", get(home))
+//!   .layer(OgLayer::new().replace_existing(true));
+//! ```
+
+use std::{
+  future::Future,
+  pin::Pin,
+  task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use http::{
+  header::{CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE},
+  HeaderMap, HeaderValue, Request, Response, StatusCode,
+};
+use http_body::Body;
+use http_body_util::{combinators::UnsyncBoxBody, BodyExt, Full};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::inject::{self, TagInjector};
+
+pub use crate::inject::OgTags;
+
+/// The error type of response bodies.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The body of responses passed through `OgService`.
+pub type OgBody = UnsyncBoxBody<Bytes, BoxError>;
+
+/// A `tower::Layer` wrapping services in `OgService`.
+#[derive(Debug, Clone, Default)]
+pub struct OgLayer {
+  injector: TagInjector,
+}
+
+impl OgLayer {
+  /// Creates a layer that keeps existing Open Graph tags.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Removes the existing Open Graph tags of responses before injecting.
+  pub fn replace_existing(mut self, replace: bool) -> Self {
+    self.injector = self.injector.replace_existing(replace);
+    self
+  }
+}
+
+impl<S> Layer<S> for OgLayer {
+  type Service = OgService<S>;
+
+  fn layer(&self, inner: S) -> Self::Service {
+    OgService {
+      inner,
+      injector: self.injector.clone(),
+    }
+  }
+}
+
+/// Injects the `OgMetadata` or `OgTags` extension of responses into their
+/// HTML body.
+#[derive(Debug, Clone)]
+pub struct OgService<S> {
+  inner: S,
+  injector: TagInjector,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for OgService<S>
+where
+  S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+  S::Future: Send + 'static,
+  ResBody: Body<Data = Bytes> + Send + 'static,
+  ResBody::Error: Into<BoxError>,
+{
+  type Response = Response<OgBody>;
+  type Error = S::Error;
+  type Future =
+    Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+  fn poll_ready(
+    &mut self,
+    cx: &mut Context<'_>,
+  ) -> Poll<Result<(), Self::Error>> {
+    self.inner.poll_ready(cx)
+  }
+
+  fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+    let response = self.inner.call(request);
+    let injector = self.injector.clone();
+    Box::pin(async move {
+      let response = response.await?;
+      Ok(inject(response, &injector).await)
+    })
+  }
+}
+
+/// Injects the properties attached to `response` into its body.
+async fn inject<B>(
+  response: Response<B>,
+  injector: &TagInjector,
+) -> Response<OgBody>
+where
+  B: Body<Data = Bytes> + Send + 'static,
+  B::Error: Into<BoxError>,
+{
+  let (mut parts, body) = response.into_parts();
+
+  let properties = inject::take_properties(
+    parts.extensions.remove(),
+    parts.extensions.remove(),
+  );
+  let properties = match properties {
+    Some(properties) if is_html(&parts.headers) => properties,
+    _ => {
+      let body = body.map_err(Into::into).boxed_unsync();
+      return Response::from_parts(parts, body);
+    }
+  };
+
+  let bytes = match body.collect().await {
+    Ok(collected) => collected.to_bytes(),
+    // Like actix-web, answer a body that cannot be read with a 500.
+    Err(err) => {
+      let err: BoxError = err.into();
+      let body = Full::new(Bytes::from(err.to_string()))
+        .map_err(|never| match never {})
+        .boxed_unsync();
+      let mut response = Response::new(body);
+      *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+      response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; charset=utf-8"),
+      );
+      return response;
+    }
+  };
+
+  let injected = std::str::from_utf8(&bytes)
+    .ok()
+    .and_then(|html| injector.inject(html, &properties));
+  let bytes = match injected {
+    Some(html) => {
+      parts.headers.remove(CONTENT_LENGTH);
+      Bytes::from(html)
+    }
+    None => bytes,
+  };
+
+  let body = Full::new(bytes).map_err(|never| match never {});
+  Response::from_parts(parts, body.boxed_unsync())
+}
+
+/// Whether the response is an uncompressed HTML document.
+fn is_html(headers: &HeaderMap) -> bool {
+  let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+  inject::is_html(header(CONTENT_TYPE), header(CONTENT_ENCODING))
+}
+
+#[cfg(test)]
+mod tests {
+  use axum::{
+    body::Body as AxumBody,
+    http::StatusCode,
+    response::{Html, IntoResponse},
+    routing::get,
+    Extension, Router,
+  };
+  use tower::ServiceExt;
+
+  use super::*;
+  use crate::{
+    inject::tests::{metadata, INJECTED, PAGE},
+    object_type::{article::ArticleMetadata, retype::ObjectMetadata},
+  };
+
+  /// A response body failing to read.
+  struct BrokenBody;
+
+  impl Body for BrokenBody {
+    type Data = Bytes;
+    type Error = std::io::Error;
+
+    fn poll_frame(
+      self: Pin<&mut Self>,
+      _: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Bytes>, Self::Error>>> {
+      Poll::Ready(Some(Err(std::io::Error::other("connection reset"))))
+    }
+  }
+
+  fn app(layer: OgLayer) -> Router {
+    Router::new()
+      .route("/", get(|| async { (Extension(metadata()), Html(PAGE)) }))
+      .route(
+        "/article",
+        get(|| async {
+          let mut article = ArticleMetadata::from_root(metadata());
+          article.add_tag("rust");
+          (Extension(OgTags::new(&article)), Html(PAGE))
+        }),
+      )
+      .route("/plain", get(|| async { Html(PAGE) }))
+      .route(
+        "/broken",
+        get(|| async {
+          (Extension(metadata()), Html(AxumBody::new(BrokenBody)))
+        }),
+      )
+      .route(
+        "/text",
+        get(|| async { (Extension(metadata()), PAGE).into_response() }),
+      )
+      .layer(layer)
+  }
+
+  async fn get_page(app: Router, uri: &str) -> (StatusCode, String) {
+    let request = Request::get(uri).body(AxumBody::empty()).unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, String::from_utf8(bytes.to_vec()).unwrap())
+  }
+
+  #[tokio::test]
+  async fn injects_metadata_into_html() {
+    let (status, html) = get_page(app(OgLayer::new()), "/").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(html, INJECTED);
+
+    let layer = OgLayer::new().replace_existing(true);
+    let (_, html) = get_page(app(layer.clone()), "/").await;
+    assert!(!html.contains("Template"));
+
+    let (_, html) = get_page(app(layer), "/article").await;
+    assert!(html.contains(r#"<meta property="og:type" content="article" />"#));
+    assert!(html.contains(r#"<meta property="article:tag" content="rust" />"#));
+  }
+
+  #[tokio::test]
+  async fn passes_through_other_responses() {
+    let layer = OgLayer::new().replace_existing(true);
+
+    let (_, html) = get_page(app(layer.clone()), "/plain").await;
+    assert_eq!(html, PAGE);
+
+    let (_, text) = get_page(app(layer), "/text").await;
+    assert_eq!(text, PAGE);
+  }
+
+  #[tokio::test]
+  async fn fails_on_unreadable_bodies() {
+    let (status, text) = get_page(app(OgLayer::new()), "/broken").await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(text, "connection reset");
+  }
+}
@@ -2,25 +2,32 @@
 
 use std::{ops::Add, str::FromStr};
 
+use crate::flat::{split_properties, FlatProperties, Properties};
 use crate::metadata::{OgMetadata, OgMetadataBuilder};
-use crate::{convert::ToHTML, error::Error, object_type::ObjectType};
+use crate::object_type::retype::{impl_object_metadata, ObjectMetadata};
+use crate::object_type::ObjectType;
+use crate::{convert::ToHTML, error::Error, Result};
 
 use chrono::serde::ts_seconds_option;
-use chrono::{DateTime, Local, Utc};
+use chrono::{DateTime, Local, SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ArticleMetadata {
   /// When the article was first published.
   #[serde(rename = "article:published_time", with = "ts_seconds_option")]
+  #[cfg_attr(feature = "schemars", schemars(with = "Option<i64>"))]
   published_time: Option<DateTime<Utc>>,
 
   /// When the article was last changed.
   #[serde(rename = "article:modified_time", with = "ts_seconds_option")]
+  #[cfg_attr(feature = "schemars", schemars(with = "Option<i64>"))]
   modified_time: Option<DateTime<Utc>>,
 
   /// When the article is out of date after.
   #[serde(rename = "article:expiration_time", with = "ts_seconds_option")]
+  #[cfg_attr(feature = "schemars", schemars(with = "Option<i64>"))]
   expiration_time: Option<DateTime<Utc>>,
 
   /// Writers of the article.
@@ -44,14 +51,7 @@ impl ArticleMetadata {
     &mut self,
     published_time: impl Into<String>,
   ) -> &mut Self {
-    match DateTime::<Utc>::from_str(&published_time.into()) {
-      Err(err) => panic!("error: {}", err),
-      Ok(date) => {
-        println!("{}", date);
-        self.published_time.insert(date);
-      }
-    };
-
+    self.published_time = Some(Self::date_from_str(published_time));
     self
   }
 
@@ -59,7 +59,7 @@ impl ArticleMetadata {
     &mut self,
     modified_time: impl Into<String>,
   ) -> &mut Self {
-    self.set_date_from_str(modified_time, &self.modified_time);
+    self.modified_time = Some(Self::date_from_str(modified_time));
     self
   }
 
@@ -67,7 +67,7 @@ impl ArticleMetadata {
     &mut self,
     expiration_time: impl Into<String>,
   ) -> &mut Self {
-    self.set_date_from_str(expiration_time, &self.expiration_time);
+    self.expiration_time = Some(Self::date_from_str(expiration_time));
     self
   }
 
@@ -95,17 +95,34 @@ impl ArticleMetadata {
     self
   }
 
-  fn set_date_from_str(
-    &self,
-    date: impl Into<String>,
-    mut prop: &Option<DateTime<Utc>>,
-  ) {
+  pub fn published_time(&self) -> Option<&DateTime<Utc>> {
+    self.published_time.as_ref()
+  }
+
+  pub fn modified_time(&self) -> Option<&DateTime<Utc>> {
+    self.modified_time.as_ref()
+  }
+
+  pub fn expiration_time(&self) -> Option<&DateTime<Utc>> {
+    self.expiration_time.as_ref()
+  }
+
+  pub fn author(&self) -> Option<&str> {
+    self.author.as_deref()
+  }
+
+  pub fn section(&self) -> Option<&str> {
+    self.section.as_deref()
+  }
+
+  pub fn tags(&self) -> &[String] {
+    &self.tags
+  }
+
+  fn date_from_str(date: impl Into<String>) -> DateTime<Utc> {
     match DateTime::<Utc>::from_str(&date.into()) {
       Err(err) => panic!("error: {}", err),
-      Ok(date) => {
-        println!("{}", date);
-        prop.as_ref().insert(&date);
-      }
+      Ok(date) => date,
     }
   }
 }
@@ -129,3 +146,63 @@ impl OgMetadataBuilder {
 }
 
 impl ToHTML for ArticleMetadata {}
+
+impl FlatProperties for ArticleMetadata {
+  fn to_properties(&self) -> Vec<(String, String)> {
+    let date =
+      |date: &DateTime<Utc>| date.to_rfc3339_opts(SecondsFormat::AutoSi, true);
+
+    let mut properties = Properties(self.root.to_properties());
+    properties.push_opt(
+      "article:published_time",
+      self.published_time.as_ref().map(date),
+    );
+    properties.push_opt(
+      "article:modified_time",
+      self.modified_time.as_ref().map(date),
+    );
+    properties.push_opt(
+      "article:expiration_time",
+      self.expiration_time.as_ref().map(date),
+    );
+    properties.push_opt("article:author", self.author.as_ref());
+    properties.push_opt("article:section", self.section.as_ref());
+    for tag in &self.tags {
+      properties.push("article:tag", tag);
+    }
+    properties.0
+  }
+
+  fn from_properties(properties: &[(String, String)]) -> Result<Self> {
+    let (root, type_specific) = split_properties(properties)?;
+    let mut article = Self::from_root(root);
+
+    let date = |property: &str, content: &str| {
+      DateTime::<Utc>::from_str(content).map_err(|_| {
+        Error::InvalidPropertyValue(property.into(), content.into())
+      })
+    };
+
+    for (property, content) in type_specific {
+      match property {
+        "article:published_time" => {
+          article.published_time = Some(date(property, content)?)
+        }
+        "article:modified_time" => {
+          article.modified_time = Some(date(property, content)?)
+        }
+        "article:expiration_time" => {
+          article.expiration_time = Some(date(property, content)?)
+        }
+        "article:author" => article.author = Some(content.into()),
+        "article:section" => article.section = Some(content.into()),
+        "article:tag" => article.tags.push(content.into()),
+        _ => {}
+      }
+    }
+
+    Ok(article)
+  }
+}
+
+impl_object_metadata!(ArticleMetadata, ObjectType::Article, root: root);
@@ -1,10 +1,15 @@
 //! Metadata utility for the Open Graph `book` meta tag.
 
+use crate::convert::ToHTML;
+use crate::flat::{split_properties, FlatProperties, Properties};
 use crate::metadata::{OgMetadata, OgMetadataBuilder};
-use crate::{object_type::ObjectType, Result};
+use crate::object_type::retype::{impl_object_metadata, ObjectMetadata};
+use crate::object_type::ObjectType;
+use crate::Result;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Book {
   /// Who wrote this book.
   #[serde(rename = "book:author")]
@@ -15,7 +20,7 @@ pub struct Book {
   pub isbn: Option<String>,
 
   /// The date the book was released.
-  #[serde(rename = "book:release_date ")]
+  #[serde(rename = "book:release_date")]
   pub release_date: Option<String>,
 
   /// Tag words associated with this book.
@@ -34,3 +39,35 @@ impl Book {
     }
   }
 }
+
+impl ToHTML for Book {}
+
+impl FlatProperties for Book {
+  fn to_properties(&self) -> Vec<(String, String)> {
+    let mut properties = Properties(self.root().to_properties());
+    properties.push_opt("book:author", self.author.as_ref());
+    properties.push_opt("book:isbn", self.isbn.as_ref());
+    properties.push_opt("book:release_date", self.release_date.as_ref());
+    properties.push_opt("book:tag", self.tag.as_ref());
+    properties.0
+  }
+
+  fn from_properties(properties: &[(String, String)]) -> Result<Self> {
+    let (root, type_specific) = split_properties(properties)?;
+    let mut book = Self::from_root(root);
+
+    for (property, content) in type_specific {
+      match property {
+        "book:author" => book.author = Some(content.into()),
+        "book:isbn" => book.isbn = Some(content.into()),
+        "book:release_date" => book.release_date = Some(content.into()),
+        "book:tag" => book.tag = Some(content.into()),
+        _ => {}
+      }
+    }
+
+    Ok(book)
+  }
+}
+
+impl_object_metadata!(Book, ObjectType::Book, builder: metadata);
@@ -0,0 +1,262 @@
+//! Resolution of `Determiner::Auto` into "a" or "an".
+//!
+//! The choice between "a" and "an" depends on how the following word is
+//! pronounced, not on how it is spelled. The heuristics below cover the
+//! common cases of English:
+//!
+//! - words starting with a vowel letter: "an Eagles album"
+//! - vowel letters pronounced with a consonant sound: "a university",
+//!   "a one-off", "a European tour"
+//! - silent "h": "an hour", "an honest review"
+//! - acronyms spelled out letter by letter: "an FAQ", "an HTML page",
+//!   "a URL"
+//! - acronyms pronounced as words: "a NASA mission", "an OPEC meeting"
+//! - numbers: "an 8-bit console", "an 11-page essay", "a 1990s hit"
+
+use crate::metadata::OgMetadata;
+
+use super::Determiner;
+
+/// Letters whose name starts with a vowel sound, e.g. "ef" for `F`.
+const VOWEL_SOUND_LETTERS: &str = "AEFHILMNORSX";
+
+/// Word prefixes starting with a vowel letter but a consonant sound.
+const CONSONANT_SOUND_PREFIXES: [&str; 16] = [
+  "uni", "use", "usa", "usu", "uti", "ure", "uri", "uro", "ufo", "uga", "uku",
+  "ubi", "eu", "ewe", "ouija", "uvu",
+];
+
+/// Whole words starting with a vowel letter but a consonant sound. Unlike
+/// "oneness", "onerous" starts with a vowel sound.
+const CONSONANT_SOUND_WORDS: [&str; 5] =
+  ["one", "ones", "once", "oneself", "oneness"];
+
+/// Exceptions to `CONSONANT_SOUND_PREFIXES`, e.g. "an unimportant detail".
+const VOWEL_SOUND_PREFIXES: [&str; 3] = ["unim", "unin", "unid"];
+
+/// Word prefixes starting with a silent "h".
+const SILENT_H_PREFIXES: [&str; 5] =
+  ["hour", "honest", "honor", "honour", "heir"];
+
+/// Acronyms that are pronounced as words rather than letter by letter.
+const WORD_ACRONYMS: [&str; 12] = [
+  "NASA", "NATO", "NAFTA", "LASER", "RADAR", "SCUBA", "FIFA", "MIDI", "SWAT",
+  "NIMBY", "SIM", "LOL",
+];
+
+impl Determiner {
+  /// The determiner as it appears in a sentence, e.g. "an".
+  ///
+  /// `Auto` has no textual form and returns an empty string;
+  /// use `resolve` first.
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Determiner::A => "a",
+      Determiner::An => "an",
+      Determiner::The => "the",
+      Determiner::Blank | Determiner::Auto => "",
+    }
+  }
+
+  /// Chooses between `Determiner::A` and `Determiner::An` for `phrase`,
+  /// based on how its first word is pronounced.
+  pub fn for_phrase(phrase: &str) -> Determiner {
+    if starts_with_vowel_sound(phrase) {
+      Determiner::An
+    } else {
+      Determiner::A
+    }
+  }
+
+  /// Resolves `Determiner::Auto` for `phrase`. Other determiners are
+  /// returned unchanged.
+  pub fn resolve(&self, phrase: &str) -> Determiner {
+    match self {
+      Determiner::Auto => Determiner::for_phrase(phrase),
+      determiner => determiner.clone(),
+    }
+  }
+
+  /// Renders `phrase` preceded by this determiner, resolving `Auto`,
+  /// e.g. "an Eagles album".
+  pub fn apply(&self, phrase: &str) -> String {
+    let phrase = phrase.trim();
+    match self.resolve(phrase).as_str() {
+      "" => phrase.to_string(),
+      determiner => format!("{} {}", determiner, phrase),
+    }
+  }
+}
+
+impl OgMetadata {
+  /// Returns the determiner with `Determiner::Auto` resolved
+  /// for the title.
+  pub fn resolved_determiner(&self) -> Option<Determiner> {
+    let determiner = self.determiner.as_ref()?;
+    let title = self.title.as_deref().unwrap_or_default();
+    Some(determiner.resolve(title))
+  }
+
+  /// Renders the title preceded by its determiner, e.g. "an Eagles album".
+  pub fn title_with_determiner(&self) -> Option<String> {
+    let title = self.title.as_deref()?;
+    let determiner = self.determiner.clone().unwrap_or_default();
+    Some(determiner.apply(title))
+  }
+}
+
+/// Checks whether the first word of `phrase` starts with a vowel sound.
+fn starts_with_vowel_sound(phrase: &str) -> bool {
+  let word: String = phrase
+    .trim_start_matches(|c: char| !c.is_alphanumeric())
+    .chars()
+    .take_while(|c| c.is_alphanumeric())
+    .collect();
+
+  let Some(first) = word.chars().next() else {
+    return false;
+  };
+
+  if first.is_ascii_digit() {
+    return number_starts_with_vowel_sound(&word);
+  }
+
+  // A single letter, e.g. "X" in "X-Men", is pronounced by its name.
+  if word.chars().count() == 1 {
+    return VOWEL_SOUND_LETTERS.contains(first.to_ascii_uppercase());
+  }
+
+  if WORD_ACRONYMS.contains(&word.trim_end_matches('s')) {
+    return is_vowel(first);
+  }
+  if is_acronym(&word) {
+    return VOWEL_SOUND_LETTERS.contains(first);
+  }
+
+  let lower = word.to_lowercase();
+  if SILENT_H_PREFIXES.iter().any(|p| lower.starts_with(p)) {
+    return true;
+  }
+  if VOWEL_SOUND_PREFIXES.iter().any(|p| lower.starts_with(p)) {
+    return true;
+  }
+  if CONSONANT_SOUND_WORDS.contains(&lower.as_str()) {
+    return false;
+  }
+  if CONSONANT_SOUND_PREFIXES
+    .iter()
+    .any(|p| lower.starts_with(p))
+  {
+    return false;
+  }
+
+  is_vowel(first)
+}
+
+/// Numbers are read out loud: "an 8", "an 11", "an 18", "an 80", "an 11000".
+fn number_starts_with_vowel_sound(word: &str) -> bool {
+  let digits: String = word.chars().take_while(char::is_ascii_digit).collect();
+  if digits.starts_with('8') {
+    return true;
+  }
+
+  // "eleven" and "eighteen" as well as "eleven thousand", "eighteen million".
+  (digits.starts_with("11") || digits.starts_with("18"))
+    && digits.len() % 3 == 2
+}
+
+/// Checks whether `word` is an acronym like "FAQ", "URLs" or "HTTPS".
+///
+/// Longer all-caps words with vowels, e.g. "HOUSE" in a shouted title, are
+/// read as words.
+fn is_acronym(word: &str) -> bool {
+  let letters = word.strip_suffix('s').unwrap_or(word);
+  let len = letters.chars().count();
+  len >= 2
+    && letters
+      .chars()
+      .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+    && (len <= 4 || !letters.chars().any(is_vowel))
+}
+
+fn is_vowel(c: char) -> bool {
+  "aeiouAEIOU".contains(c)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn article(phrase: &str) -> &'static str {
+    Determiner::Auto.resolve(phrase).as_str()
+  }
+
+  #[test]
+  fn resolves_regular_words() {
+    assert_eq!(article("Eagles album"), "an");
+    assert_eq!(article("apple"), "an");
+    assert_eq!(article("banana"), "a");
+    assert_eq!(article("  \"Ode\" to joy"), "an");
+  }
+
+  #[test]
+  fn resolves_pronunciation_exceptions() {
+    assert_eq!(article("university"), "a");
+    assert_eq!(article("European tour"), "a");
+    assert_eq!(article("one-off"), "a");
+    assert_eq!(article("unimportant detail"), "an");
+    assert_eq!(article("umbrella"), "an");
+    assert_eq!(article("hour"), "an");
+    assert_eq!(article("honest review"), "an");
+    assert_eq!(article("house"), "a");
+    assert_eq!(article("onerous task"), "an");
+    assert_eq!(article("oneness"), "a");
+  }
+
+  #[test]
+  fn resolves_acronyms() {
+    assert_eq!(article("FAQ"), "an");
+    assert_eq!(article("HTML page"), "an");
+    assert_eq!(article("MRI scan"), "an");
+    assert_eq!(article("URL"), "a");
+    assert_eq!(article("UFO"), "a");
+    assert_eq!(article("NASA mission"), "a");
+    assert_eq!(article("OPEC meeting"), "an");
+    assert_eq!(article("X-Men comic"), "an");
+    assert_eq!(article("HTTPS link"), "an");
+    assert_eq!(article("HOUSE OF CARDS"), "a");
+    assert_eq!(article("ELEPHANT"), "an");
+    assert_eq!(article("LASER pointer"), "a");
+  }
+
+  #[test]
+  fn resolves_numbers() {
+    assert_eq!(article("8-bit console"), "an");
+    assert_eq!(article("11-page essay"), "an");
+    assert_eq!(article("18th century"), "an");
+    assert_eq!(article("1990s hit"), "a");
+    assert_eq!(article("110 year old tree"), "a");
+  }
+
+  #[test]
+  fn keeps_explicit_determiners() {
+    assert_eq!(Determiner::The.resolve("apple"), Determiner::The);
+    assert_eq!(Determiner::A.resolve("apple"), Determiner::A);
+    assert_eq!(Determiner::Blank.apply("apple"), "apple");
+  }
+
+  #[test]
+  fn renders_title_with_determiner() {
+    let metadata = OgMetadata {
+      title: Some("Eagles album".into()),
+      determiner: Some(Determiner::Auto),
+      ..Default::default()
+    };
+
+    assert_eq!(metadata.resolved_determiner(), Some(Determiner::An));
+    assert_eq!(
+      metadata.title_with_determiner().as_deref(),
+      Some("an Eagles album")
+    );
+  }
+}
@@ -30,17 +30,25 @@
 //!
 //! ```
 
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 
+use crate::{error::Error, utils::closest_match};
+
 pub mod article;
 pub mod book;
+mod determiner;
 pub mod music;
 pub mod profile;
+pub mod properties;
+pub mod retype;
 pub mod video;
 pub mod website;
 
 /// The type of object in the graph this refers to.
 #[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum ObjectType {
   /// Represents a song in the music category.
   #[serde(rename = "music.song")]
@@ -93,7 +101,41 @@ pub enum ObjectType {
 }
 
 impl ObjectType {
-  /// Performs a value conversion from any value that implements the `Into` trait
+  /// All object types defined by the Open Graph protocol.
+  pub const ALL: [ObjectType; 12] = [
+    ObjectType::MusicSong,
+    ObjectType::MusicAlbum,
+    ObjectType::MusicPlaylist,
+    ObjectType::MusicRadioStation,
+    ObjectType::VideoMovie,
+    ObjectType::VideoEpisode,
+    ObjectType::VideoTvShow,
+    ObjectType::VideoOther,
+    ObjectType::Article,
+    ObjectType::Book,
+    ObjectType::Profile,
+    ObjectType::Website,
+  ];
+
+  /// The value of the `og:type` meta tag, e.g. `music.song`.
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      ObjectType::MusicSong => "music.song",
+      ObjectType::MusicAlbum => "music.album",
+      ObjectType::MusicPlaylist => "music.playlist",
+      ObjectType::MusicRadioStation => "music.radio_station",
+      ObjectType::VideoMovie => "video.movie",
+      ObjectType::VideoEpisode => "video.episode",
+      ObjectType::VideoTvShow => "video.tv_show",
+      ObjectType::VideoOther => "video.other",
+      ObjectType::Article => "article",
+      ObjectType::Book => "book",
+      ObjectType::Profile => "profile",
+      ObjectType::Website => "website",
+    }
+  }
+
+  /// Performs a lenient value conversion from any value that implements the `Into` trait
   /// to an Open Graph object type.
   ///
   /// ## Returns
@@ -101,6 +143,7 @@ impl ObjectType {
   /// The transformed string value as `ObjectType`.
   ///
   /// Falls back to `ObjectType::Website`, if provided value does not match any valid string.
+  /// Use `str::parse` to reject unknown values instead.
   pub fn from_string(value: impl Into<String>) -> ObjectType {
     let obj_type: &str = &value.into();
 
@@ -123,10 +166,12 @@ impl ObjectType {
 }
 
 /// Enum representing the word that appears before an Open Graph object's title in a sentence.
-/// If auto is chosen, the consumer of your data should chose between "a" or "an".
+/// If auto is chosen, the consumer of your data should chose between "a" or "an";
+/// `Determiner::resolve` does that for a given title.
 ///
 /// Default is "" (blank).
 #[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Determiner {
   #[serde(rename = "a")]
   A,
@@ -145,7 +190,35 @@ pub enum Determiner {
   Auto,
 }
 
+impl FromStr for ObjectType {
+  type Err = Error;
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    ObjectType::ALL
+      .into_iter()
+      .find(|object_type| object_type.as_str() == s)
+      .ok_or_else(|| {
+        let names = ObjectType::ALL.map(|object_type| object_type.as_str());
+        let suggestion = closest_match(s, &names).unwrap_or_default();
+        Error::InvalidObjectType(s.into(), suggestion.into())
+      })
+  }
+}
+
+impl TryFrom<&str> for ObjectType {
+  type Error = Error;
+  fn try_from(value: &str) -> crate::Result<Self> {
+    ObjectType::from_str(value)
+  }
+}
+
+/// Valid values of the `og:determiner` meta tag.
+const DETERMINERS: [&str; 5] = ["a", "an", "the", "", "auto"];
+
 impl Determiner {
+  /// Performs a lenient value conversion to a determiner.
+  ///
+  /// Falls back to `Determiner::Blank`, if provided value does not match any valid string.
+  /// Use `str::parse` to reject unknown values instead.
   pub fn from_string(value: impl Into<String>) -> Determiner {
     let determ: &str = &value.into();
     match determ {
@@ -158,6 +231,25 @@ impl Determiner {
   }
 }
 
+impl FromStr for Determiner {
+  type Err = Error;
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    if DETERMINERS.contains(&s) {
+      return Ok(Determiner::from_string(s));
+    }
+
+    let suggestion = closest_match(s, &DETERMINERS).unwrap_or_default();
+    Err(Error::InvalidDeterminer(s.into(), suggestion.into()))
+  }
+}
+
+impl TryFrom<&str> for Determiner {
+  type Error = Error;
+  fn try_from(value: &str) -> crate::Result<Self> {
+    Determiner::from_str(value)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -213,6 +305,34 @@ mod tests {
       ObjectType::VideoTvShow
     );
   }
+
+  #[test]
+  fn object_type_strict_conversion() {
+    assert_eq!(
+      "music.song".parse::<ObjectType>().unwrap(),
+      ObjectType::MusicSong
+    );
+    assert_eq!(ObjectType::try_from("book").unwrap(), ObjectType::Book);
+    for object_type in ObjectType::ALL {
+      assert_eq!(
+        object_type.as_str().parse::<ObjectType>().unwrap(),
+        object_type
+      );
+    }
+  }
+
+  #[test]
+  fn object_type_strict_conversion_suggests_closest() {
+    let err = "Artcle".parse::<ObjectType>().unwrap_err();
+    assert!(matches!(&err, Error::InvalidObjectType(value, suggestion)
+      if value == "Artcle" && suggestion == "article"));
+
+    let err = ObjectType::try_from("video.tvshow").unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Invalid object type 'video.tvshow'. Did you mean 'video.tv_show'?"
+    );
+  }
   // ========================================
   // endregion ObjectType
 
@@ -241,5 +361,15 @@ mod tests {
   fn auto_determiner() {
     assert_eq!(Determiner::from_string("auto"), Determiner::Auto)
   }
+
+  #[test]
+  fn determiner_strict_conversion() {
+    assert_eq!("".parse::<Determiner>().unwrap(), Determiner::Blank);
+    assert_eq!(Determiner::try_from("auto").unwrap(), Determiner::Auto);
+
+    let err = "teh".parse::<Determiner>().unwrap_err();
+    assert!(matches!(&err, Error::InvalidDeterminer(value, suggestion)
+      if value == "teh" && suggestion == "the"));
+  }
   // endregion Determiner
 }
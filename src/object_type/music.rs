@@ -1,11 +1,14 @@
 //! Metadata utility for the Open Graph `music` meta tag.
 
+use crate::flat::impl_flat_properties;
 use crate::metadata::{OgMetadata, OgMetadataBuilder};
-use crate::{object_type::ObjectType, Result};
+use crate::object_type::{retype::impl_object_metadata, ObjectType};
+use crate::Result;
 use serde::{de::IntoDeserializer, Deserialize, Serialize};
 
 // TODO: Add missing props
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MusicAlbum {
   #[serde(flatten)]
   metadata: OgMetadataBuilder,
@@ -21,6 +24,7 @@ impl MusicAlbum {
 
 // TODO: Add missing props
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MusicSong {
   #[serde(flatten)]
   metadata: OgMetadataBuilder,
@@ -36,6 +40,7 @@ impl MusicSong {
 
 // TODO: Add missing props
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MusicPlaylist {
   #[serde(flatten)]
   metadata: OgMetadataBuilder,
@@ -51,6 +56,7 @@ impl MusicPlaylist {
 
 // TODO: Add missing props
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MusicRadioStation {
   #[serde(flatten)]
   metadata: OgMetadataBuilder,
@@ -63,3 +69,10 @@ impl MusicRadioStation {
     }
   }
 }
+
+impl_object_metadata!(MusicAlbum, ObjectType::MusicAlbum, builder: metadata);
+impl_object_metadata!(MusicSong, ObjectType::MusicSong, builder: metadata);
+impl_object_metadata!(MusicPlaylist, ObjectType::MusicPlaylist, builder: metadata);
+impl_object_metadata!(MusicRadioStation, ObjectType::MusicRadioStation, builder: metadata);
+
+impl_flat_properties!(MusicAlbum, MusicSong, MusicPlaylist, MusicRadioStation);
@@ -1,10 +1,14 @@
 //! Metadata utility for the Open Graph `profile` meta tag.
 
+use crate::flat::{split_properties, FlatProperties, Properties};
 use crate::metadata::{OgMetadata, OgMetadataBuilder};
-use crate::{convert::ToHTML, object_type::ObjectType, Result};
+use crate::object_type::retype::{impl_object_metadata, ObjectMetadata};
+use crate::object_type::ObjectType;
+use crate::{convert::ToHTML, error::Error, Result};
 use serde::{de::IntoDeserializer, Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Gender {
   #[serde(rename = "male")]
   Male,
@@ -14,6 +18,7 @@ pub enum Gender {
 }
 
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ProfileMetadata {
   /// A name normally given to an individual by a parent or self-chosen.
   #[serde(rename = "profile:first_name")]
@@ -53,3 +58,50 @@ impl OgMetadataBuilder {
 }
 
 impl ToHTML for ProfileMetadata {}
+
+impl FlatProperties for ProfileMetadata {
+  fn to_properties(&self) -> Vec<(String, String)> {
+    let mut properties = Properties(self.root.to_properties());
+    properties.push_opt("profile:first_name", self.first_name.as_ref());
+    properties.push_opt("profile:last_name", self.last_name.as_ref());
+    properties.push_opt("profile:username", self.username.as_ref());
+    if let Some(gender) = &self.gender {
+      let gender = match gender {
+        Gender::Male => "male",
+        Gender::Female => "female",
+      };
+      properties.push("profile:gender", gender);
+    }
+    properties.0
+  }
+
+  fn from_properties(properties: &[(String, String)]) -> Result<Self> {
+    let (root, type_specific) = split_properties(properties)?;
+    let mut profile = Self::from_root(root);
+
+    for (property, content) in type_specific {
+      match property {
+        "profile:first_name" => profile.first_name = Some(content.into()),
+        "profile:last_name" => profile.last_name = Some(content.into()),
+        "profile:username" => profile.username = Some(content.into()),
+        "profile:gender" => {
+          profile.gender = Some(match content {
+            "male" => Gender::Male,
+            "female" => Gender::Female,
+            _ => {
+              return Err(Error::InvalidPropertyValue(
+                property.into(),
+                content.into(),
+              ))
+            }
+          })
+        }
+        _ => {}
+      }
+    }
+
+    Ok(profile)
+  }
+}
+
+impl_object_metadata!(ProfileMetadata, ObjectType::Profile, root: root);
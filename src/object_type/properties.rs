@@ -0,0 +1,285 @@
+//! The properties defined by the Open Graph protocol for each object type.
+
+use serde::Serialize;
+
+use super::ObjectType;
+
+/// A property defined by the Open Graph protocol.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropertyInfo {
+  /// The property name, e.g. `og:title`.
+  pub name: &'static str,
+
+  /// The type of value, e.g. `url` or `datetime`. Arrays end with `[]`.
+  pub value_type: &'static str,
+
+  /// Whether every page must declare the property. These are the
+  /// properties `OgMetadata::validate`, and thus `ogp validate`, requires.
+  pub required: bool,
+
+  /// A short description of the property.
+  pub description: &'static str,
+}
+
+const fn property(
+  name: &'static str,
+  value_type: &'static str,
+  description: &'static str,
+) -> PropertyInfo {
+  PropertyInfo {
+    name,
+    value_type,
+    required: false,
+    description,
+  }
+}
+
+const fn required(
+  name: &'static str,
+  value_type: &'static str,
+  description: &'static str,
+) -> PropertyInfo {
+  PropertyInfo {
+    name,
+    value_type,
+    required: true,
+    description,
+  }
+}
+
+/// Properties shared by all object types.
+pub const COMMON_PROPERTIES: [PropertyInfo; 23] = [
+  required("og:title", "string", "The title of the object"),
+  property(
+    "og:type",
+    "enum",
+    "The type of the object, e.g. video.movie. Defaults to website",
+  ),
+  property("og:image", "url[]", "An image representing the object"),
+  required("og:url", "url", "The canonical URL of the object"),
+  required(
+    "og:description",
+    "string",
+    "A one to two sentence description",
+  ),
+  property("og:site_name", "string", "The name of the overall site"),
+  property(
+    "og:determiner",
+    "enum",
+    "The word before the title: a, an, the, \"\" or auto",
+  ),
+  property("og:locale", "string", "The locale, e.g. en_US"),
+  property("og:locale:alternate", "string[]", "Other available locales"),
+  property("og:image:url", "url", "Identical to og:image"),
+  property("og:image:secure_url", "url", "An HTTPS URL of the image"),
+  property("og:image:type", "mime_type", "The MIME type of the image"),
+  property(
+    "og:image:width",
+    "integer",
+    "The width of the image in pixels",
+  ),
+  property(
+    "og:image:height",
+    "integer",
+    "The height of the image in pixels",
+  ),
+  property("og:image:alt", "string", "A description of the image"),
+  property("og:video", "url[]", "A video complementing the object"),
+  property("og:video:secure_url", "url", "An HTTPS URL of the video"),
+  property("og:video:type", "mime_type", "The MIME type of the video"),
+  property(
+    "og:video:width",
+    "integer",
+    "The width of the video in pixels",
+  ),
+  property(
+    "og:video:height",
+    "integer",
+    "The height of the video in pixels",
+  ),
+  property(
+    "og:audio",
+    "url[]",
+    "An audio file complementing the object",
+  ),
+  property(
+    "og:audio:secure_url",
+    "url",
+    "An HTTPS URL of the audio file",
+  ),
+  property(
+    "og:audio:type",
+    "mime_type",
+    "The MIME type of the audio file",
+  ),
+];
+
+const MUSIC_SONG: [PropertyInfo; 5] = [
+  property(
+    "music:duration",
+    "integer",
+    "The length of the song in seconds",
+  ),
+  property(
+    "music:album",
+    "music.album[]",
+    "The album this song is from",
+  ),
+  property("music:album:disc", "integer", "Which disc of the album"),
+  property("music:album:track", "integer", "Which track of the album"),
+  property(
+    "music:musician",
+    "profile[]",
+    "The musician that made the song",
+  ),
+];
+
+const MUSIC_ALBUM: [PropertyInfo; 5] = [
+  property("music:song", "music.song[]", "A song on this album"),
+  property("music:song:disc", "integer", "Which disc the song is on"),
+  property("music:song:track", "integer", "Which track the song is"),
+  property(
+    "music:musician",
+    "profile[]",
+    "The musician that made the album",
+  ),
+  property(
+    "music:release_date",
+    "datetime",
+    "When the album was released",
+  ),
+];
+
+const MUSIC_PLAYLIST: [PropertyInfo; 4] = [
+  property("music:song", "music.song[]", "A song on this playlist"),
+  property("music:song:disc", "integer", "Which disc the song is on"),
+  property("music:song:track", "integer", "Which track the song is"),
+  property("music:creator", "profile", "The creator of the playlist"),
+];
+
+const MUSIC_RADIO_STATION: [PropertyInfo; 1] = [property(
+  "music:creator",
+  "profile",
+  "The creator of the station",
+)];
+
+const VIDEO: [PropertyInfo; 7] = [
+  property("video:actor", "profile[]", "Actors in the video"),
+  property("video:actor:role", "string", "The role the actor played"),
+  property("video:director", "profile[]", "Directors of the video"),
+  property("video:writer", "profile[]", "Writers of the video"),
+  property(
+    "video:duration",
+    "integer",
+    "The length of the video in seconds",
+  ),
+  property(
+    "video:release_date",
+    "datetime",
+    "When the video was released",
+  ),
+  property(
+    "video:tag",
+    "string[]",
+    "Tag words associated with the video",
+  ),
+];
+
+const VIDEO_EPISODE: [PropertyInfo; 1] = [property(
+  "video:series",
+  "video.tv_show",
+  "Which series this episode belongs to",
+)];
+
+const ARTICLE: [PropertyInfo; 6] = [
+  property(
+    "article:published_time",
+    "datetime",
+    "When the article was first published",
+  ),
+  property(
+    "article:modified_time",
+    "datetime",
+    "When the article was last changed",
+  ),
+  property(
+    "article:expiration_time",
+    "datetime",
+    "When the article is out of date after",
+  ),
+  property("article:author", "profile[]", "Writers of the article"),
+  property("article:section", "string", "A high-level section name"),
+  property(
+    "article:tag",
+    "string[]",
+    "Tag words associated with the article",
+  ),
+];
+
+const BOOK: [PropertyInfo; 4] = [
+  property("book:author", "profile[]", "Who wrote the book"),
+  property("book:isbn", "string", "The ISBN"),
+  property(
+    "book:release_date",
+    "datetime",
+    "When the book was released",
+  ),
+  property("book:tag", "string[]", "Tag words associated with the book"),
+];
+
+const PROFILE: [PropertyInfo; 4] = [
+  property("profile:first_name", "string", "The first name"),
+  property("profile:last_name", "string", "The last name"),
+  property("profile:username", "string", "A short unique string"),
+  property("profile:gender", "enum", "Their gender: male or female"),
+];
+
+impl ObjectType {
+  /// The properties specific to this object type, e.g. `article:author`.
+  pub fn type_properties(&self) -> Vec<PropertyInfo> {
+    match self {
+      ObjectType::MusicSong => MUSIC_SONG.to_vec(),
+      ObjectType::MusicAlbum => MUSIC_ALBUM.to_vec(),
+      ObjectType::MusicPlaylist => MUSIC_PLAYLIST.to_vec(),
+      ObjectType::MusicRadioStation => MUSIC_RADIO_STATION.to_vec(),
+      ObjectType::VideoEpisode => [VIDEO.as_slice(), &VIDEO_EPISODE].concat(),
+      ObjectType::VideoMovie
+      | ObjectType::VideoTvShow
+      | ObjectType::VideoOther => VIDEO.to_vec(),
+      ObjectType::Article => ARTICLE.to_vec(),
+      ObjectType::Book => BOOK.to_vec(),
+      ObjectType::Profile => PROFILE.to_vec(),
+      ObjectType::Website => Vec::new(),
+    }
+  }
+
+  /// All properties of this object type, common properties first.
+  pub fn properties(&self) -> Vec<PropertyInfo> {
+    [COMMON_PROPERTIES.as_slice(), &self.type_properties()].concat()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn lists_properties_per_type() {
+    assert!(ObjectType::Website.type_properties().is_empty());
+    assert_eq!(
+      ObjectType::Website.properties().len(),
+      COMMON_PROPERTIES.len()
+    );
+
+    let episode = ObjectType::VideoEpisode.type_properties();
+    assert_eq!(episode.last().map(|p| p.name), Some("video:series"));
+
+    let required: Vec<_> = ObjectType::Article
+      .properties()
+      .into_iter()
+      .filter(|p| p.required)
+      .map(|p| p.name)
+      .collect();
+    assert_eq!(required, ["og:title", "og:url", "og:description"]);
+  }
+}
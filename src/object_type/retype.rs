@@ -0,0 +1,264 @@
+//! Conversion between Open Graph object types.
+//!
+//! All object types share the same `OgMetadata` root (title, URL, images
+//! and so on) and add their own, namespaced properties on top of it,
+//! e.g. `article:author`. Re-typing moves the root across and reports
+//! the type-specific properties that the target type cannot hold.
+//!
+//! ```rust
+//! use ogp::metadata::OgMetadataBuilder;
+//! use ogp::object_type::{article::Article, retype::ObjectMetadata};
+//! use ogp::object_type::website::WebsiteMetadata;
+//!
+//! let mut builder = OgMetadataBuilder::new();
+//! builder.set_title("Release notes");
+//!
+//! let mut article = builder.article();
+//! article.set_author("Jane Doe");
+//!
+//! let retyped = article.retype::<WebsiteMetadata>();
+//! assert_eq!(retyped.lost_properties, vec!["article:author"]);
+//! assert_eq!(retyped.metadata.root().title.as_deref(), Some("Release notes"));
+//! ```
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+  error::Error, metadata::OgMetadata, object_type::ObjectType, Result,
+};
+
+/// An object type struct built on top of the shared `OgMetadata` root.
+pub trait ObjectMetadata: Serialize + Sized {
+  /// The object type this struct represents.
+  fn object_type() -> ObjectType;
+
+  /// Returns a copy of the metadata shared by all object types.
+  fn root(&self) -> OgMetadata;
+
+  /// Consumes the object and returns the metadata shared by all object types.
+  fn into_root(self) -> OgMetadata;
+
+  /// Replaces the metadata shared by all object types, keeping the
+  /// type-specific properties. The object type of `root` is overwritten.
+  fn set_root(&mut self, root: OgMetadata);
+
+  /// Creates the object from shared metadata, without any type-specific
+  /// properties. The object type of `root` is overwritten.
+  fn from_root(root: OgMetadata) -> Self;
+
+  /// Names of the type-specific properties that have a value,
+  /// e.g. `article:author`.
+  fn type_specific_properties(&self) -> Vec<String> {
+    let Ok(Value::Object(properties)) = serde_json::to_value(self) else {
+      return Vec::new();
+    };
+
+    properties
+      .into_iter()
+      .filter(|(property, value)| {
+        let is_empty = match value {
+          Value::Null => true,
+          Value::Array(values) => values.is_empty(),
+          _ => false,
+        };
+        !property.starts_with("og:") && !is_empty
+      })
+      .map(|(property, _)| property.trim().to_string())
+      .collect()
+  }
+
+  /// Converts into another object type, moving the shared metadata across.
+  ///
+  /// Type-specific properties are dropped; their names are reported
+  /// in `Retyped::lost_properties`.
+  fn retype<T: ObjectMetadata>(self) -> Retyped<T> {
+    let lost_properties = self.type_specific_properties();
+    Retyped {
+      metadata: T::from_root(self.into_root()),
+      lost_properties,
+    }
+  }
+
+  /// Converts into another object type, failing with
+  /// `Error::LossyRetype` if any type-specific property would be lost.
+  fn try_retype<T: ObjectMetadata>(self) -> Result<T> {
+    let retyped = self.retype::<T>();
+    if retyped.is_lossless() {
+      return Ok(retyped.metadata);
+    }
+
+    Err(Error::LossyRetype(
+      Self::object_type().as_str().into(),
+      T::object_type().as_str().into(),
+      retyped.lost_properties.join(", "),
+    ))
+  }
+}
+
+/// The result of converting between object types.
+#[derive(Debug, Clone)]
+pub struct Retyped<T> {
+  /// The converted object.
+  pub metadata: T,
+
+  /// Type-specific properties of the source that have been dropped.
+  pub lost_properties: Vec<String>,
+}
+
+impl<T> Retyped<T> {
+  /// Checks whether no property has been dropped.
+  pub fn is_lossless(&self) -> bool {
+    self.lost_properties.is_empty()
+  }
+}
+
+impl OgMetadata {
+  /// Re-types parsed metadata, e.g. the result of `extract::extract`,
+  /// into an object type struct.
+  pub fn retype<T: ObjectMetadata>(self) -> T {
+    T::from_root(self)
+  }
+}
+
+/// Implements `ObjectMetadata` for an object type struct, given the field
+/// that holds its `OgMetadata` root or `OgMetadataBuilder`.
+macro_rules! impl_object_metadata {
+  ($struct:ty, $object_type:expr, root: $field:ident) => {
+    impl $crate::object_type::retype::ObjectMetadata for $struct {
+      fn object_type() -> $crate::object_type::ObjectType {
+        $object_type
+      }
+
+      fn root(&self) -> $crate::metadata::OgMetadata {
+        self.$field.clone()
+      }
+
+      fn into_root(self) -> $crate::metadata::OgMetadata {
+        self.$field
+      }
+
+      fn set_root(&mut self, root: $crate::metadata::OgMetadata) {
+        self.$field = $crate::metadata::OgMetadata {
+          object_type: $object_type,
+          ..root
+        };
+      }
+
+      #[allow(clippy::needless_update)]
+      fn from_root(root: $crate::metadata::OgMetadata) -> Self {
+        Self {
+          $field: $crate::metadata::OgMetadata {
+            object_type: $object_type,
+            ..root
+          },
+          ..Default::default()
+        }
+      }
+    }
+  };
+  ($struct:ty, $object_type:expr, builder: $field:ident) => {
+    impl $crate::object_type::retype::ObjectMetadata for $struct {
+      fn object_type() -> $crate::object_type::ObjectType {
+        $object_type
+      }
+
+      fn root(&self) -> $crate::metadata::OgMetadata {
+        self.$field.get_metadata()
+      }
+
+      fn into_root(self) -> $crate::metadata::OgMetadata {
+        self.$field.into()
+      }
+
+      fn set_root(&mut self, root: $crate::metadata::OgMetadata) {
+        self.$field.set_metadata($crate::metadata::OgMetadata {
+          object_type: $object_type,
+          ..root
+        });
+      }
+
+      #[allow(clippy::needless_update)]
+      fn from_root(root: $crate::metadata::OgMetadata) -> Self {
+        let root = $crate::metadata::OgMetadata {
+          object_type: $object_type,
+          ..root
+        };
+        Self {
+          $field: root.into(),
+          ..Default::default()
+        }
+      }
+    }
+  };
+}
+
+pub(crate) use impl_object_metadata;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::metadata::OgMetadataBuilder;
+  use crate::object_type::{
+    article::ArticleMetadata, book::Book, music::MusicSong,
+    profile::ProfileMetadata, website::WebsiteMetadata,
+  };
+
+  fn builder() -> OgMetadataBuilder {
+    let mut builder = OgMetadataBuilder::new();
+    builder
+      .set_title("The Rock")
+      .set_url("https://example.com/the-rock");
+    builder
+  }
+
+  #[test]
+  fn moves_root_across_types() {
+    let mut article = builder().article();
+    article.set_section("Movies").add_tag("action");
+
+    let retyped = article.retype::<Book>();
+    let root = retyped.metadata.root();
+    assert_eq!(root.object_type, ObjectType::Book);
+    assert_eq!(root.title.as_deref(), Some("The Rock"));
+    assert_eq!(
+      retyped.lost_properties,
+      vec!["article:section", "article:tag"]
+    );
+  }
+
+  #[test]
+  fn retypes_without_type_specific_properties() {
+    let retyped = Book::new().retype::<ProfileMetadata>();
+    assert!(retyped.is_lossless());
+    assert_eq!(retyped.metadata.root().object_type, ObjectType::Profile);
+  }
+
+  #[test]
+  fn fails_on_lossy_retype() {
+    let mut profile = ProfileMetadata::from_root(OgMetadata::default());
+    profile.username = Some("jdoe".into());
+
+    let err = profile.try_retype::<MusicSong>().unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Converting 'profile' to 'music.song' would lose profile:username"
+    );
+
+    let website = WebsiteMetadata::from_root(builder().get_metadata());
+    assert!(website.try_retype::<ArticleMetadata>().is_ok());
+  }
+
+  #[test]
+  fn retypes_parsed_metadata() {
+    let metadata = crate::extract::extract(
+      r#"<meta property="og:title" content="The Rock">"#,
+    )
+    .unwrap()
+    .metadata;
+
+    let song: MusicSong = metadata.retype();
+    assert_eq!(song.root().object_type, ObjectType::MusicSong);
+    assert_eq!(song.root().title.as_deref(), Some("The Rock"));
+  }
+}
@@ -1,11 +1,14 @@
 //! Metadata utility for the Open Graph `music` meta tag.
 
+use crate::flat::impl_flat_properties;
 use crate::metadata::{OgMetadata, OgMetadataBuilder};
-use crate::{object_type::ObjectType, Result};
+use crate::object_type::{retype::impl_object_metadata, ObjectType};
+use crate::Result;
 use serde::{Deserialize, Serialize};
 
 // TODO: Add missing props
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VideoMovie {
   #[serde(flatten)]
   metadata: OgMetadataBuilder,
@@ -21,6 +24,7 @@ impl VideoMovie {
 
 // TODO: Add missing props
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VideoEpisode {
   #[serde(flatten)]
   metadata: OgMetadataBuilder,
@@ -36,6 +40,7 @@ impl VideoEpisode {
 
 // TODO: Add missing props
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VideoTvShow {
   #[serde(flatten)]
   metadata: OgMetadataBuilder,
@@ -51,6 +56,7 @@ impl VideoTvShow {
 
 // TODO: Add missing props
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VideoOther {
   #[serde(flatten)]
   metadata: OgMetadataBuilder,
@@ -64,3 +70,10 @@ impl VideoOther {
     }
   }
 }
+
+impl_object_metadata!(VideoMovie, ObjectType::VideoMovie, builder: metadata);
+impl_object_metadata!(VideoEpisode, ObjectType::VideoEpisode, builder: metadata);
+impl_object_metadata!(VideoTvShow, ObjectType::VideoTvShow, builder: metadata);
+impl_object_metadata!(VideoOther, ObjectType::VideoOther, builder: metadata);
+
+impl_flat_properties!(VideoMovie, VideoEpisode, VideoTvShow, VideoOther);
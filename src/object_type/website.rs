@@ -1,12 +1,15 @@
 // TODO: Add docs
 
+use crate::flat::impl_flat_properties;
 use crate::metadata::{OgMetadata, OgMetadataBuilder};
-use crate::{convert::ToHTML, object_type::ObjectType, Result};
+use crate::object_type::{retype::impl_object_metadata, ObjectType};
+use crate::Result;
 
 use serde::{de::IntoDeserializer, Deserialize, Serialize};
 use serde_json::json;
 
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct WebsiteMetadata {
   #[serde(flatten)]
   root: OgMetadata,
@@ -26,4 +29,6 @@ impl Website for OgMetadataBuilder {
   }
 }
 
-impl ToHTML for WebsiteMetadata {}
+impl_object_metadata!(WebsiteMetadata, ObjectType::Website, root: root);
+
+impl_flat_properties!(WebsiteMetadata);
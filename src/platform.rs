@@ -0,0 +1,640 @@
+//! Platform-specific validation profiles.
+//!
+//! Every consumer of Open Graph metadata has its own rules on top of the
+//! protocol: minimum and recommended image sizes, the aspect ratio images
+//! are cropped to, how long titles and descriptions may be before they are
+//! truncated, and which tags are ignored altogether.
+//!
+//! A `PlatformProfile` captures these rules for one platform. Validating
+//! an `OgMetadata` value against a profile first runs the regular
+//! `Validatable` checks and then the platform rules, and produces a
+//! `PlatformReport` listing errors and warnings.
+//!
+//! The built-in profiles are based on each platform's published sharing
+//! guidelines. Platforms change their rules over time, so all fields of
+//! a profile are public and can be adjusted.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ogp::metadata::OgMetadata;
+//! use ogp::platform::{validate_for, Platform};
+//!
+//! let metadata = OgMetadata {
+//!   title: Some("Open Graph Protocol coming to Rust".into()),
+//!   ..Default::default()
+//! };
+//!
+//! for report in validate_for(&metadata, &Platform::ALL) {
+//!   for finding in &report.findings {
+//!     println!("{}: {}", report.platform, finding);
+//!   }
+//! }
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::{
+  error::Error,
+  metadata::{Image, OgMetadata},
+  validator::Validatable,
+};
+
+/// A consumer of Open Graph metadata with its own validation rules.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Platform {
+  Facebook,
+  LinkedIn,
+  X,
+  Slack,
+  Discord,
+  Telegram,
+}
+
+impl Platform {
+  /// All platforms with a built-in profile.
+  pub const ALL: [Platform; 6] = [
+    Platform::Facebook,
+    Platform::LinkedIn,
+    Platform::X,
+    Platform::Slack,
+    Platform::Discord,
+    Platform::Telegram,
+  ];
+
+  /// Returns the built-in validation profile of this platform.
+  pub fn profile(&self) -> PlatformProfile {
+    PlatformProfile::for_platform(*self)
+  }
+}
+
+impl fmt::Display for Platform {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let name = match self {
+      Platform::Facebook => "Facebook",
+      Platform::LinkedIn => "LinkedIn",
+      Platform::X => "X",
+      Platform::Slack => "Slack",
+      Platform::Discord => "Discord",
+      Platform::Telegram => "Telegram",
+    };
+    write!(f, "{}", name)
+  }
+}
+
+impl FromStr for Platform {
+  type Err = Error;
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    match s.to_lowercase().as_str() {
+      "facebook" => Ok(Platform::Facebook),
+      "linkedin" => Ok(Platform::LinkedIn),
+      "x" | "twitter" => Ok(Platform::X),
+      "slack" => Ok(Platform::Slack),
+      "discord" => Ok(Platform::Discord),
+      "telegram" => Ok(Platform::Telegram),
+      _ => Err(Error::Generic(format!("Unknown platform '{}'", s))),
+    }
+  }
+}
+
+/// The validation rules of a platform.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PlatformProfile {
+  /// The platform these rules belong to.
+  pub platform: Platform,
+
+  /// Images smaller than this (width, height) are not shown at all.
+  pub min_image_size: (u32, u32),
+
+  /// Images smaller than this (width, height) are shown, but look blurry
+  /// or are shown as a small thumbnail instead of a large card.
+  pub recommended_image_size: (u32, u32),
+
+  /// The width / height ratio images are cropped to.
+  pub aspect_ratio: f32,
+
+  /// How far an image's aspect ratio may deviate from `aspect_ratio`,
+  /// relative to it, before a noticeable part gets cropped.
+  pub aspect_ratio_tolerance: f32,
+
+  /// The maximum image file size in bytes. Larger images are ignored.
+  ///
+  /// File sizes are not part of the metadata, so this is reported as a
+  /// hint whenever an image is set.
+  pub max_image_bytes: u64,
+
+  /// The number of characters after which the title is truncated.
+  pub title_max_chars: usize,
+
+  /// The number of characters after which the description is truncated.
+  pub description_max_chars: usize,
+
+  /// Properties the platform ignores.
+  pub ignored_properties: Vec<&'static str>,
+
+  /// What the preview card shows as the name of the site.
+  pub site_label: SiteLabel,
+
+  /// Whether the preview card shows the description.
+  pub shows_description: bool,
+}
+
+/// What a preview card shows as the name of the site.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SiteLabel {
+  /// The `og:site_name`, falling back to the domain of `og:url`.
+  SiteName,
+
+  /// The domain of `og:url`, e.g. `example.com`.
+  Domain,
+
+  /// The uppercased domain of `og:url`, e.g. `EXAMPLE.COM`.
+  DomainUppercase,
+}
+
+impl PlatformProfile {
+  /// Returns the built-in validation profile of `platform`.
+  pub fn for_platform(platform: Platform) -> Self {
+    match platform {
+      Platform::Facebook => PlatformProfile {
+        platform,
+        min_image_size: (200, 200),
+        recommended_image_size: (1200, 630),
+        aspect_ratio: 1.91,
+        aspect_ratio_tolerance: 0.05,
+        max_image_bytes: 8 * 1024 * 1024,
+        title_max_chars: 88,
+        description_max_chars: 200,
+        ignored_properties: vec![],
+        site_label: SiteLabel::DomainUppercase,
+        shows_description: true,
+      },
+      Platform::LinkedIn => PlatformProfile {
+        platform,
+        min_image_size: (200, 200),
+        recommended_image_size: (1200, 627),
+        aspect_ratio: 1.91,
+        aspect_ratio_tolerance: 0.05,
+        max_image_bytes: 5 * 1024 * 1024,
+        title_max_chars: 70,
+        description_max_chars: 100,
+        ignored_properties: vec![
+          "og:video",
+          "og:audio",
+          "og:determiner",
+          "og:locale:alternate",
+        ],
+        site_label: SiteLabel::Domain,
+        shows_description: false,
+      },
+      Platform::X => PlatformProfile {
+        platform,
+        min_image_size: (300, 157),
+        recommended_image_size: (1200, 600),
+        aspect_ratio: 2.0,
+        aspect_ratio_tolerance: 0.05,
+        max_image_bytes: 5 * 1024 * 1024,
+        title_max_chars: 70,
+        description_max_chars: 200,
+        ignored_properties: vec![
+          "og:video",
+          "og:audio",
+          "og:determiner",
+          "og:locale",
+          "og:locale:alternate",
+        ],
+        site_label: SiteLabel::Domain,
+        shows_description: false,
+      },
+      Platform::Slack => PlatformProfile {
+        platform,
+        min_image_size: (1, 1),
+        recommended_image_size: (1200, 630),
+        aspect_ratio: 1.91,
+        aspect_ratio_tolerance: 0.25,
+        max_image_bytes: 10 * 1024 * 1024,
+        title_max_chars: 150,
+        description_max_chars: 300,
+        ignored_properties: vec![
+          "og:audio",
+          "og:determiner",
+          "og:locale",
+          "og:locale:alternate",
+        ],
+        site_label: SiteLabel::SiteName,
+        shows_description: true,
+      },
+      Platform::Discord => PlatformProfile {
+        platform,
+        min_image_size: (1, 1),
+        recommended_image_size: (1200, 630),
+        aspect_ratio: 1.91,
+        aspect_ratio_tolerance: 0.25,
+        max_image_bytes: 8 * 1024 * 1024,
+        title_max_chars: 256,
+        description_max_chars: 350,
+        ignored_properties: vec![
+          "og:audio",
+          "og:determiner",
+          "og:locale",
+          "og:locale:alternate",
+        ],
+        site_label: SiteLabel::SiteName,
+        shows_description: true,
+      },
+      Platform::Telegram => PlatformProfile {
+        platform,
+        min_image_size: (1, 1),
+        recommended_image_size: (1200, 630),
+        aspect_ratio: 1.91,
+        aspect_ratio_tolerance: 0.25,
+        max_image_bytes: 5 * 1024 * 1024,
+        title_max_chars: 70,
+        description_max_chars: 200,
+        ignored_properties: vec![
+          "og:audio",
+          "og:video",
+          "og:determiner",
+          "og:locale:alternate",
+        ],
+        site_label: SiteLabel::SiteName,
+        shows_description: true,
+      },
+    }
+  }
+
+  /// Validates `metadata` against the rules of this platform.
+  ///
+  /// Runs the `Validatable` checks of the metadata and its media first,
+  /// reporting failures as errors, followed by the platform rules.
+  pub fn validate(&self, metadata: &OgMetadata) -> PlatformReport {
+    let mut report = PlatformReport {
+      platform: self.platform,
+      findings: Vec::new(),
+    };
+
+    if let Err(err) = metadata.validate() {
+      let property = match &err {
+        Error::MissingRequiredProperty(property) => format!("og:{}", property),
+        _ => "og".into(),
+      };
+      report.error(&property, err.to_string());
+    }
+
+    for (property, result) in metadata
+      .images
+      .iter()
+      .map(|img| ("og:image", img.validate()))
+      .chain(metadata.videos.iter().map(|v| ("og:video", v.validate())))
+      .chain(metadata.audios.iter().map(|a| ("og:audio", a.validate())))
+    {
+      if let Err(err) = result {
+        report.error(property, err.to_string());
+      }
+    }
+
+    self.check_text(
+      &mut report,
+      "og:title",
+      metadata.title.as_deref(),
+      self.title_max_chars,
+    );
+    self.check_text(
+      &mut report,
+      "og:description",
+      metadata.description.as_deref(),
+      self.description_max_chars,
+    );
+
+    match metadata.images.first() {
+      None => report.warning(
+        "og:image",
+        format!(
+          "No image is set; {} shows a preview without image",
+          self.platform
+        ),
+      ),
+      Some(image) => {
+        report.hint(
+          "og:image",
+          format!(
+            "{} ignores images over {}",
+            self.platform,
+            format_bytes(self.max_image_bytes)
+          ),
+        );
+        self.check_image(&mut report, image);
+      }
+    }
+
+    for property in &self.ignored_properties {
+      if has_property(metadata, property) {
+        report.warning(
+          property,
+          format!("'{}' is ignored by {}", property, self.platform),
+        );
+      }
+    }
+
+    report
+  }
+
+  fn check_text(
+    &self,
+    report: &mut PlatformReport,
+    property: &str,
+    text: Option<&str>,
+    max_chars: usize,
+  ) {
+    let Some(text) = text else {
+      return;
+    };
+
+    let chars = text.chars().count();
+    if chars > max_chars {
+      report.warning(
+        property,
+        format!(
+          "'{}' has {} characters and is truncated to {} by {}",
+          property, chars, max_chars, self.platform
+        ),
+      );
+    }
+  }
+
+  fn check_image(&self, report: &mut PlatformReport, image: &Image) {
+    let (Some(width), Some(height)) = (image.width, image.height) else {
+      report.warning(
+        "og:image",
+        format!(
+          "Image dimensions are unknown; set 'og:image:width' and \
+          'og:image:height' so {} can render the preview right away",
+          self.platform
+        ),
+      );
+      return;
+    };
+
+    let (min_width, min_height) = self.min_image_size;
+    if width < min_width || height < min_height {
+      report.error(
+        "og:image",
+        format!(
+          "Image is {}x{} but {} requires at least {}x{}; it will not be shown",
+          width, height, self.platform, min_width, min_height
+        ),
+      );
+      return;
+    }
+
+    let (rec_width, rec_height) = self.recommended_image_size;
+    if width < rec_width || height < rec_height {
+      report.warning(
+        "og:image",
+        format!(
+          "Image is {}x{} but {} recommends at least {}x{}",
+          width, height, self.platform, rec_width, rec_height
+        ),
+      );
+    }
+
+    let ratio = width as f32 / height.max(1) as f32;
+    let deviation = (ratio - self.aspect_ratio).abs() / self.aspect_ratio;
+    if deviation > self.aspect_ratio_tolerance {
+      report.warning(
+        "og:image",
+        format!(
+          "Image aspect ratio is {:.2}:1 but {} crops to {:.2}:1",
+          ratio, self.platform, self.aspect_ratio
+        ),
+      );
+    }
+  }
+}
+
+/// Validates `metadata` against the built-in profile of each platform.
+pub fn validate_for(
+  metadata: &OgMetadata,
+  platforms: &[Platform],
+) -> Vec<PlatformReport> {
+  platforms
+    .iter()
+    .map(|platform| platform.profile().validate(metadata))
+    .collect()
+}
+
+/// The severity of a `Finding`.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+  /// The preview is broken or missing information.
+  Error,
+
+  /// The preview is shown, but does not look as intended.
+  Warning,
+
+  /// A rule that cannot be checked from the metadata alone, e.g. the
+  /// maximum image file size.
+  Hint,
+}
+
+/// A single problem found while validating against a platform profile.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Finding {
+  pub severity: Severity,
+
+  /// The property the finding refers to, e.g. `og:image`.
+  pub property: String,
+
+  /// A human readable description of the problem.
+  pub message: String,
+}
+
+impl fmt::Display for Finding {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let severity = match self.severity {
+      Severity::Error => "error",
+      Severity::Warning => "warning",
+      Severity::Hint => "hint",
+    };
+    write!(f, "{} [{}]: {}", severity, self.property, self.message)
+  }
+}
+
+/// The result of validating metadata against a platform profile.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PlatformReport {
+  pub platform: Platform,
+  pub findings: Vec<Finding>,
+}
+
+impl PlatformReport {
+  /// Returns the findings with `Severity::Error`.
+  pub fn errors(&self) -> impl Iterator<Item = &Finding> {
+    self
+      .findings
+      .iter()
+      .filter(|finding| finding.severity == Severity::Error)
+  }
+
+  /// Returns the findings with `Severity::Warning`.
+  pub fn warnings(&self) -> impl Iterator<Item = &Finding> {
+    self
+      .findings
+      .iter()
+      .filter(|finding| finding.severity == Severity::Warning)
+  }
+
+  /// Returns the findings with `Severity::Hint`.
+  pub fn hints(&self) -> impl Iterator<Item = &Finding> {
+    self
+      .findings
+      .iter()
+      .filter(|finding| finding.severity == Severity::Hint)
+  }
+
+  /// Checks whether the report contains no errors.
+  pub fn is_ok(&self) -> bool {
+    self.errors().next().is_none()
+  }
+
+  fn error(&mut self, property: &str, message: String) {
+    self.push(Severity::Error, property, message);
+  }
+
+  fn warning(&mut self, property: &str, message: String) {
+    self.push(Severity::Warning, property, message);
+  }
+
+  fn hint(&mut self, property: &str, message: String) {
+    self.push(Severity::Hint, property, message);
+  }
+
+  fn push(&mut self, severity: Severity, property: &str, message: String) {
+    self.findings.push(Finding {
+      severity,
+      property: property.into(),
+      message,
+    });
+  }
+}
+
+/// Formats a file size in whole megabytes, or kilobytes below 1 MB.
+fn format_bytes(bytes: u64) -> String {
+  match bytes {
+    bytes if bytes >= 1024 * 1024 => format!("{} MB", bytes / (1024 * 1024)),
+    bytes => format!("{} KB", bytes / 1024),
+  }
+}
+
+/// Checks whether `metadata` declares `property`.
+fn has_property(metadata: &OgMetadata, property: &str) -> bool {
+  match property {
+    "og:url" => metadata.url.is_some(),
+    "og:title" => metadata.title.is_some(),
+    "og:description" => metadata.description.is_some(),
+    "og:site_name" => metadata.site_name.is_some(),
+    "og:determiner" => metadata.determiner.is_some(),
+    "og:locale" => metadata.locale.is_some(),
+    "og:locale:alternate" => metadata
+      .locale_alternate
+      .as_ref()
+      .is_some_and(|locales| !locales.is_empty()),
+    "og:image" => !metadata.images.is_empty(),
+    "og:video" => !metadata.videos.is_empty(),
+    "og:audio" => !metadata.audios.is_empty(),
+    _ => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::metadata::Video;
+
+  fn metadata(width: u32, height: u32) -> OgMetadata {
+    let mut image = Image::from_str("https://example.com/cover.png").unwrap();
+    image.width = Some(width);
+    image.height = Some(height);
+
+    OgMetadata {
+      title: Some("The Rock".into()),
+      url: Some("https://example.com/rock".into()),
+      description: Some("A movie".into()),
+      images: vec![image],
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn parses_platform_names() {
+    assert_eq!(Platform::from_str("LinkedIn").unwrap(), Platform::LinkedIn);
+    assert_eq!(Platform::from_str("twitter").unwrap(), Platform::X);
+    assert!(Platform::from_str("myspace").is_err());
+  }
+
+  #[test]
+  fn recommended_image_passes() {
+    let report = Platform::Facebook.profile().validate(&metadata(1200, 630));
+    let messages: Vec<String> =
+      report.findings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+      messages,
+      ["hint [og:image]: Facebook ignores images over 8 MB"]
+    );
+
+    let report = Platform::LinkedIn.profile().validate(&OgMetadata {
+      images: vec![],
+      ..metadata(1200, 627)
+    });
+    assert_eq!(report.hints().count(), 0);
+  }
+
+  #[test]
+  fn reports_missing_required_properties_as_errors() {
+    let metadata = OgMetadata::default();
+    let report = Platform::LinkedIn.profile().validate(&metadata);
+    assert!(!report.is_ok());
+    assert_eq!(report.warnings().count(), 1);
+  }
+
+  #[test]
+  fn reports_image_size_and_aspect_ratio() {
+    let report = Platform::X.profile().validate(&metadata(200, 200));
+    assert_eq!(report.errors().count(), 1);
+
+    let report = Platform::X.profile().validate(&metadata(600, 600));
+    assert!(report.is_ok());
+    assert_eq!(report.warnings().count(), 2);
+  }
+
+  #[test]
+  fn reports_truncated_text_and_ignored_properties() {
+    let mut metadata = metadata(1200, 630);
+    metadata.title = Some("A".repeat(80));
+    metadata.videos.push(Video::default());
+
+    let facebook = Platform::Facebook.profile().validate(&metadata);
+    assert!(facebook.is_ok());
+    assert_eq!(facebook.warnings().count(), 0);
+
+    let linkedin = Platform::LinkedIn.profile().validate(&metadata);
+    let properties: Vec<&str> = linkedin
+      .warnings()
+      .map(|finding| finding.property.as_str())
+      .collect();
+    assert_eq!(properties, vec!["og:title", "og:video"]);
+  }
+
+  #[test]
+  fn validates_for_all_platforms() {
+    let reports = validate_for(&metadata(1200, 630), &Platform::ALL);
+    assert_eq!(reports.len(), 6);
+    assert!(reports.iter().all(PlatformReport::is_ok));
+  }
+}
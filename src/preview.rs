@@ -0,0 +1,471 @@
+//! Share-preview simulation.
+//!
+//! A `Preview` shows how a platform would render the share card for an
+//! `OgMetadata` value: the title, description and site name after the
+//! platform's truncation and fallback rules, which `og:image` is picked,
+//! and the aspect ratio it is cropped to.
+//!
+//! Previews are computed offline from the metadata alone. They can be
+//! rendered as a self-contained HTML snippet or SVG image, e.g. to show
+//! CMS editors a mock of the card before publishing.
+//!
+//! # Rules
+//!
+//! - The title falls back to `og:site_name`, then to the domain of `og:url`.
+//! - The site name is shown as configured by the profile's `SiteLabel`.
+//! - The description is omitted on platforms that do not show it.
+//! - Texts longer than the platform limit are cut and end with `…`.
+//! - The first `og:image` that satisfies the platform's minimum size is
+//!   picked. Images without known dimensions are assumed to be large enough.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ogp::metadata::OgMetadata;
+//! use ogp::platform::Platform;
+//! use ogp::preview::Preview;
+//!
+//! let metadata = OgMetadata {
+//!   title: Some("Open Graph Protocol coming to Rust".into()),
+//!   url: Some("https://github.com/ekkolon/ogp".into()),
+//!   ..Default::default()
+//! };
+//!
+//! let preview = Preview::new(&metadata, &Platform::Facebook.profile());
+//! assert_eq!(preview.site_name.as_deref(), Some("GITHUB.COM"));
+//!
+//! let svg = preview.to_svg();
+//! ```
+
+use serde::Serialize;
+use url::Url;
+
+use crate::{
+  metadata::{Image, OgMetadata},
+  platform::{Platform, PlatformProfile, SiteLabel},
+  utils::escape_html,
+};
+
+/// The width of rendered cards in pixels.
+const CARD_WIDTH: u32 = 500;
+
+/// The approximate number of characters fitting on a line of a card.
+const TITLE_LINE_CHARS: usize = 52;
+const DESCRIPTION_LINE_CHARS: usize = 64;
+
+/// The image of a share preview.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PreviewImage {
+  /// The index of the picked image within `OgMetadata::images`.
+  pub index: usize,
+
+  /// The URL the platform fetches: `og:image:secure_url` if set,
+  /// otherwise `og:image`.
+  pub url: String,
+
+  /// The width / height ratio the image is cropped to.
+  pub crop_ratio: f32,
+
+  /// The visible region of the image, if its dimensions are known.
+  pub crop: Option<Crop>,
+}
+
+/// A region of an image in pixels.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crop {
+  pub x: u32,
+  pub y: u32,
+  pub width: u32,
+  pub height: u32,
+}
+
+/// How a platform would render the share card for some metadata.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Preview {
+  /// The platform this preview simulates.
+  pub platform: Platform,
+
+  /// The title as shown, after fallbacks and truncation.
+  pub title: Option<String>,
+
+  /// Whether the title has been truncated.
+  pub title_truncated: bool,
+
+  /// The description as shown, or `None` if the platform does not show it.
+  pub description: Option<String>,
+
+  /// Whether the description has been truncated.
+  pub description_truncated: bool,
+
+  /// The site name as shown.
+  pub site_name: Option<String>,
+
+  /// The picked image, if any.
+  pub image: Option<PreviewImage>,
+}
+
+impl Preview {
+  /// Simulates the share card `profile`'s platform renders for `metadata`.
+  pub fn new(metadata: &OgMetadata, profile: &PlatformProfile) -> Self {
+    let domain = metadata
+      .url
+      .as_deref()
+      .and_then(|url| Url::parse(url).ok())
+      .and_then(|url| url.host_str().map(str::to_string))
+      .map(|host| host.trim_start_matches("www.").to_string());
+
+    let site_name = match profile.site_label {
+      SiteLabel::SiteName => metadata.site_name.clone().or(domain.clone()),
+      SiteLabel::Domain => domain.clone(),
+      SiteLabel::DomainUppercase => domain.as_ref().map(|d| d.to_uppercase()),
+    };
+
+    let title = metadata
+      .title
+      .clone()
+      .or_else(|| metadata.site_name.clone())
+      .or(domain)
+      .map(|title| truncate(&title, profile.title_max_chars));
+
+    let description = metadata
+      .description
+      .as_deref()
+      .filter(|_| profile.shows_description)
+      .map(|description| truncate(description, profile.description_max_chars));
+
+    let image = metadata
+      .images
+      .iter()
+      .enumerate()
+      .find(|(_, image)| image.url.is_some() && fits(image, profile))
+      .map(|(index, image)| PreviewImage {
+        index,
+        url: image
+          .secure_url
+          .as_ref()
+          .or(image.url.as_ref())
+          .map(|url| url.to_string())
+          .unwrap_or_default(),
+        crop_ratio: profile.aspect_ratio,
+        crop: crop(image, profile.aspect_ratio),
+      });
+
+    Preview {
+      platform: profile.platform,
+      title_truncated: title.as_ref().is_some_and(|(_, cut)| *cut),
+      title: title.map(|(title, _)| title),
+      description_truncated: description.as_ref().is_some_and(|(_, cut)| *cut),
+      description: description.map(|(description, _)| description),
+      site_name,
+      image,
+    }
+  }
+
+  /// Renders the card as a self-contained HTML snippet with inline styles.
+  pub fn to_html(&self) -> String {
+    let mut html = format!(
+      r#"<div class="ogp-preview" data-platform="{}" style="width:{}px;border:1px solid #dadde1;border-radius:8px;overflow:hidden;font-family:Helvetica,Arial,sans-serif;background:#f2f3f5">"#,
+      escape_html(&self.platform.to_string()),
+      CARD_WIDTH
+    );
+
+    if let Some(image) = &self.image {
+      // An `<img>` rather than a CSS background, as escaping does not
+      // protect URLs inside `url()`.
+      html.push_str(&format!(
+        r#"<img src="{}" alt="" style="display:block;width:100%;aspect-ratio:{:.2};object-fit:cover;background:#d0d3d8">"#,
+        escape_html(&image.url),
+        image.crop_ratio
+      ));
+    }
+
+    html.push_str(r#"<div style="padding:10px 12px">"#);
+    if let Some(site_name) = &self.site_name {
+      html.push_str(&format!(
+        r#"<div style="font-size:12px;color:#606770">{}</div>"#,
+        escape_html(site_name)
+      ));
+    }
+    if let Some(title) = &self.title {
+      html.push_str(&format!(
+        r#"<div style="font-size:16px;font-weight:600;color:#1d2129">{}</div>"#,
+        escape_html(title)
+      ));
+    }
+    if let Some(description) = &self.description {
+      html.push_str(&format!(
+        r#"<div style="font-size:14px;color:#606770">{}</div>"#,
+        escape_html(description)
+      ));
+    }
+    html.push_str("</div></div>");
+
+    html
+  }
+
+  /// Renders the card as a self-contained SVG image.
+  ///
+  /// The picked image is referenced by URL and drawn over a placeholder,
+  /// so the mock still shows the crop area when viewed offline.
+  pub fn to_svg(&self) -> String {
+    let image_height = self
+      .image
+      .as_ref()
+      .map(|image| (CARD_WIDTH as f32 / image.crop_ratio).round() as u32)
+      .unwrap_or(0);
+
+    let mut lines: Vec<(u32, &str, &str, String)> = Vec::new();
+    if let Some(site_name) = &self.site_name {
+      lines.push((12, "#606770", "normal", site_name.clone()));
+    }
+    for line in
+      wrap(self.title.as_deref().unwrap_or_default(), TITLE_LINE_CHARS)
+    {
+      lines.push((16, "#1d2129", "bold", line));
+    }
+    for line in wrap(
+      self.description.as_deref().unwrap_or_default(),
+      DESCRIPTION_LINE_CHARS,
+    ) {
+      lines.push((14, "#606770", "normal", line));
+    }
+
+    let text_height: u32 =
+      lines.iter().map(|(size, ..)| size + 6).sum::<u32>() + 20;
+    let height = image_height + text_height;
+
+    let mut svg = format!(
+      r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="Helvetica,Arial,sans-serif"><rect width="{w}" height="{h}" rx="8" fill="#f2f3f5" stroke="#dadde1"/>"##,
+      w = CARD_WIDTH,
+      h = height
+    );
+
+    if let Some(image) = &self.image {
+      svg.push_str(&format!(
+        r##"<rect width="{w}" height="{h}" fill="#d0d3d8"/><text x="{cx}" y="{cy}" font-size="12" fill="#606770" text-anchor="middle">{ratio:.2}:1</text><image href="{url}" width="{w}" height="{h}" preserveAspectRatio="xMidYMid slice"/>"##,
+        w = CARD_WIDTH,
+        h = image_height,
+        cx = CARD_WIDTH / 2,
+        cy = image_height / 2,
+        ratio = image.crop_ratio,
+        url = escape_html(&image.url)
+      ));
+    }
+
+    let mut y = image_height + 10;
+    for (size, color, weight, line) in lines {
+      y += size + 6;
+      svg.push_str(&format!(
+        r#"<text x="12" y="{}" font-size="{}" fill="{}" font-weight="{}">{}</text>"#,
+        y - 6,
+        size,
+        color,
+        weight,
+        escape_html(&line)
+      ));
+    }
+
+    svg.push_str("</svg>");
+    svg
+  }
+}
+
+/// Simulates the share card of `metadata` for each platform.
+pub fn preview_for(
+  metadata: &OgMetadata,
+  platforms: &[Platform],
+) -> Vec<Preview> {
+  platforms
+    .iter()
+    .map(|platform| Preview::new(metadata, &platform.profile()))
+    .collect()
+}
+
+/// Cuts `text` to at most `max_chars` characters, ending with `…`.
+///
+/// Returns the shown text and whether it has been truncated.
+fn truncate(text: &str, max_chars: usize) -> (String, bool) {
+  let text = text.trim();
+  if text.chars().count() <= max_chars {
+    return (text.to_string(), false);
+  }
+
+  let cut: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+  (format!("{}…", cut.trim_end()), true)
+}
+
+/// Checks whether the image satisfies the profile's minimum size.
+fn fits(image: &Image, profile: &PlatformProfile) -> bool {
+  let (min_width, min_height) = profile.min_image_size;
+  match (image.width, image.height) {
+    (Some(width), Some(height)) => width >= min_width && height >= min_height,
+    _ => true,
+  }
+}
+
+/// Computes the centered region of `image` that is visible at `ratio`.
+fn crop(image: &Image, ratio: f32) -> Option<Crop> {
+  let (width, height) = (image.width?, image.height?);
+  if width == 0 || height == 0 {
+    return None;
+  }
+
+  let image_ratio = width as f32 / height as f32;
+  let (crop_width, crop_height) = if image_ratio > ratio {
+    ((height as f32 * ratio).round() as u32, height)
+  } else {
+    (width, (width as f32 / ratio).round() as u32)
+  };
+
+  Some(Crop {
+    x: (width - crop_width.min(width)) / 2,
+    y: (height - crop_height.min(height)) / 2,
+    width: crop_width.min(width),
+    height: crop_height.min(height),
+  })
+}
+
+/// Wraps `text` into lines of at most `line_chars` characters, breaking
+/// at whitespace. At most two lines are returned.
+fn wrap(text: &str, line_chars: usize) -> Vec<String> {
+  let mut lines: Vec<String> = Vec::new();
+  let mut line = String::new();
+
+  for word in text.split_whitespace() {
+    let len = line.chars().count() + word.chars().count() + 1;
+    if !line.is_empty() && len > line_chars {
+      lines.push(std::mem::take(&mut line));
+    }
+    if !line.is_empty() {
+      line.push(' ');
+    }
+    line.push_str(word);
+  }
+
+  if !line.is_empty() {
+    lines.push(line);
+  }
+
+  if lines.len() > 2 {
+    lines.truncate(2);
+    let (last, _) = truncate(&format!("{}…", lines[1]), line_chars);
+    lines[1] = last;
+  }
+
+  lines
+}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr;
+
+  use super::*;
+
+  fn metadata() -> OgMetadata {
+    let mut small = Image::from_str("https://example.com/small.png").unwrap();
+    small.width = Some(100);
+    small.height = Some(100);
+
+    let mut square = Image::from_str("http://example.com/square.png").unwrap();
+    square.secure_url = Url::parse("https://example.com/square.png").ok();
+    square.width = Some(1000);
+    square.height = Some(1000);
+
+    OgMetadata {
+      title: Some("The Rock".into()),
+      url: Some("https://www.example.com/rock".into()),
+      site_name: Some("Example".into()),
+      description: Some("A movie about a rock.".into()),
+      images: vec![small, square],
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn applies_site_label_and_description_rules() {
+    let metadata = metadata();
+
+    let facebook = Preview::new(&metadata, &Platform::Facebook.profile());
+    assert_eq!(facebook.site_name.as_deref(), Some("EXAMPLE.COM"));
+    assert!(facebook.description.is_some());
+
+    let linkedin = Preview::new(&metadata, &Platform::LinkedIn.profile());
+    assert_eq!(linkedin.site_name.as_deref(), Some("example.com"));
+    assert!(linkedin.description.is_none());
+
+    let slack = Preview::new(&metadata, &Platform::Slack.profile());
+    assert_eq!(slack.site_name.as_deref(), Some("Example"));
+  }
+
+  #[test]
+  fn falls_back_and_truncates_title() {
+    let mut metadata = metadata();
+    metadata.title = None;
+    let preview = Preview::new(&metadata, &Platform::X.profile());
+    assert_eq!(preview.title.as_deref(), Some("Example"));
+
+    metadata.title = Some("A".repeat(100));
+    let preview = Preview::new(&metadata, &Platform::X.profile());
+    let title = preview.title.unwrap();
+    assert!(preview.title_truncated);
+    assert_eq!(title.chars().count(), 70);
+    assert!(title.ends_with('…'));
+  }
+
+  #[test]
+  fn picks_first_image_large_enough() {
+    let facebook = Preview::new(&metadata(), &Platform::Facebook.profile());
+    let image = facebook.image.unwrap();
+    assert_eq!(image.index, 1);
+    assert_eq!(image.url, "https://example.com/square.png");
+    assert_eq!(
+      image.crop,
+      Some(Crop {
+        x: 0,
+        y: 238,
+        width: 1000,
+        height: 524
+      })
+    );
+
+    let slack = Preview::new(&metadata(), &Platform::Slack.profile());
+    assert_eq!(slack.image.unwrap().index, 0);
+  }
+
+  #[test]
+  fn renders_escaped_html_and_svg() {
+    let mut metadata = metadata();
+    metadata.title = Some("Tom & Jerry <3".into());
+    let preview = Preview::new(&metadata, &Platform::Discord.profile());
+
+    let html = preview.to_html();
+    assert!(html.contains("Tom &amp; Jerry &lt;3"));
+    assert!(html.contains("https://example.com/small.png"));
+
+    let svg = preview.to_svg();
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.ends_with("</svg>"));
+    assert!(svg.contains("Tom &amp; Jerry &lt;3"));
+  }
+
+  #[test]
+  fn renders_hostile_image_urls_as_attributes() {
+    let mut preview = Preview::new(&metadata(), &Platform::Slack.profile());
+    if let Some(image) = &mut preview.image {
+      image.url =
+        r#"https://example.com/a.png');background:url('x') "><script>"#.into();
+    }
+
+    let html = preview.to_html();
+    assert!(!html.contains("<script>"));
+    assert!(html.contains(
+      r#"<img src="https://example.com/a.png&#39;);background:url(&#39;x&#39;) &quot;&gt;&lt;script&gt;" alt="""#
+    ));
+  }
+
+  #[test]
+  fn previews_all_platforms() {
+    let previews = preview_for(&metadata(), &Platform::ALL);
+    assert_eq!(previews.len(), 6);
+  }
+}
@@ -0,0 +1,504 @@
+//! Local image probing for filling in `Image` dimensions and MIME types.
+//!
+//! Static site generators know their images are on disk. Probing reads
+//! just enough of an image file to determine its format and dimensions,
+//! so the `og:image:width`, `og:image:height` and `og:image:type` tags can
+//! be populated without manual data entry. Pixel data is never decoded.
+//!
+//! Supported formats are PNG, JPEG, GIF, WebP and AVIF.
+//!
+//! This module is only available with the `probe` feature enabled.
+//!
+//! # Usage
+//!
+//! ```rust,no_run
+//! use std::str::FromStr;
+//!
+//! use ogp::metadata::Image;
+//! use ogp::validator::DimensionsValidator;
+//!
+//! let mut image = Image::from_str("https://example.com/cover.png").unwrap();
+//! image.probe_path("public/cover.png").unwrap();
+//!
+//! assert!(image.validate_dimensions().is_ok());
+//! ```
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::{
+  error::Error,
+  metadata::{Image, MediaType},
+  Result,
+};
+
+/// The number of bytes read from a file before probing is first attempted.
+const INITIAL_READ_SIZE: usize = 64 * 1024;
+
+/// The format and dimensions of an image, as read from its headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageInfo {
+  /// The number of pixels wide.
+  pub width: u32,
+
+  /// The number of pixels high.
+  pub height: u32,
+
+  /// The MIME type of the image format, e.g. `image/png`.
+  pub media_type: MediaType,
+}
+
+/// Probes the image headers contained in `bytes`.
+pub fn probe_bytes(bytes: &[u8]) -> Result<ImageInfo> {
+  match probe_header(bytes)? {
+    Some(info) => Ok(info),
+    None => Err(Error::ImageProbe("Truncated image header".into())),
+  }
+}
+
+/// Probes the headers of the image file at `path`.
+///
+/// The file is read incrementally until the headers are complete,
+/// which for most images means only the first few bytes.
+pub fn probe_path(path: impl AsRef<Path>) -> Result<ImageInfo> {
+  let mut file = File::open(path)?;
+  let mut buffer = Vec::new();
+  let mut read_size = INITIAL_READ_SIZE;
+
+  loop {
+    let read = (&mut file)
+      .take(read_size as u64)
+      .read_to_end(&mut buffer)?;
+
+    if let Some(info) = probe_header(&buffer)? {
+      return Ok(info);
+    }
+
+    if read < read_size {
+      return Err(Error::ImageProbe("Truncated image header".into()));
+    }
+
+    read_size = buffer.len();
+  }
+}
+
+impl Image {
+  /// Sets width, height and MIME type from the headers of the image file
+  /// at `path`.
+  pub fn probe_path(&mut self, path: impl AsRef<Path>) -> Result<&mut Self> {
+    let info = probe_path(path)?;
+    Ok(self.apply_info(info))
+  }
+
+  /// Sets width, height and MIME type from the image headers in `bytes`.
+  pub fn probe_bytes(&mut self, bytes: &[u8]) -> Result<&mut Self> {
+    let info = probe_bytes(bytes)?;
+    Ok(self.apply_info(info))
+  }
+
+  fn apply_info(&mut self, info: ImageInfo) -> &mut Self {
+    self.width = Some(info.width);
+    self.height = Some(info.height);
+    self.mimetype = Some(info.media_type);
+    self
+  }
+}
+
+/// Probes an image header.
+///
+/// Returns `None` if more bytes are needed to complete the header.
+fn probe_header(bytes: &[u8]) -> Result<Option<ImageInfo>> {
+  if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+    return Ok(probe_png(bytes));
+  }
+
+  if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+    return Ok(probe_gif(bytes));
+  }
+
+  if bytes.starts_with(&[0xff, 0xd8]) {
+    return probe_jpeg(bytes);
+  }
+
+  if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+    return probe_webp(bytes);
+  }
+
+  if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" {
+    return probe_avif(bytes);
+  }
+
+  if bytes.len() < 12 {
+    return Ok(None);
+  }
+
+  Err(Error::ImageProbe("Unsupported image format".into()))
+}
+
+fn info(width: u32, height: u32, media_type: &str) -> ImageInfo {
+  ImageInfo {
+    width,
+    height,
+    media_type: MediaType::new_unchecked(media_type),
+  }
+}
+
+fn u16_be(bytes: &[u8], pos: usize) -> Option<u16> {
+  Some(u16::from_be_bytes(
+    bytes.get(pos..pos + 2)?.try_into().ok()?,
+  ))
+}
+
+fn u16_le(bytes: &[u8], pos: usize) -> Option<u16> {
+  Some(u16::from_le_bytes(
+    bytes.get(pos..pos + 2)?.try_into().ok()?,
+  ))
+}
+
+fn u24_le(bytes: &[u8], pos: usize) -> Option<u32> {
+  let b = bytes.get(pos..pos + 3)?;
+  Some(u32::from(b[0]) | u32::from(b[1]) << 8 | u32::from(b[2]) << 16)
+}
+
+fn u32_be(bytes: &[u8], pos: usize) -> Option<u32> {
+  Some(u32::from_be_bytes(
+    bytes.get(pos..pos + 4)?.try_into().ok()?,
+  ))
+}
+
+fn u32_le(bytes: &[u8], pos: usize) -> Option<u32> {
+  Some(u32::from_le_bytes(
+    bytes.get(pos..pos + 4)?.try_into().ok()?,
+  ))
+}
+
+/// The `IHDR` chunk always comes first and holds the dimensions.
+fn probe_png(bytes: &[u8]) -> Option<ImageInfo> {
+  let width = u32_be(bytes, 16)?;
+  let height = u32_be(bytes, 20)?;
+  Some(info(width, height, "image/png"))
+}
+
+/// The logical screen descriptor follows the signature.
+fn probe_gif(bytes: &[u8]) -> Option<ImageInfo> {
+  let width = u16_le(bytes, 6)?;
+  let height = u16_le(bytes, 8)?;
+  Some(info(width.into(), height.into(), "image/gif"))
+}
+
+/// Walks the segments up to the first start-of-frame marker.
+fn probe_jpeg(bytes: &[u8]) -> Result<Option<ImageInfo>> {
+  let mut pos = 2;
+
+  loop {
+    let Some(&byte) = bytes.get(pos) else {
+      return Ok(None);
+    };
+
+    if byte != 0xff {
+      return Err(Error::ImageProbe("Malformed JPEG segment".into()));
+    }
+
+    // Markers may be preceded by any number of fill bytes.
+    while bytes.get(pos + 1) == Some(&0xff) {
+      pos += 1;
+    }
+
+    let Some(&marker) = bytes.get(pos + 1) else {
+      return Ok(None);
+    };
+
+    match marker {
+      // Standalone markers without a length.
+      0x01 | 0xd0..=0xd7 => {
+        pos += 2;
+        continue;
+      }
+      0xd9 | 0xda => {
+        return Err(Error::ImageProbe("JPEG has no frame header".into()));
+      }
+      // Start of frame, except DHT (c4), JPG (c8) and DAC (cc).
+      0xc0..=0xcf if !matches!(marker, 0xc4 | 0xc8 | 0xcc) => {
+        let (Some(height), Some(width)) =
+          (u16_be(bytes, pos + 5), u16_be(bytes, pos + 7))
+        else {
+          return Ok(None);
+        };
+        return Ok(Some(info(width.into(), height.into(), "image/jpeg")));
+      }
+      _ => {
+        let Some(length) = u16_be(bytes, pos + 2) else {
+          return Ok(None);
+        };
+        pos += 2 + usize::from(length);
+      }
+    }
+  }
+}
+
+/// Reads the dimensions from the first chunk, which is one of `VP8 `
+/// (lossy), `VP8L` (lossless) or `VP8X` (extended).
+fn probe_webp(bytes: &[u8]) -> Result<Option<ImageInfo>> {
+  let Some(chunk) = bytes.get(12..16) else {
+    return Ok(None);
+  };
+
+  let dimensions = match chunk {
+    b"VP8 " => u16_le(bytes, 26)
+      .zip(u16_le(bytes, 28))
+      .map(|(w, h)| (u32::from(w & 0x3fff), u32::from(h & 0x3fff))),
+    b"VP8L" => u32_le(bytes, 21)
+      .map(|bits| ((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1)),
+    b"VP8X" => u24_le(bytes, 24)
+      .zip(u24_le(bytes, 27))
+      .map(|(w, h)| (w + 1, h + 1)),
+    _ => return Err(Error::ImageProbe("Unsupported WebP chunk".into())),
+  };
+
+  Ok(dimensions.map(|(width, height)| info(width, height, "image/webp")))
+}
+
+/// Finds the image spatial extents (`ispe`) properties in
+/// `meta` > `iprp` > `ipco`.
+///
+/// Grid images carry an `ispe` for every tile next to the one of the
+/// full image, so the largest one is taken.
+fn probe_avif(bytes: &[u8]) -> Result<Option<ImageInfo>> {
+  let Some(ftyp) = boxes(bytes).next() else {
+    return Ok(None);
+  };
+  let ftyp = ftyp?;
+  let brands = ftyp
+    .body
+    .get(..4)
+    .into_iter()
+    .chain(ftyp.body.get(8..).unwrap_or_default().chunks(4));
+  if !brands.into_iter().any(|b| b == b"avif" || b == b"avis") {
+    return Err(Error::ImageProbe("Unsupported image format".into()));
+  }
+
+  let mut meta = None;
+  for isobmff_box in boxes(bytes) {
+    match isobmff_box {
+      Ok(b) if &b.kind == b"meta" => {
+        meta = Some(b);
+        break;
+      }
+      Ok(_) => continue,
+      Err(_) => return Ok(None),
+    }
+  }
+  let Some(meta) = meta else {
+    return Ok(None);
+  };
+
+  // `meta` is a full box, starting with version and flags.
+  let ipco = meta
+    .body
+    .get(4..)
+    .and_then(|body| find_box(body, b"iprp"))
+    .and_then(|iprp| find_box(iprp, b"ipco"));
+  let Some(ipco) = ipco else {
+    return Err(Error::ImageProbe("AVIF has no image properties".into()));
+  };
+
+  let largest = boxes(ipco)
+    .filter_map(|b| b.ok())
+    .filter(|b| &b.kind == b"ispe")
+    .filter_map(|ispe| Some((u32_be(ispe.body, 4)?, u32_be(ispe.body, 8)?)))
+    .max_by_key(|(width, height)| u64::from(*width) * u64::from(*height));
+
+  match largest {
+    Some((width, height)) => Ok(Some(info(width, height, "image/avif"))),
+    None => Err(Error::ImageProbe("AVIF has no image extents".into())),
+  }
+}
+
+/// A box of the ISO base media file format.
+struct IsobmffBox<'a> {
+  kind: [u8; 4],
+  body: &'a [u8],
+}
+
+/// Returns the body of the first box of the given kind in `bytes`.
+fn find_box<'a>(bytes: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+  boxes(bytes)
+    .filter_map(|b| b.ok())
+    .find(|b| &b.kind == kind)
+    .map(|b| b.body)
+}
+
+/// Iterates over the boxes in `bytes`. Yields an error for a box that
+/// exceeds `bytes`, i.e. when more data is needed.
+fn boxes(bytes: &[u8]) -> impl Iterator<Item = Result<IsobmffBox<'_>>> {
+  let mut pos = 0;
+  std::iter::from_fn(move || {
+    if pos >= bytes.len() {
+      return None;
+    }
+
+    let truncated = || Error::ImageProbe("Truncated AVIF box".into());
+    let (Some(size), Some(kind)) =
+      (u32_be(bytes, pos), bytes.get(pos + 4..pos + 8))
+    else {
+      pos = bytes.len();
+      return Some(Err(truncated()));
+    };
+
+    let (header, size) = match size {
+      0 => (8, bytes.len() - pos),
+      1 => {
+        let large = bytes
+          .get(pos + 8..pos + 16)
+          .map(|large| u64::from_be_bytes(large.try_into().unwrap()))
+          .and_then(|large| usize::try_from(large).ok());
+        match large {
+          Some(large) => (16, large),
+          None => {
+            pos = bytes.len();
+            return Some(Err(truncated()));
+          }
+        }
+      }
+      size => (8, size as usize),
+    };
+
+    let end = pos.checked_add(size);
+    let body = match end.and_then(|end| bytes.get(pos + header..end)) {
+      Some(body) if size >= header => body,
+      _ => {
+        pos = bytes.len();
+        return Some(Err(truncated()));
+      }
+    };
+
+    pos += size;
+    Some(Ok(IsobmffBox {
+      kind: kind.try_into().unwrap(),
+      body,
+    }))
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr;
+
+  use super::*;
+  use crate::validator::{DimensionsValidator, Validatable};
+
+  fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+    bytes.extend(width.to_be_bytes());
+    bytes.extend(height.to_be_bytes());
+    bytes.extend([8, 6, 0, 0, 0]);
+    bytes
+  }
+
+  fn isobmff(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+    let mut bytes = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+    bytes.extend(kind);
+    bytes.extend(body);
+    bytes
+  }
+
+  #[test]
+  fn probes_png() {
+    let info = probe_bytes(&png(1200, 630)).unwrap();
+    assert_eq!((info.width, info.height), (1200, 630));
+    assert_eq!(info.media_type.as_str(), "image/png");
+  }
+
+  #[test]
+  fn probes_gif() {
+    let info = probe_bytes(b"GIF89a\x40\x01\xf0\x00\x00\x00").unwrap();
+    assert_eq!((info.width, info.height), (320, 240));
+    assert_eq!(info.media_type.as_str(), "image/gif");
+  }
+
+  #[test]
+  fn probes_jpeg() {
+    let mut bytes = vec![0xff, 0xd8, 0xff, 0xe0, 0x00, 0x10];
+    bytes.extend(b"JFIF\0\x01\x01\0\0\x01\0\x01\0\0");
+    bytes.extend([0xff, 0xff, 0xc2, 0x00, 0x11, 0x08]);
+    bytes.extend(480u16.to_be_bytes());
+    bytes.extend(640u16.to_be_bytes());
+    bytes.extend([0x03, 0x01, 0x22, 0x00]);
+
+    let info = probe_bytes(&bytes).unwrap();
+    assert_eq!((info.width, info.height), (640, 480));
+    assert_eq!(info.media_type.as_str(), "image/jpeg");
+  }
+
+  #[test]
+  fn probes_webp() {
+    let mut lossy = b"RIFF\0\0\0\0WEBPVP8 \0\0\0\0\0\0\0\x9d\x01\x2a".to_vec();
+    lossy.extend(800u16.to_le_bytes());
+    lossy.extend(600u16.to_le_bytes());
+    let info = probe_bytes(&lossy).unwrap();
+    assert_eq!((info.width, info.height), (800, 600));
+
+    let mut lossless = b"RIFF\0\0\0\0WEBPVP8L\0\0\0\0\x2f".to_vec();
+    lossless.extend((799u32 | (599 << 14)).to_le_bytes());
+    let info = probe_bytes(&lossless).unwrap();
+    assert_eq!((info.width, info.height), (800, 600));
+
+    let mut extended = b"RIFF\0\0\0\0WEBPVP8X\0\0\0\0\0\0\0\0".to_vec();
+    extended.extend(&1199u32.to_le_bytes()[..3]);
+    extended.extend(&629u32.to_le_bytes()[..3]);
+    let info = probe_bytes(&extended).unwrap();
+    assert_eq!((info.width, info.height), (1200, 630));
+    assert_eq!(info.media_type.as_str(), "image/webp");
+  }
+
+  #[test]
+  fn probes_avif() {
+    let ispe = |w: u32, h: u32| {
+      let mut body = vec![0, 0, 0, 0];
+      body.extend(w.to_be_bytes());
+      body.extend(h.to_be_bytes());
+      isobmff(b"ispe", &body)
+    };
+    let ipco = isobmff(b"ipco", &[ispe(512, 512), ispe(2048, 1024)].concat());
+    let iprp = isobmff(b"iprp", &ipco);
+    let meta = isobmff(b"meta", &[vec![0, 0, 0, 0], iprp].concat());
+    let ftyp = isobmff(b"ftyp", b"avif\0\0\0\0mif1miaf");
+
+    let info = probe_bytes(&[ftyp, meta].concat()).unwrap();
+    assert_eq!((info.width, info.height), (2048, 1024));
+    assert_eq!(info.media_type.as_str(), "image/avif");
+  }
+
+  #[test]
+  fn rejects_unsupported_and_truncated_images() {
+    assert!(probe_bytes(b"BM\0\0\0\0\0\0\0\0\0\0\0\0").is_err());
+    assert!(probe_bytes(&png(1, 1)[..20]).is_err());
+
+    // A box claiming a 64-bit size beyond the end of the input.
+    let ftyp = isobmff(b"ftyp", b"avif\0\0\0\0mif1miaf");
+    let mut huge = 1u32.to_be_bytes().to_vec();
+    huge.extend(b"meta");
+    huge.extend(u64::MAX.to_be_bytes());
+    assert!(probe_bytes(&[ftyp, huge].concat()).is_err());
+  }
+
+  #[test]
+  fn populates_image_from_file() {
+    let path = std::env::temp_dir().join("ogp-probe-test.png");
+    std::fs::write(&path, png(1200, 630)).unwrap();
+
+    let mut image = Image::from_str("https://example.com/cover.png").unwrap();
+    image.probe_path(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(image.width, Some(1200));
+    assert_eq!(image.height, Some(630));
+    assert_eq!(
+      image.mimetype.as_ref().map(MediaType::as_str),
+      Some("image/png")
+    );
+    assert!(image.validate_dimensions().is_ok());
+    assert!(image.validate().is_ok());
+  }
+}
@@ -0,0 +1,236 @@
+//! JSON Schemas of the metadata types.
+//!
+//! With the `schemars` feature enabled, all metadata types implement
+//! `schemars::JsonSchema`. The schemas describe the JSON produced by the
+//! serde derives and encode the constraints enforced by the validators, so
+//! that forms validated against them in the browser pass validation in Rust:
+//!
+//! - `og:title`, `og:url` and `og:description` are required
+//! - URLs must use http or https, secure URLs https
+//! - image URLs with a file extension must use an allowed image extension
+//! - locales must be in the format `language_TERRITORY`
+//! - media types must be allowed for the kind of media
+//! - image and video dimensions must be declared together
+//! - object types, determiners and genders are enums
+//!
+//! This module is only available with the `schemars` feature enabled.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ogp::metadata::OgMetadata;
+//!
+//! let schema = schemars::schema_for!(OgMetadata);
+//! assert_eq!(schema.get("title").unwrap(), "OgMetadata");
+//!
+//! for (name, schema) in ogp::schema::schemas() {
+//!   let json = serde_json::to_string_pretty(&schema).unwrap();
+//!   // e.g. write `json` to `schemas/{name}.json`
+//! }
+//! ```
+
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+use crate::{
+  metadata::{
+    Audio, Image, OgMetadata, Video, AUDIO_MEDIA_TYPES, IMAGE_MEDIA_TYPES,
+    VIDEO_MEDIA_TYPES,
+  },
+  object_type::{
+    article::ArticleMetadata,
+    book::Book,
+    music::{MusicAlbum, MusicPlaylist, MusicRadioStation, MusicSong},
+    profile::ProfileMetadata,
+    video::{VideoEpisode, VideoMovie, VideoOther, VideoTvShow},
+    website::WebsiteMetadata,
+  },
+};
+
+/// An http or https URL, as accepted by `validate_http_url`.
+pub const HTTP_URL_PATTERN: &str =
+  r"^[hH][tT][tT][pP][sS]?://[^\s/?#]+([/?#]\S*)?$";
+
+/// An https URL, as required for secure URLs.
+pub const HTTPS_URL_PATTERN: &str =
+  r"^[hH][tT][tT][pP][sS]://[^\s/?#]+([/?#]\S*)?$";
+
+/// An http or https URL whose last path segment has no file extension or
+/// one of the allowed image extensions (see `ALLOWED_MEDIA_FILE_EXT`).
+pub const IMAGE_URL_PATTERN: &str = concat!(
+  r"^[hH][tT][tT][pP][sS]?://[^\s/?#]+",
+  r"(/([^?#]*/)?([^/?#.]*|[^/?#]*\.",
+  r"([pP][nN][gG]|[jJ][pP][eE]?[gG]|[gG][iI][fF]|[wW][eE][bB][pP]|[aA][vV][iI][fF])?))?",
+  r"([?#]\S*)?$"
+);
+
+/// A locale in the format `language_TERRITORY`, e.g. `en_US`.
+pub const LOCALE_PATTERN: &str = r"^[a-zA-Z]{2}_[a-zA-Z]{2}$";
+
+/// The JSON Schemas of all metadata types, by type name.
+pub fn schemas() -> Vec<(&'static str, Schema)> {
+  fn schema<T: JsonSchema>() -> Schema {
+    SchemaGenerator::default().into_root_schema_for::<T>()
+  }
+
+  vec![
+    ("OgMetadata", schema::<OgMetadata>()),
+    ("Image", schema::<Image>()),
+    ("Video", schema::<Video>()),
+    ("Audio", schema::<Audio>()),
+    ("WebsiteMetadata", schema::<WebsiteMetadata>()),
+    ("ArticleMetadata", schema::<ArticleMetadata>()),
+    ("ProfileMetadata", schema::<ProfileMetadata>()),
+    ("Book", schema::<Book>()),
+    ("MusicSong", schema::<MusicSong>()),
+    ("MusicAlbum", schema::<MusicAlbum>()),
+    ("MusicPlaylist", schema::<MusicPlaylist>()),
+    ("MusicRadioStation", schema::<MusicRadioStation>()),
+    ("VideoMovie", schema::<VideoMovie>()),
+    ("VideoEpisode", schema::<VideoEpisode>()),
+    ("VideoTvShow", schema::<VideoTvShow>()),
+    ("VideoOther", schema::<VideoOther>()),
+  ]
+}
+
+fn media_type(allowed: &[&str]) -> Schema {
+  json_schema!({
+    "type": ["string", "null"],
+    "enum": allowed.iter().map(|t| Some(*t)).chain([None]).collect::<Vec<_>>(),
+  })
+}
+
+pub(crate) fn image_media_type(_: &mut SchemaGenerator) -> Schema {
+  media_type(&IMAGE_MEDIA_TYPES)
+}
+
+pub(crate) fn video_media_type(_: &mut SchemaGenerator) -> Schema {
+  media_type(&VIDEO_MEDIA_TYPES)
+}
+
+pub(crate) fn audio_media_type(_: &mut SchemaGenerator) -> Schema {
+  media_type(&AUDIO_MEDIA_TYPES)
+}
+
+/// Requires `width` and `height` to be declared together, like
+/// `DimensionsValidator`.
+pub(crate) fn both_dimensions(schema: &mut Schema) {
+  let declared = |dimension: &str| {
+    json_schema!({
+      "properties": { (dimension): { "type": "integer" } },
+      "required": [dimension],
+    })
+  };
+
+  schema.insert(
+    "allOf".into(),
+    serde_json::json!([
+      { "if": declared("width"), "then": declared("height") },
+      { "if": declared("height"), "then": declared("width") },
+    ]),
+  );
+}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr;
+
+  use regex::Regex;
+  use serde_json::Value;
+
+  use super::*;
+  use crate::validator::Validatable;
+
+  fn matches(pattern: &str, value: &str) -> bool {
+    Regex::new(pattern).unwrap().is_match(value)
+  }
+
+  #[test]
+  fn patterns_agree_with_validators() {
+    let urls = [
+      "https://example.com/cover.png",
+      "HTTPS://example.com/cover.JPG?size=2",
+      "http://example.com/images/cover",
+      "http://example.com",
+      "https://example.com/dir/",
+      "https://example.com/cover.bmp",
+      "https://example.com/a.png/cover.tiff#x",
+    ];
+    for url in urls {
+      let accepted =
+        Image::from_str(url).is_ok_and(|img| img.validate().is_ok());
+      assert_eq!(matches(IMAGE_URL_PATTERN, url), accepted, "{}", url);
+    }
+
+    for url in ["ftp://example.com/a", "example.com", "https://"] {
+      assert!(!matches(HTTP_URL_PATTERN, url), "{}", url);
+    }
+    assert!(matches(HTTPS_URL_PATTERN, "https://example.com/a"));
+    assert!(!matches(HTTPS_URL_PATTERN, "http://example.com/a"));
+
+    assert!(matches(LOCALE_PATTERN, "en_US"));
+    assert!(!matches(LOCALE_PATTERN, "en-US"));
+  }
+
+  #[test]
+  fn encodes_constraints() {
+    let schema = schemars::schema_for!(OgMetadata);
+    let schema = schema.as_value();
+
+    let required: Vec<&str> = schema["required"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .filter_map(Value::as_str)
+      .collect();
+    for property in ["og:type", "og:url", "og:title", "og:description"] {
+      assert!(required.contains(&property), "{}", property);
+    }
+
+    let properties = &schema["properties"];
+    assert_eq!(properties["og:url"]["pattern"], HTTP_URL_PATTERN);
+    assert_eq!(
+      properties["og:locale:alternate"]["items"]["pattern"],
+      LOCALE_PATTERN
+    );
+
+    let object_types: Vec<&Value> = schema["$defs"]["ObjectType"]["oneOf"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|variant| &variant["const"])
+      .collect();
+    assert_eq!(object_types.len(), 12);
+    assert!(object_types.contains(&&"video.tv_show".into()));
+    assert!(schema["$defs"]["Determiner"]["enum"]
+      .as_array()
+      .unwrap()
+      .contains(&"".into()));
+
+    let image = &schema["$defs"]["Image"];
+    assert_eq!(image["properties"]["url"]["pattern"], IMAGE_URL_PATTERN);
+    assert_eq!(
+      image["properties"]["secure_url"]["pattern"],
+      HTTPS_URL_PATTERN
+    );
+    assert!(image["properties"]["type"]["enum"]
+      .as_array()
+      .unwrap()
+      .contains(&"image/webp".into()));
+    assert_eq!(image["allOf"].as_array().map(Vec::len), Some(2));
+  }
+
+  #[test]
+  fn generates_schemas_of_object_types() {
+    let schemas = schemas();
+    assert_eq!(schemas.len(), 16);
+
+    let (_, article) = &schemas[5];
+    let properties = &article.as_value()["properties"];
+    assert_eq!(properties["article:published_time"]["type"][0], "integer");
+    assert!(properties.get("og:title").is_some());
+
+    let (_, profile) = &schemas[6];
+    let genders = &profile.as_value()["$defs"]["Gender"]["enum"];
+    assert_eq!(genders, &serde_json::json!(["male", "female"]));
+  }
+}
@@ -0,0 +1,432 @@
+//! Consistency checks across all pages of a site.
+//!
+//! Validation of a single page cannot tell whether its metadata fits the
+//! rest of the site. The `SiteAnalyzer` collects the metadata of many pages
+//! together with the URL each page is served from and reports:
+//!
+//! - titles and descriptions shared by several pages
+//! - several pages claiming the same canonical URL (`og:url`)
+//! - pages whose `og:url` does not point to the page itself
+//! - `og:site_name` values that differ between pages
+//! - `og:locale` values that differ between pages of a single-locale site
+//! - `og:locale:alternate` locales no page of the site is published in
+//!
+//! URLs are compared in their canonical form (see `UrlNormalizer`). A
+//! trailing `index.html` and trailing slashes are ignored, so that
+//! `https://example.com/blog/index.html` matches `https://example.com/blog/`.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ogp::metadata::OgMetadata;
+//! use ogp::site::SiteAnalyzer;
+//! use url::Url;
+//!
+//! let page = |url: &str| OgMetadata {
+//!   title: Some("Home".into()),
+//!   url: Some(url.into()),
+//!   ..Default::default()
+//! };
+//!
+//! let mut analyzer = SiteAnalyzer::new();
+//! analyzer
+//!   .add_page(Url::parse("https://example.com/").unwrap(), page("https://example.com/"))
+//!   .add_page(Url::parse("https://example.com/a").unwrap(), page("https://example.com/a"));
+//!
+//! let findings = analyzer.analyze();
+//! assert_eq!(findings.len(), 1);
+//! assert_eq!(findings[0].to_string(), "2 pages share the title 'Home'");
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde::Serialize;
+use url::Url;
+
+use crate::{
+  canonical::UrlNormalizer, metadata::OgMetadata, platform::Severity,
+};
+
+/// A problem spanning several pages of a site.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SiteFinding {
+  /// Several pages share the same `og:title`.
+  DuplicateTitle { title: String, pages: Vec<Url> },
+
+  /// Several pages share the same `og:description`.
+  DuplicateDescription {
+    description: String,
+    pages: Vec<Url>,
+  },
+
+  /// Several pages claim the same canonical URL.
+  DuplicateUrl { url: String, pages: Vec<Url> },
+
+  /// The `og:url` of a page points somewhere else.
+  UrlMismatch { page: Url, url: String },
+
+  /// Pages declare different `og:site_name` values.
+  InconsistentSiteName { values: BTreeMap<String, Vec<Url>> },
+
+  /// Pages of a single-locale site declare different `og:locale` values.
+  InconsistentLocale { values: BTreeMap<String, Vec<Url>> },
+
+  /// Pages declare an `og:locale:alternate` no page is published in.
+  MissingAlternateLocale { locale: String, pages: Vec<Url> },
+}
+
+impl SiteFinding {
+  /// How severe the finding is. Conflicting canonical URLs make crawlers
+  /// pick the wrong page and are errors, everything else is a warning.
+  pub fn severity(&self) -> Severity {
+    match self {
+      SiteFinding::DuplicateUrl { .. } | SiteFinding::UrlMismatch { .. } => {
+        Severity::Error
+      }
+      _ => Severity::Warning,
+    }
+  }
+
+  /// The pages the finding refers to.
+  pub fn pages(&self) -> Vec<&Url> {
+    match self {
+      SiteFinding::DuplicateTitle { pages, .. }
+      | SiteFinding::DuplicateDescription { pages, .. }
+      | SiteFinding::DuplicateUrl { pages, .. }
+      | SiteFinding::MissingAlternateLocale { pages, .. } => {
+        pages.iter().collect()
+      }
+      SiteFinding::UrlMismatch { page, .. } => vec![page],
+      SiteFinding::InconsistentSiteName { values }
+      | SiteFinding::InconsistentLocale { values } => {
+        values.values().flatten().collect()
+      }
+    }
+  }
+}
+
+impl fmt::Display for SiteFinding {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let values = |values: &BTreeMap<String, Vec<Url>>| {
+      values
+        .iter()
+        .map(|(value, pages)| format!("'{}' ({} pages)", value, pages.len()))
+        .collect::<Vec<_>>()
+        .join(", ")
+    };
+
+    match self {
+      SiteFinding::DuplicateTitle { title, pages } => {
+        write!(f, "{} pages share the title '{}'", pages.len(), title)
+      }
+      SiteFinding::DuplicateDescription { description, pages } => write!(
+        f,
+        "{} pages share the description '{}'",
+        pages.len(),
+        description
+      ),
+      SiteFinding::DuplicateUrl { url, pages } => {
+        write!(f, "{} pages claim the canonical URL '{}'", pages.len(), url)
+      }
+      SiteFinding::UrlMismatch { page, url } => {
+        write!(f, "Page '{}' declares the canonical URL '{}'", page, url)
+      }
+      SiteFinding::InconsistentSiteName { values: site_names } => {
+        write!(f, "Pages use different site names: {}", values(site_names))
+      }
+      SiteFinding::InconsistentLocale { values: locales } => {
+        write!(f, "Pages use different locales: {}", values(locales))
+      }
+      SiteFinding::MissingAlternateLocale { locale, pages } => write!(
+        f,
+        "{} pages list the alternate locale '{}', but no page uses it",
+        pages.len(),
+        locale
+      ),
+    }
+  }
+}
+
+/// Collects the metadata of pages and checks them for consistency.
+#[derive(Debug, Clone, Default)]
+pub struct SiteAnalyzer {
+  normalizer: UrlNormalizer,
+  pages: Vec<(Url, OgMetadata)>,
+}
+
+impl SiteAnalyzer {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Uses `normalizer` to compare URLs, e.g. one built from a `SiteConfig`.
+  pub fn with_normalizer(normalizer: UrlNormalizer) -> Self {
+    Self {
+      normalizer,
+      pages: Vec::new(),
+    }
+  }
+
+  /// Adds the metadata of the page served from `url`.
+  pub fn add_page(&mut self, url: Url, metadata: OgMetadata) -> &mut Self {
+    self.pages.push((url, metadata));
+    self
+  }
+
+  /// The number of pages added.
+  pub fn len(&self) -> usize {
+    self.pages.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.pages.is_empty()
+  }
+
+  /// Runs all checks. Findings are ordered by kind, then by value.
+  pub fn analyze(&self) -> Vec<SiteFinding> {
+    let mut findings = Vec::new();
+
+    let titles = self.group_by(|metadata| metadata.title.clone());
+    findings.extend(
+      duplicates(titles)
+        .map(|(title, pages)| SiteFinding::DuplicateTitle { title, pages }),
+    );
+
+    let descriptions = self.group_by(|metadata| metadata.description.clone());
+    findings.extend(duplicates(descriptions).map(|(description, pages)| {
+      SiteFinding::DuplicateDescription { description, pages }
+    }));
+
+    let urls = self.group_by(|metadata| {
+      let url = metadata.url.as_deref()?;
+      Some(self.page_key(url).unwrap_or_else(|| url.to_string()))
+    });
+    findings.extend(
+      duplicates(urls)
+        .map(|(url, pages)| SiteFinding::DuplicateUrl { url, pages }),
+    );
+
+    for (page, metadata) in &self.pages {
+      let Some(url) = metadata.url.as_ref() else {
+        continue;
+      };
+      if self.page_key(url) != self.page_key(page.as_str()) {
+        findings.push(SiteFinding::UrlMismatch {
+          page: page.clone(),
+          url: url.clone(),
+        });
+      }
+    }
+
+    let site_names = self.group_by(|metadata| metadata.site_name.clone());
+    if site_names.len() > 1 {
+      findings.push(SiteFinding::InconsistentSiteName { values: site_names });
+    }
+
+    let locales = self.group_by(|metadata| metadata.locale.clone());
+    let alternates: BTreeMap<String, Vec<Url>> =
+      self.group_by_all(|metadata| {
+        metadata.locale_alternate.clone().unwrap_or_default()
+      });
+
+    // Multilingual sites use several locales on purpose.
+    if locales.len() > 1 && alternates.is_empty() {
+      findings.push(SiteFinding::InconsistentLocale {
+        values: locales.clone(),
+      });
+    }
+
+    for (locale, pages) in alternates {
+      if !locales.contains_key(&locale) {
+        findings.push(SiteFinding::MissingAlternateLocale { locale, pages });
+      }
+    }
+
+    findings
+  }
+
+  /// Groups pages by the value of a property, skipping unset values.
+  fn group_by(
+    &self,
+    value: impl Fn(&OgMetadata) -> Option<String>,
+  ) -> BTreeMap<String, Vec<Url>> {
+    self.group_by_all(|metadata| value(metadata).into_iter().collect())
+  }
+
+  /// Groups pages by each value of a list property.
+  fn group_by_all(
+    &self,
+    values: impl Fn(&OgMetadata) -> Vec<String>,
+  ) -> BTreeMap<String, Vec<Url>> {
+    let mut groups: BTreeMap<String, Vec<Url>> = BTreeMap::new();
+    for (page, metadata) in &self.pages {
+      let values: BTreeSet<String> = values(metadata)
+        .into_iter()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect();
+
+      for value in values {
+        groups.entry(value).or_default().push(page.clone());
+      }
+    }
+    groups
+  }
+
+  /// The canonical form of `url` used for comparisons, or `None` if it is
+  /// not a valid HTTP(S) URL.
+  fn page_key(&self, url: &str) -> Option<String> {
+    let url = self.normalizer.normalize_str(url).ok()?;
+    let mut key = url.to_string();
+    if let Some(query_start) = key.find('?') {
+      let (path, query) = key.split_at(query_start);
+      key = format!("{}{}", trim_path(path), query);
+    } else {
+      key = trim_path(&key).to_string();
+    }
+    Some(key)
+  }
+}
+
+fn trim_path(path: &str) -> &str {
+  let path = path
+    .strip_suffix("index.html")
+    .or_else(|| path.strip_suffix("index.htm"))
+    .unwrap_or(path);
+  path.trim_end_matches('/')
+}
+
+fn duplicates(
+  groups: BTreeMap<String, Vec<Url>>,
+) -> impl Iterator<Item = (String, Vec<Url>)> {
+  groups.into_iter().filter(|(_, pages)| pages.len() > 1)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn url(url: &str) -> Url {
+    Url::parse(url).unwrap()
+  }
+
+  fn page(path: &str, title: &str) -> (Url, OgMetadata) {
+    let page_url = url(&format!("https://example.com{}", path));
+    let metadata = OgMetadata {
+      title: Some(title.into()),
+      url: Some(page_url.to_string()),
+      site_name: Some("Example".into()),
+      locale: Some("en_US".into()),
+      ..Default::default()
+    };
+    (page_url, metadata)
+  }
+
+  fn analyze(pages: Vec<(Url, OgMetadata)>) -> Vec<SiteFinding> {
+    let mut analyzer = SiteAnalyzer::new();
+    for (url, metadata) in pages {
+      analyzer.add_page(url, metadata);
+    }
+    analyzer.analyze()
+  }
+
+  #[test]
+  fn accepts_consistent_site() {
+    let findings = analyze(vec![
+      page("/", "Home"),
+      page("/blog/", "Blog"),
+      page("/about", "About"),
+    ]);
+    assert!(findings.is_empty());
+  }
+
+  #[test]
+  fn finds_duplicates() {
+    let (about, mut metadata) = page("/about", "Home");
+    metadata.description = Some("Welcome".into());
+    let (home, mut home_metadata) = page("/", "Home");
+    home_metadata.description = Some(" Welcome ".into());
+
+    let findings = analyze(vec![
+      (home.clone(), home_metadata),
+      (about.clone(), metadata),
+    ]);
+    assert_eq!(
+      findings,
+      [
+        SiteFinding::DuplicateTitle {
+          title: "Home".into(),
+          pages: vec![home.clone(), about.clone()],
+        },
+        SiteFinding::DuplicateDescription {
+          description: "Welcome".into(),
+          pages: vec![home, about],
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn checks_canonical_urls() {
+    let (index, mut metadata) = page("/blog/index.html", "Blog");
+    metadata.url = Some("https://example.com/blog/?utm_source=x".into());
+    let (copy, mut copy_metadata) = page("/blog/copy.html", "Copy");
+    copy_metadata.url = Some("https://example.com/blog".into());
+
+    let findings =
+      analyze(vec![(index, metadata), (copy.clone(), copy_metadata)]);
+    assert_eq!(findings.len(), 2);
+    assert!(matches!(
+      &findings[0],
+      SiteFinding::DuplicateUrl { url, pages }
+        if url == "https://example.com/blog" && pages.len() == 2
+    ));
+    assert_eq!(
+      findings[1],
+      SiteFinding::UrlMismatch {
+        page: copy,
+        url: "https://example.com/blog".into(),
+      }
+    );
+    assert_eq!(findings[1].severity(), Severity::Error);
+  }
+
+  #[test]
+  fn checks_site_names_and_locales() {
+    let (home, metadata) = page("/", "Home");
+    let (blog, mut blog_metadata) = page("/blog/", "Blog");
+    blog_metadata.site_name = Some("Example Blog".into());
+    blog_metadata.locale = Some("en_GB".into());
+
+    let findings = analyze(vec![(home, metadata), (blog, blog_metadata)]);
+    let kinds: Vec<String> =
+      findings.iter().map(|finding| finding.to_string()).collect();
+    assert_eq!(
+      kinds,
+      [
+        "Pages use different site names: 'Example' (1 pages), 'Example Blog' (1 pages)",
+        "Pages use different locales: 'en_GB' (1 pages), 'en_US' (1 pages)",
+      ]
+    );
+  }
+
+  #[test]
+  fn checks_alternate_locales() {
+    let (home, mut metadata) = page("/", "Home");
+    metadata.locale_alternate = Some(vec!["de_DE".into(), "fr_FR".into()]);
+    let (de, mut de_metadata) = page("/de/", "Startseite");
+    de_metadata.locale = Some("de_DE".into());
+    de_metadata.locale_alternate = Some(vec!["en_US".into()]);
+
+    let findings = analyze(vec![(home.clone(), metadata), (de, de_metadata)]);
+    assert_eq!(
+      findings,
+      [SiteFinding::MissingAlternateLocale {
+        locale: "fr_FR".into(),
+        pages: vec![home],
+      }]
+    );
+  }
+}
@@ -0,0 +1,313 @@
+//! Template engine helpers rendering Open Graph meta tags.
+//!
+//! Each engine gets an `og_tags` filter (a helper for Handlebars) that
+//! renders the tags of a metadata value, escaped and in the same order as
+//! `ToHTML`, so templates never hand-write `<meta>` lines:
+//!
+//! - Askama (feature `askama`): `{{ og|og_tags }}` after re-exporting
+//!   `og_tags` from the template's `filters` module. `{{ og.meta_tags() }}`
+//!   works without a filter.
+//! - Tera (feature `tera`): `{{ og | og_tags }}` after `register_tera`.
+//! - MiniJinja (feature `minijinja`): `{{ og | og_tags }}` after
+//!   `register_minijinja`.
+//! - Handlebars (feature `handlebars`): `{{og_tags og}}` after
+//!   `register_handlebars`.
+//!
+//! The engine helpers are only available with their feature enabled;
+//! `properties_of` and `render_value` are always available.
+//!
+//! Askama passes typed values to the filter. The other engines pass the
+//! serialized value, which is read back according to its `og:type`, e.g. as
+//! `ArticleMetadata` for articles. The flat representation (see
+//! `ogp::flat`) is accepted as well.
+//!
+//! The output is marked as safe for each engine, so it is not escaped
+//! again. For engines without a helper, such as Maud, use the `Display`
+//! adapter returned by `ToHTML::meta_tags`.
+//!
+//! # Usage
+//!
+//! ```rust
+//! # #[cfg(feature = "minijinja")]
+//! # {
+//! use ogp::metadata::OgMetadata;
+//!
+//! let mut env = minijinja::Environment::new();
+//! ogp::template::register_minijinja(&mut env);
+//!
+//! let og = OgMetadata {
+//!   title: Some("The Rock".into()),
+//!   ..Default::default()
+//! };
+//! let html = env
+//!   .render_str("{{ og | og_tags }}", minijinja::context! { og })
+//!   .unwrap();
+//! assert!(html.contains(r#"<meta property="og:title" content="The Rock" />"#));
+//! # }
+//! ```
+
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{
+  error::Error,
+  flat::FlatProperties,
+  inject::render_tags,
+  metadata::OgMetadata,
+  object_type::{
+    article::ArticleMetadata,
+    book::Book,
+    music::{MusicAlbum, MusicPlaylist, MusicRadioStation, MusicSong},
+    profile::ProfileMetadata,
+    video::{VideoEpisode, VideoMovie, VideoOther, VideoTvShow},
+    ObjectType,
+  },
+  Result,
+};
+
+#[cfg(feature = "askama")]
+use crate::convert::{MetaTags, ToHTML};
+
+/// Reads the properties of a serialized metadata value.
+///
+/// Objects are deserialized as the struct of their `og:type`. Arrays are
+/// read as the flat representation and returned as they are.
+pub fn properties_of(value: Value) -> Result<Vec<(String, String)>> {
+  fn properties<T: DeserializeOwned + FlatProperties>(
+    value: Value,
+  ) -> Result<Vec<(String, String)>> {
+    serde_json::from_value::<T>(value)
+      .map(|value| value.to_properties())
+      .map_err(|err| Error::Generic(err.to_string()))
+  }
+
+  if value.is_array() {
+    return serde_json::from_value(value)
+      .map_err(|err| Error::Generic(err.to_string()));
+  }
+
+  let object_type = value
+    .get("og:type")
+    .and_then(Value::as_str)
+    .map(ObjectType::from_str)
+    .transpose()?
+    .unwrap_or_default();
+
+  match object_type {
+    ObjectType::Article => properties::<ArticleMetadata>(value),
+    ObjectType::Profile => properties::<ProfileMetadata>(value),
+    ObjectType::Book => properties::<Book>(value),
+    ObjectType::MusicSong => properties::<MusicSong>(value),
+    ObjectType::MusicAlbum => properties::<MusicAlbum>(value),
+    ObjectType::MusicPlaylist => properties::<MusicPlaylist>(value),
+    ObjectType::MusicRadioStation => properties::<MusicRadioStation>(value),
+    ObjectType::VideoMovie => properties::<VideoMovie>(value),
+    ObjectType::VideoEpisode => properties::<VideoEpisode>(value),
+    ObjectType::VideoTvShow => properties::<VideoTvShow>(value),
+    ObjectType::VideoOther => properties::<VideoOther>(value),
+    _ => properties::<OgMetadata>(value),
+  }
+}
+
+/// Renders the meta tags of a serialized metadata value, one per line.
+pub fn render_value(value: Value) -> Result<String> {
+  properties_of(value).map(|properties| render_tags(&properties))
+}
+
+/// The Askama filter `og_tags`, rendering the meta tags of any `ToHTML`
+/// value. Re-export it from the `filters` module of your templates:
+///
+/// ```rust
+/// mod filters {
+///   pub use ogp::template::og_tags;
+/// }
+///
+/// #[derive(askama::Template)]
+/// #[template(source = "<head>{{ og|og_tags }}</head>", ext = "html")]
+/// struct Page {
+///   og: ogp::metadata::OgMetadata,
+/// }
+/// ```
+#[cfg(feature = "askama")]
+#[askama::filter_fn]
+pub fn og_tags<'a, T: ToHTML>(
+  value: &'a T,
+  _: &dyn askama::Values,
+) -> askama::Result<MetaTags<'a, T>> {
+  Ok(value.meta_tags())
+}
+
+/// Registers the `og_tags` filter with Tera.
+#[cfg(feature = "tera")]
+pub fn register_tera(tera: &mut tera::Tera) {
+  tera.register_filter("og_tags", TeraOgTags);
+}
+
+#[cfg(feature = "tera")]
+struct TeraOgTags;
+
+#[cfg(feature = "tera")]
+impl tera::Filter for TeraOgTags {
+  fn filter(
+    &self,
+    value: &tera::Value,
+    _: &std::collections::HashMap<String, tera::Value>,
+  ) -> tera::Result<tera::Value> {
+    render_value(value.clone())
+      .map(tera::Value::String)
+      .map_err(|err| tera::Error::msg(err.to_string()))
+  }
+
+  fn is_safe(&self) -> bool {
+    true
+  }
+}
+
+/// Registers the `og_tags` filter with MiniJinja.
+#[cfg(feature = "minijinja")]
+pub fn register_minijinja(env: &mut minijinja::Environment<'_>) {
+  env.add_filter("og_tags", minijinja_og_tags);
+}
+
+#[cfg(feature = "minijinja")]
+fn minijinja_og_tags(
+  value: minijinja::Value,
+) -> std::result::Result<minijinja::Value, minijinja::Error> {
+  let error = |err: String| {
+    minijinja::Error::new(minijinja::ErrorKind::InvalidOperation, err)
+  };
+  let value =
+    serde_json::to_value(&value).map_err(|err| error(err.to_string()))?;
+  render_value(value)
+    .map(minijinja::Value::from_safe_string)
+    .map_err(|err| error(err.to_string()))
+}
+
+/// Registers the `og_tags` helper with Handlebars.
+#[cfg(feature = "handlebars")]
+pub fn register_handlebars(handlebars: &mut handlebars::Handlebars<'_>) {
+  handlebars.register_helper("og_tags", Box::new(handlebars_og_tags));
+}
+
+#[cfg(feature = "handlebars")]
+fn handlebars_og_tags(
+  helper: &handlebars::Helper<'_>,
+  _: &handlebars::Handlebars<'_>,
+  _: &handlebars::Context,
+  _: &mut handlebars::RenderContext<'_, '_>,
+  out: &mut dyn handlebars::Output,
+) -> handlebars::HelperResult {
+  use handlebars::RenderErrorReason;
+
+  let value = helper
+    .param(0)
+    .ok_or(RenderErrorReason::ParamNotFoundForIndex("og_tags", 0))?
+    .value();
+  let tags = render_value(value.clone())
+    .map_err(|err| RenderErrorReason::Other(err.to_string()))?;
+  out.write(&tags)?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+  use crate::object_type::retype::ObjectMetadata;
+
+  const EXPECTED: &str = "<meta property=\"og:type\" content=\"article\" />\n\
+    <meta property=\"og:title\" content=\"Tom &amp; Jerry\" />\n\
+    <meta property=\"article:tag\" content=\"cartoons\" />";
+
+  fn article() -> ArticleMetadata {
+    let mut article = ArticleMetadata::from_root(OgMetadata {
+      title: Some("Tom & Jerry".into()),
+      ..Default::default()
+    });
+    article.add_tag("cartoons");
+    article
+  }
+
+  #[test]
+  fn reads_properties_by_object_type() {
+    let value = serde_json::to_value(article()).unwrap();
+    assert_eq!(render_value(value).unwrap(), EXPECTED);
+
+    let flat = json!([["og:title", "<b>"]]);
+    assert_eq!(
+      render_value(flat).unwrap(),
+      r#"<meta property="og:title" content="&lt;b&gt;" />"#
+    );
+
+    assert!(render_value(json!({ "og:type": "movie" })).is_err());
+  }
+
+  #[cfg(feature = "askama")]
+  #[test]
+  fn renders_askama_filter() {
+    use askama::Template;
+
+    mod filters {
+      pub use crate::template::og_tags;
+    }
+
+    #[derive(Template)]
+    #[template(source = "<head>{{ og|og_tags }}</head>", ext = "html")]
+    struct Page {
+      og: ArticleMetadata,
+    }
+
+    let html = Page { og: article() }.render().unwrap();
+    assert_eq!(html, format!("<head>{EXPECTED}</head>"));
+  }
+
+  #[cfg(feature = "tera")]
+  #[test]
+  fn renders_tera_filter() {
+    let mut tera = tera::Tera::default();
+    register_tera(&mut tera);
+    tera
+      .add_raw_template("page.html", "<head>{{ og | og_tags }}</head>")
+      .unwrap();
+
+    let context =
+      tera::Context::from_serialize(json!({ "og": article() })).unwrap();
+    let html = tera.render("page.html", &context).unwrap();
+    assert_eq!(html, format!("<head>{EXPECTED}</head>"));
+  }
+
+  #[cfg(feature = "minijinja")]
+  #[test]
+  fn renders_minijinja_filter() {
+    let mut env = minijinja::Environment::new();
+    register_minijinja(&mut env);
+    env
+      .add_template("page.html", "<head>{{ og | og_tags }}</head>")
+      .unwrap();
+
+    let html = env
+      .get_template("page.html")
+      .unwrap()
+      .render(minijinja::context! { og => article() })
+      .unwrap();
+    assert_eq!(html, format!("<head>{EXPECTED}</head>"));
+  }
+
+  #[cfg(feature = "handlebars")]
+  #[test]
+  fn renders_handlebars_helper() {
+    let mut handlebars = handlebars::Handlebars::new();
+    register_handlebars(&mut handlebars);
+    handlebars
+      .register_template_string("page", "<head>{{og_tags og}}</head>")
+      .unwrap();
+
+    let html = handlebars
+      .render("page", &json!({ "og": article() }))
+      .unwrap();
+    assert_eq!(html, format!("<head>{EXPECTED}</head>"));
+  }
+}
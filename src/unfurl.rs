@@ -0,0 +1,598 @@
+//! Link unfurling with fallbacks for pages without Open Graph tags.
+//!
+//! Many pages declare no or incomplete Open Graph metadata. The `Unfurler`
+//! fills in missing properties from other sources, in order of precedence:
+//!
+//! 1. Open Graph tags
+//! 2. Twitter card tags (`twitter:title`, `twitter:description`,
+//!    `twitter:image`)
+//! 3. JSON-LD (`headline` or `name`, `description`, `image`, `url`,
+//!    `publisher.name`)
+//! 4. `<title>`
+//! 5. `<meta name="description">`
+//! 6. `<link rel="canonical">`
+//! 7. `<link rel="apple-touch-icon">` or `<link rel="icon">`
+//! 8. The first `<img>` in `<body>` declaring a width and height of at
+//!    least `min_image_size` pixels
+//!
+//! Each source is read into an `OgMetadata` layer, and the layers are
+//! merged with a `MergePolicy`. The resulting `Merged::sources` record the
+//! `Source` every property came from, e.g. to show the confidence of a
+//! value. Images are taken from a single source, the first that has any.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use ogp::unfurl::{Source, Unfurler};
+//!
+//! let html = r#"<html><head>
+//!   <title>Release notes</title>
+//!   <meta name="twitter:description" content="What's new">
+//!   <link rel="canonical" href="/news">
+//! </head></html>"#;
+//!
+//! let mut unfurler = Unfurler::new();
+//! unfurler.set_page_url("https://example.com/news?ref=feed".parse().unwrap());
+//! let unfurled = unfurler.unfurl(html);
+//!
+//! assert_eq!(unfurled.metadata.title.as_deref(), Some("Release notes"));
+//! assert_eq!(unfurled.source_of("og:title"), Some(Source::Title.as_str()));
+//! assert_eq!(unfurled.source_of("og:description"), Some("twitter_card"));
+//! assert_eq!(unfurled.metadata.url.as_deref(), Some("https://example.com/news"));
+//! ```
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use url::Url;
+
+use crate::{
+  error::Error,
+  extract::{html, Extractor, ParseMode},
+  merge::{MergePolicy, Merged},
+  metadata::{Image, OgMetadata},
+  utils::resolve_http_url,
+};
+
+/// The default minimum width and height of a body image, in pixels.
+pub const DEFAULT_MIN_IMAGE_SIZE: u32 = 200;
+
+/// A source of unfurled properties, see the module docs.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+  OpenGraph,
+  TwitterCard,
+  JsonLd,
+  Title,
+  MetaDescription,
+  Canonical,
+  Icon,
+  BodyImage,
+}
+
+impl Source {
+  /// All sources, in order of precedence.
+  pub const ALL: [Source; 8] = [
+    Source::OpenGraph,
+    Source::TwitterCard,
+    Source::JsonLd,
+    Source::Title,
+    Source::MetaDescription,
+    Source::Canonical,
+    Source::Icon,
+    Source::BodyImage,
+  ];
+
+  /// The name of the source, as recorded in `Merged::sources`.
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Source::OpenGraph => "open_graph",
+      Source::TwitterCard => "twitter_card",
+      Source::JsonLd => "json_ld",
+      Source::Title => "title",
+      Source::MetaDescription => "meta_description",
+      Source::Canonical => "canonical",
+      Source::Icon => "icon",
+      Source::BodyImage => "body_image",
+    }
+  }
+}
+
+impl fmt::Display for Source {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl FromStr for Source {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Source::ALL
+      .into_iter()
+      .find(|source| source.as_str() == s)
+      .ok_or_else(|| Error::Generic(format!("Unknown unfurl source '{}'", s)))
+  }
+}
+
+/// Unfurls links from Open Graph tags and fallback sources.
+#[derive(Debug, Clone)]
+pub struct Unfurler {
+  page_url: Option<Url>,
+  min_image_size: u32,
+}
+
+impl Default for Unfurler {
+  fn default() -> Self {
+    Unfurler {
+      page_url: None,
+      min_image_size: DEFAULT_MIN_IMAGE_SIZE,
+    }
+  }
+}
+
+impl Unfurler {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets the URL the document was retrieved from. Used to resolve relative
+  /// URLs when the document has no `<base href>`.
+  pub fn set_page_url(&mut self, page_url: Url) -> &mut Self {
+    self.page_url = Some(page_url);
+    self
+  }
+
+  /// Sets the minimum width and height of body images.
+  pub fn set_min_image_size(&mut self, size: u32) -> &mut Self {
+    self.min_image_size = size;
+    self
+  }
+
+  /// Unfurls `html`, recording the source of every property.
+  pub fn unfurl(&self, html: &str) -> Merged<OgMetadata> {
+    let layers = self.layers(html);
+
+    // Merge from the lowest to the highest precedence, so that the last
+    // source setting a property, i.e. the most trusted one, wins.
+    let sources: Vec<(&str, &OgMetadata)> = layers
+      .iter()
+      .rev()
+      .map(|(source, metadata)| (source.as_str(), metadata))
+      .collect();
+    MergePolicy::default().merge(&sources)
+  }
+
+  /// The metadata read from each source, in order of precedence.
+  pub fn layers(&self, html: &str) -> Vec<(Source, OgMetadata)> {
+    let tags = html::scan(html);
+
+    // Open Graph tags are extracted leniently. Markup too broken even for
+    // that is ignored, like any other missing source.
+    let extraction = self.page_url.as_ref().map_or_else(
+      || Extractor::new(ParseMode::Lenient).extract(html),
+      |page_url| {
+        Extractor::new(ParseMode::Lenient)
+          .set_page_url(page_url.clone())
+          .extract(html)
+      },
+    );
+    let (open_graph, base_url) = match extraction {
+      Ok(extraction) => (extraction.metadata, extraction.base_url),
+      Err(_) => (OgMetadata::default(), self.page_url.clone()),
+    };
+    let base_url = base_url.as_ref();
+
+    vec![
+      (Source::OpenGraph, open_graph),
+      (Source::TwitterCard, twitter_card(&tags, base_url)),
+      (Source::JsonLd, json_ld(&tags, base_url)),
+      (Source::Title, title(&tags)),
+      (Source::MetaDescription, meta_description(&tags)),
+      (Source::Canonical, canonical(&tags, base_url)),
+      (Source::Icon, icon(&tags, base_url)),
+      (
+        Source::BodyImage,
+        body_image(&tags, base_url, self.min_image_size),
+      ),
+    ]
+  }
+}
+
+/// Unfurls `html` with the default options.
+pub fn unfurl(html: &str) -> Merged<OgMetadata> {
+  Unfurler::new().unfurl(html)
+}
+
+/// The `(name, content)` pairs of `<meta>` tags, by `name` or `property`.
+fn meta_tags(tags: &[html::Tag]) -> impl Iterator<Item = (String, &str)> {
+  tags
+    .iter()
+    .filter(|tag| tag.name == "meta")
+    .filter_map(|tag| {
+      let name = tag.attr("name").or_else(|| tag.attr("property"))?;
+      let content = tag.attr("content").or_else(|| tag.attr("value"))?;
+      Some((name.trim().to_ascii_lowercase(), content.trim()))
+    })
+}
+
+/// The first non-empty `content` of a `<meta>` tag with the given name.
+fn meta_content<'a>(tags: &'a [html::Tag], name: &str) -> Option<&'a str> {
+  meta_tags(tags)
+    .find(|(key, content)| key == name && !content.is_empty())
+    .map(|(_, content)| content)
+}
+
+/// Whether the space-separated `rel` attribute of `tag` contains `rel`.
+fn has_rel(tag: &html::Tag, rel: &str) -> bool {
+  tag.attr("rel").is_some_and(|value| {
+    value
+      .split_ascii_whitespace()
+      .any(|token| token.eq_ignore_ascii_case(rel))
+  })
+}
+
+fn image(url: &str, base_url: Option<&Url>) -> Option<Image> {
+  Image::from_str_with_base(url.trim(), base_url).ok()
+}
+
+fn twitter_card(tags: &[html::Tag], base_url: Option<&Url>) -> OgMetadata {
+  let mut image = meta_content(tags, "twitter:image")
+    .or_else(|| meta_content(tags, "twitter:image:src"))
+    .and_then(|url| image(url, base_url));
+  if let Some(image) = &mut image {
+    image.alt = meta_content(tags, "twitter:image:alt").map(str::to_string);
+  }
+
+  OgMetadata {
+    title: meta_content(tags, "twitter:title").map(str::to_string),
+    description: meta_content(tags, "twitter:description").map(str::to_string),
+    images: image.into_iter().collect(),
+    ..Default::default()
+  }
+}
+
+fn json_ld(tags: &[html::Tag], base_url: Option<&Url>) -> OgMetadata {
+  let mut entities = Vec::new();
+  for tag in tags {
+    let is_json_ld = tag.name == "script"
+      && tag.attr("type").is_some_and(|kind| {
+        kind.trim().eq_ignore_ascii_case("application/ld+json")
+      });
+    let value = is_json_ld
+      .then_some(tag.text.as_deref())
+      .flatten()
+      .and_then(|text| serde_json::from_str::<Value>(text.trim()).ok());
+    if let Some(value) = value {
+      collect_entities(value, &mut entities);
+    }
+  }
+
+  // The main entity is the first one with a title, e.g. the `Article`
+  // rather than the `BreadcrumbList` of a page.
+  let string =
+    |value: &Value| value.as_str().map(str::trim).map(str::to_string);
+  let Some(entity) = entities.iter().find(|entity| {
+    entity
+      .get("headline")
+      .or_else(|| entity.get("name"))
+      .is_some()
+  }) else {
+    return OgMetadata::default();
+  };
+
+  OgMetadata {
+    title: entity
+      .get("headline")
+      .or_else(|| entity.get("name"))
+      .and_then(string),
+    description: entity.get("description").and_then(string),
+    url: entity
+      .get("url")
+      .and_then(Value::as_str)
+      .and_then(|url| resolve_http_url(url.trim(), base_url).ok())
+      .map(String::from),
+    site_name: entity
+      .get("publisher")
+      .and_then(|publisher| publisher.get("name"))
+      .and_then(string),
+    images: entity
+      .get("image")
+      .and_then(|value| json_ld_image(value, base_url))
+      .into_iter()
+      .collect(),
+    ..Default::default()
+  }
+}
+
+/// Collects the entities of a JSON-LD value, including `@graph` members.
+fn collect_entities(value: Value, entities: &mut Vec<Value>) {
+  match value {
+    Value::Array(values) => {
+      values
+        .into_iter()
+        .for_each(|value| collect_entities(value, entities));
+    }
+    Value::Object(mut object) => {
+      if let Some(graph) = object.remove("@graph") {
+        collect_entities(graph, entities);
+      }
+      entities.push(Value::Object(object));
+    }
+    _ => {}
+  }
+}
+
+/// Reads an `image` given as URL, `ImageObject` or a list of either.
+fn json_ld_image(value: &Value, base_url: Option<&Url>) -> Option<Image> {
+  let dimension = |value: Option<&Value>| match value? {
+    Value::Number(number) => number.as_u64().and_then(|n| n.try_into().ok()),
+    Value::String(s) => s.trim().trim_end_matches("px").parse().ok(),
+    _ => None,
+  };
+
+  match value {
+    Value::String(url) => image(url, base_url),
+    Value::Array(values) => {
+      values.first().and_then(|v| json_ld_image(v, base_url))
+    }
+    Value::Object(object) => {
+      let url = object.get("url").or_else(|| object.get("contentUrl"))?;
+      let mut image = image(url.as_str()?, base_url)?;
+      // Dimensions are only meaningful as a pair.
+      if let (Some(width), Some(height)) = (
+        dimension(object.get("width")),
+        dimension(object.get("height")),
+      ) {
+        image.width = Some(width);
+        image.height = Some(height);
+      }
+      Some(image)
+    }
+    _ => None,
+  }
+}
+
+fn title(tags: &[html::Tag]) -> OgMetadata {
+  let title = tags
+    .iter()
+    .find(|tag| tag.name == "title")
+    .and_then(|tag| tag.text.as_deref())
+    .map(|text| {
+      html::decode_entities(text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+    })
+    .filter(|title| !title.is_empty());
+
+  OgMetadata {
+    title,
+    ..Default::default()
+  }
+}
+
+fn meta_description(tags: &[html::Tag]) -> OgMetadata {
+  OgMetadata {
+    description: meta_content(tags, "description").map(str::to_string),
+    ..Default::default()
+  }
+}
+
+fn canonical(tags: &[html::Tag], base_url: Option<&Url>) -> OgMetadata {
+  let url = tags
+    .iter()
+    .filter(|tag| tag.name == "link" && has_rel(tag, "canonical"))
+    .filter_map(|tag| tag.attr("href"))
+    .find_map(|href| resolve_http_url(href.trim(), base_url).ok());
+
+  OgMetadata {
+    url: url.map(String::from),
+    ..Default::default()
+  }
+}
+
+/// Touch icons are preferred, as they are larger than favicons.
+fn icon(tags: &[html::Tag], base_url: Option<&Url>) -> OgMetadata {
+  let icon = |rels: &[&str]| {
+    tags
+      .iter()
+      .filter(|tag| {
+        tag.name == "link" && rels.iter().any(|rel| has_rel(tag, rel))
+      })
+      .find_map(|tag| {
+        let mut image = image(tag.attr("href")?, base_url)?;
+        let sizes = tag.attr("sizes").unwrap_or_default().to_ascii_lowercase();
+        let size = sizes.split_once('x').and_then(|(width, height)| {
+          Some((width.trim().parse().ok()?, height.trim().parse().ok()?))
+        });
+        if let Some((width, height)) = size {
+          image.width = Some(width);
+          image.height = Some(height);
+        }
+        Some(image)
+      })
+  };
+
+  let image = icon(&["apple-touch-icon", "apple-touch-icon-precomposed"])
+    .or_else(|| icon(&["icon"]));
+
+  OgMetadata {
+    images: image.into_iter().collect(),
+    ..Default::default()
+  }
+}
+
+fn body_image(
+  tags: &[html::Tag],
+  base_url: Option<&Url>,
+  min_size: u32,
+) -> OgMetadata {
+  let dimension = |tag: &html::Tag, name: &str| {
+    tag
+      .attr(name)
+      .and_then(|value| value.trim().trim_end_matches("px").parse::<u32>().ok())
+  };
+
+  let body = tags.iter().position(|tag| tag.name == "body").unwrap_or(0);
+  let image = tags[body..]
+    .iter()
+    .filter(|tag| tag.name == "img")
+    .find_map(|tag| {
+      let width = dimension(tag, "width").filter(|width| *width >= min_size)?;
+      let height =
+        dimension(tag, "height").filter(|height| *height >= min_size)?;
+      let mut image = image(tag.attr("src")?, base_url)?;
+      image.width = Some(width);
+      image.height = Some(height);
+      image.alt = tag
+        .attr("alt")
+        .map(str::to_string)
+        .filter(|alt| !alt.is_empty());
+      Some(image)
+    });
+
+  OgMetadata {
+    images: image.into_iter().collect(),
+    ..Default::default()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn image_url(metadata: &OgMetadata) -> Option<&str> {
+    metadata.images.first()?.url.as_ref().map(Url::as_str)
+  }
+
+  #[test]
+  fn prefers_open_graph_tags() {
+    let html = r#"<head>
+      <meta property="og:title" content="OG title">
+      <meta property="og:image" content="https://example.com/og.png">
+      <meta name="twitter:title" content="Twitter title">
+      <meta name="twitter:description" content="Twitter description">
+      <meta name="twitter:image" content="https://example.com/card.png">
+      <title>Page title</title>
+    </head>"#;
+
+    let unfurled = unfurl(html);
+    assert_eq!(unfurled.metadata.title.as_deref(), Some("OG title"));
+    assert_eq!(unfurled.source_of("og:title"), Some("open_graph"));
+    assert_eq!(
+      unfurled.metadata.description.as_deref(),
+      Some("Twitter description")
+    );
+    assert_eq!(unfurled.source_of("og:description"), Some("twitter_card"));
+    assert_eq!(
+      image_url(&unfurled.metadata),
+      Some("https://example.com/og.png")
+    );
+    assert_eq!(unfurled.sources_of("og:image"), ["open_graph"]);
+  }
+
+  #[test]
+  fn reads_json_ld() {
+    let html = r#"<head>
+      <script type="application/ld+json">
+        {"@context": "https://schema.org", "@graph": [
+          {"@type": "BreadcrumbList", "itemListElement": []},
+          {"@type": "NewsArticle", "headline": "Launch",
+           "description": "We launched",
+           "url": "https://example.com/launch",
+           "publisher": {"@type": "Organization", "name": "Example"},
+           "image": [{"@type": "ImageObject", "url": "/launch.png",
+                      "width": 1200, "height": "630"}]}
+        ]}
+      </script>
+      <title>Launch | Example</title>
+    </head>"#;
+
+    let mut unfurler = Unfurler::new();
+    unfurler.set_page_url(Url::parse("https://example.com/").unwrap());
+    let unfurled = unfurler.unfurl(html);
+
+    let metadata = &unfurled.metadata;
+    assert_eq!(metadata.title.as_deref(), Some("Launch"));
+    assert_eq!(metadata.site_name.as_deref(), Some("Example"));
+    assert_eq!(metadata.url.as_deref(), Some("https://example.com/launch"));
+    assert_eq!(image_url(metadata), Some("https://example.com/launch.png"));
+    assert_eq!(metadata.images[0].height, Some(630));
+    for property in ["og:title", "og:description", "og:url", "og:image"] {
+      assert_eq!(
+        unfurled.source_of(property),
+        Some("json_ld"),
+        "{}",
+        property
+      );
+    }
+  }
+
+  #[test]
+  fn falls_back_to_plain_html() {
+    let html = r#"<html><head>
+      <title>
+        Tom &amp; Jerry
+      </title>
+      <meta name="Description" content="A cartoon">
+      <link rel="canonical" href="/tom-and-jerry">
+      <link rel="shortcut icon" href="/favicon.ico">
+    </head><body>
+      <img src="/spacer.gif" width="1" height="1">
+      <img src="/logo.png">
+      <img src="poster.jpg" width="600" height="400px" alt="Poster">
+    </body></html>"#;
+
+    let mut unfurler = Unfurler::new();
+    unfurler.set_page_url(Url::parse("https://example.com/cartoons/").unwrap());
+    let unfurled = unfurler.unfurl(html);
+
+    let metadata = &unfurled.metadata;
+    assert_eq!(metadata.title.as_deref(), Some("Tom & Jerry"));
+    assert_eq!(metadata.description.as_deref(), Some("A cartoon"));
+    assert_eq!(
+      metadata.url.as_deref(),
+      Some("https://example.com/tom-and-jerry")
+    );
+    assert_eq!(image_url(metadata), Some("https://example.com/favicon.ico"));
+    assert_eq!(unfurled.source_of("og:title"), Some("title"));
+    assert_eq!(
+      unfurled.source_of("og:description"),
+      Some("meta_description")
+    );
+    assert_eq!(unfurled.source_of("og:url"), Some("canonical"));
+    assert_eq!(unfurled.source_of("og:image"), Some("icon"));
+
+    let layers = unfurler.layers(html);
+    let (source, body_image) = &layers[7];
+    assert_eq!(*source, Source::BodyImage);
+    assert_eq!(
+      image_url(body_image),
+      Some("https://example.com/cartoons/poster.jpg")
+    );
+    assert_eq!(body_image.images[0].alt.as_deref(), Some("Poster"));
+
+    unfurler.set_min_image_size(1000);
+    assert!(unfurler.layers(html)[7].1.images.is_empty());
+  }
+
+  #[test]
+  fn prefers_touch_icons() {
+    let html = r#"<link rel="icon" href="/favicon.ico">
+      <link rel="apple-touch-icon" sizes="180x180" href="/touch.png">"#;
+
+    let mut unfurler = Unfurler::new();
+    unfurler.set_page_url(Url::parse("https://example.com/").unwrap());
+    let metadata = unfurler.unfurl(html).metadata;
+    assert_eq!(image_url(&metadata), Some("https://example.com/touch.png"));
+    assert_eq!(metadata.images[0].width, Some(180));
+
+    for source in Source::ALL {
+      assert_eq!(Source::from_str(source.as_str()).unwrap(), source);
+    }
+    assert!(Source::from_str("guess").is_err());
+  }
+}
@@ -68,20 +68,47 @@ use crate::{error::Error, Result};
 pub fn validate_http_url(url: &str) -> Result<Url> {
   match Url::from_str(url) {
     Err(err) => Err(Error::UrlParseError(err.to_string())),
-    Ok(url) => {
-      let scheme = url.scheme();
-      if scheme == "http" || scheme == "https" {
-        return Ok(url);
-      }
+    Ok(url) => validate_http_scheme(url),
+  }
+}
 
-      Err(Error::InvalidHttpUrlScheme(scheme.into()))
+/// Parses an HTTP(S) URL, resolving relative (`/img/cover.png`) and
+/// protocol-relative (`//cdn.example.com/x.jpg`) URLs against `base`.
+///
+/// Absolute URLs are validated as is. Without a `base`, relative URLs
+/// are rejected just like in `validate_http_url`.
+pub fn resolve_http_url(url: &str, base: Option<&Url>) -> Result<Url> {
+  match (Url::from_str(url), base) {
+    (Err(url::ParseError::RelativeUrlWithoutBase), Some(base)) => {
+      match base.join(url) {
+        Err(err) => Err(Error::UrlParseError(err.to_string())),
+        Ok(url) => validate_http_scheme(url),
+      }
     }
+    _ => validate_http_url(url),
   }
 }
 
+fn validate_http_scheme(url: Url) -> Result<Url> {
+  let scheme = url.scheme();
+  if scheme == "http" || scheme == "https" {
+    return Ok(url);
+  }
+
+  Err(Error::InvalidHttpUrlScheme(scheme.into()))
+}
+
 /// A constant array containing the allowed image file extensions.
-pub const ALLOWED_MEDIA_FILE_EXT: [&str; 5] =
-  ["png", "jpg", "jpeg", "gif", "webp"];
+pub const ALLOWED_MEDIA_FILE_EXT: [&str; 6] =
+  ["png", "jpg", "jpeg", "gif", "webp", "avif"];
+
+/// Returns the lowercased file extension of the last path segment of `url`,
+/// e.g. `png` for `https://example.com/cover.PNG?size=2`.
+pub fn url_file_ext(url: &Url) -> Option<String> {
+  let filename = url.path_segments()?.next_back()?;
+  let (_, ext) = filename.rsplit_once('.')?;
+  (!ext.is_empty()).then(|| ext.to_lowercase())
+}
 
 /// Checks if a given filename has a valid image extension based on a predefined set of allowed extensions.
 ///
@@ -103,6 +130,52 @@ pub fn is_valid_image_ext(filename: &str) -> bool {
   ALLOWED_MEDIA_FILE_EXT.contains(&ext.as_str())
 }
 
+/// Escapes a string for use in HTML text and attribute values.
+pub fn escape_html(value: &str) -> String {
+  let mut escaped = String::with_capacity(value.len());
+  for c in value.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&#39;"),
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+/// Returns the candidate closest to `value` by edit distance, ignoring case.
+pub fn closest_match<'a>(
+  value: &str,
+  candidates: &[&'a str],
+) -> Option<&'a str> {
+  let value = value.to_lowercase();
+  candidates
+    .iter()
+    .min_by_key(|candidate| edit_distance(&value, candidate))
+    .copied()
+}
+
+/// The Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+  let b: Vec<char> = b.chars().collect();
+  let mut row: Vec<usize> = (0..=b.len()).collect();
+
+  for (i, ca) in a.chars().enumerate() {
+    let mut previous = row[0];
+    row[0] = i + 1;
+    for (j, cb) in b.iter().enumerate() {
+      let substitution = previous + usize::from(ca != *cb);
+      previous = row[j + 1];
+      row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+    }
+  }
+
+  row[b.len()]
+}
+
 /// Check if a given string value represents a valid locale
 /// in the format `language_TERRITORY` (e.g "en_US").
 ///
@@ -153,6 +226,16 @@ fn is_valid_locale_format(locale: &str) -> bool {
 mod tests {
   use super::*;
 
+  #[test]
+  fn finds_closest_match() {
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    assert_eq!(
+      closest_match("Artcle", &["book", "article", "profile"]),
+      Some("article")
+    );
+    assert_eq!(closest_match("x", &[]), None);
+  }
+
   // region    validate_site_url
   #[test]
   fn valid_http_url() {
@@ -185,6 +268,38 @@ mod tests {
   }
   // endregion validate_site_url
 
+  // region    resolve_http_url
+  #[test]
+  fn resolve_relative_url() {
+    let base = Url::parse("https://example.com/blog/post.html").unwrap();
+    let url = resolve_http_url("/img/cover.png", Some(&base)).unwrap();
+    assert_eq!(url.as_str(), "https://example.com/img/cover.png");
+
+    let url = resolve_http_url("cover.png", Some(&base)).unwrap();
+    assert_eq!(url.as_str(), "https://example.com/blog/cover.png");
+  }
+
+  #[test]
+  fn resolve_protocol_relative_url() {
+    let base = Url::parse("https://example.com").unwrap();
+    let url = resolve_http_url("//cdn.example.com/x.jpg", Some(&base)).unwrap();
+    assert_eq!(url.as_str(), "https://cdn.example.com/x.jpg");
+  }
+
+  #[test]
+  fn resolve_absolute_url_ignores_base() {
+    let base = Url::parse("https://example.com").unwrap();
+    let url = resolve_http_url("http://other.com/a.png", Some(&base)).unwrap();
+    assert_eq!(url.as_str(), "http://other.com/a.png");
+    assert!(resolve_http_url("ftp://other.com/a.png", Some(&base)).is_err());
+  }
+
+  #[test]
+  fn resolve_relative_url_without_base() {
+    assert!(resolve_http_url("/img/cover.png", None).is_err());
+  }
+  // endregion resolve_http_url
+
   // region    is_valid_image_ext
   #[test]
   fn valid_image_extension() {
@@ -210,6 +325,15 @@ mod tests {
     assert!(!is_valid_image_ext(filename));
   }
 
+  #[test]
+  fn url_file_extension() {
+    let url = Url::parse("https://example.com/a/cover.PNG?size=2").unwrap();
+    assert_eq!(url_file_ext(&url).as_deref(), Some("png"));
+
+    let url = Url::parse("https://example.com/v1.2/photo").unwrap();
+    assert_eq!(url_file_ext(&url), None);
+  }
+
   #[test]
   fn case_sensitive_extension() {
     let filename = "image.JPG";
@@ -217,6 +341,16 @@ mod tests {
   }
   // endregion is_valid_image_extension
 
+  // region    escape_html
+  #[test]
+  fn escapes_html() {
+    assert_eq!(
+      escape_html(r#"<a href="x">Tom & Jerry's</a>"#),
+      "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+    );
+  }
+  // endregion escape_html
+
   // region    validate_locale
   #[test]
   fn valid_locale() {
@@ -45,6 +45,7 @@
 //! - `Validatable`: A trait for general validation.
 //! - `DimensionsValidator`: A trait for validating dimensions, including width and height.
 //! - `SecureURLValidator`: A trait for validating secure URLs.
+//! - `MediaTypeValidator`: A trait for validating declared media (MIME) types.
 //!
 //! ## Validatable
 //!
@@ -56,7 +57,14 @@
 //!
 //! ## SecureURLValidator
 //!
-//! The `SecureURLValidator` trait provides methods for retrieving a secure URL and a `validate_secure_url` method to ensure the URL uses the "https" scheme.
+//! The `SecureURLValidator` trait provides methods for retrieving a secure URL and a `validate_secure_url` method to ensure the URL uses the "https" scheme
+//! and points at the same resource as the regular URL. The `validate_https_only` method additionally requires the regular URL to use "https",
+//! for sites that are configured as HTTPS-only.
+//!
+//! ## MediaTypeValidator
+//!
+//! The `MediaTypeValidator` trait provides methods for retrieving the declared media type and URL of the data, and a `validate_media_type` method
+//! to ensure the media type is allowed for the kind of media and matches the URL's file extension.
 //!
 //! # Usage
 //!
@@ -91,7 +99,12 @@
 //! ```
 //!
 
-use crate::{error::Error, Result};
+use crate::{
+  error::Error,
+  metadata::{is_media_ext, MediaKind, MediaType},
+  utils::{is_valid_image_ext, url_file_ext},
+  Result,
+};
 
 /// A trait for general validation.
 pub trait Validatable {
@@ -124,19 +137,150 @@ pub trait SecureURLValidator {
   /// Retrieves the secure URL of the data.
   fn secure_url(&self) -> Option<url::Url>;
 
-  /// Validates that the URL uses the "https" scheme.
+  /// Retrieves the URL the secure URL is an alternate for.
+  fn url(&self) -> Option<url::Url> {
+    None
+  }
+
+  /// Validates that the secure URL uses the "https" scheme and points at
+  /// the same resource (same path) as the URL.
   fn validate_secure_url(&self) -> Result<()> {
     match self.secure_url() {
       None => Ok(()),
-      Some(url) => {
-        let scheme = url.scheme();
-        if scheme == "https" {
-          return Ok(());
+      Some(secure_url) => {
+        let scheme = secure_url.scheme();
+        if scheme != "https" {
+          return Err(Error::InvalidHttpsUrlScheme(scheme.into()));
+        }
+
+        match self.url() {
+          Some(url) if url.path() != secure_url.path() => {
+            Err(Error::SecureUrlMismatch(url.into(), secure_url.into()))
+          }
+          _ => Ok(()),
         }
+      }
+    }
+  }
+
+  /// Validates that both the URL and the secure URL use the "https" scheme.
+  ///
+  /// Use this when the site is configured as HTTPS-only.
+  fn validate_https_only(&self) -> Result<()> {
+    if let Some(url) = self.url() {
+      let scheme = url.scheme();
+      if scheme != "https" {
+        return Err(Error::InvalidHttpsUrlScheme(scheme.into()));
+      }
+    }
+
+    self.validate_secure_url()
+  }
+}
+
+/// A trait for validating declared media (MIME) types.
+pub trait MediaTypeValidator {
+  /// Retrieves the kind of media, which determines the allowed media types.
+  fn media_kind(&self) -> MediaKind;
+
+  /// Retrieves the declared media type of the data.
+  fn media_type(&self) -> Option<MediaType>;
+
+  /// Retrieves the URL of the media.
+  fn media_url(&self) -> Option<url::Url>;
+
+  /// Infers the media type from the file extension of the media URL.
+  fn inferred_media_type(&self) -> Option<MediaType> {
+    let ext = url_file_ext(&self.media_url()?)?;
+    self.media_kind().media_type_for_ext(&ext)
+  }
 
-        let err = Error::InvalidHttpsUrlScheme(scheme.into());
-        Err(err)
+  /// Validates that the declared media type is allowed for the kind of media
+  /// and matches the file extension of the media URL.
+  ///
+  /// Image URLs with the file extension of other media, e.g. `bmp` or
+  /// `mp4`, are rejected. URLs without such an extension, e.g. `og.php` for
+  /// generated images, are not checked.
+  fn validate_media_type(&self) -> Result<()> {
+    let kind = self.media_kind();
+    let ext = self.media_url().as_ref().and_then(url_file_ext);
+
+    if let (MediaKind::Image, Some(ext)) = (kind, ext.as_ref()) {
+      if is_media_ext(ext) && !is_valid_image_ext(ext) {
+        return Err(Error::InvalidImageExtension(ext.into()));
+      }
+    }
+
+    let Some(declared) = self.media_type() else {
+      return Ok(());
+    };
+
+    if !kind.allows(&declared) {
+      return Err(Error::UnsupportedMediaType(
+        declared.into(),
+        kind.allowed_types().join("|"),
+      ));
+    }
+
+    match (ext, self.inferred_media_type()) {
+      (Some(ext), Some(inferred)) if inferred != declared => {
+        Err(Error::MediaTypeMismatch(declared.into(), ext))
       }
+      _ => Ok(()),
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr;
+
+  use super::*;
+  use crate::metadata::{Audio, Image, Video};
+
+  fn url(s: &str) -> Option<url::Url> {
+    Some(url::Url::from_str(s).unwrap())
+  }
+
+  #[test]
+  fn secure_url_must_use_https() {
+    let image = Image {
+      url: url("http://example.com/ogp.jpg"),
+      secure_url: url("http://example.com/ogp.jpg"),
+      ..Default::default()
+    };
+    assert!(matches!(
+      image.validate(),
+      Err(Error::InvalidHttpsUrlScheme(_))
+    ));
+  }
+
+  #[test]
+  fn secure_url_must_match_url() {
+    let mut video = Video {
+      url: url("http://example.com/movie.swf"),
+      secure_url: url("https://secure.example.com/movie.swf"),
+      ..Default::default()
+    };
+    assert!(video.validate().is_ok());
+
+    video.secure_url = url("https://secure.example.com/other.swf");
+    assert!(matches!(
+      video.validate(),
+      Err(Error::SecureUrlMismatch(_, _))
+    ));
+  }
+
+  #[test]
+  fn https_only_requires_https_url() {
+    let mut audio = Audio {
+      url: url("http://example.com/sound.mp3"),
+      ..Default::default()
+    };
+    assert!(audio.validate().is_ok());
+    assert!(audio.validate_https_only().is_err());
+
+    audio.url = url("https://example.com/sound.mp3");
+    assert!(audio.validate_https_only().is_ok());
+  }
+}